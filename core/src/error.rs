@@ -8,9 +8,24 @@ pub enum ValidationError {
     /// Failed to resolve the schema because an invalid URI was provided for
     /// `$ref` field.
     ///
-    /// Currently, we only support `#/{definitions,parameters}/Name` in `$ref` field.
-    #[error("Invalid $ref URI {:?}. Only relative URIs are supported.", _0)]
+    /// Currently, we only support `#/{definitions,parameters,responses}/Name`
+    /// (or the corresponding `#/components/...` in v3) in `$ref` field.
+    #[error(
+        "Invalid $ref URI {:?}. Only references to objects in the spec are supported.",
+        _0
+    )]
     InvalidRefURI(String),
+    /// The `$ref` field refers to an object in another document (given along with the
+    /// reference). The resolver doesn't load documents, so such references should be
+    /// bundled into the spec before resolving (`paperclip::v2::from_path` and
+    /// `paperclip::bundle::load` do this).
+    #[error(
+        "$ref {:?} refers to another document ({:?}). Load the spec with `from_path` \
+         (or `bundle::load`) for resolving such references.",
+        _1,
+        _0
+    )]
+    ExternalReference(String, String),
     /// The specified reference is missing in the spec.
     #[error("Reference missing in spec: {}", _0)]
     MissingReference(String),
@@ -35,4 +50,76 @@ pub enum ValidationError {
         _3
     )]
     InvalidParameterType(String, String, Option<DataType>, ParameterIn),
+    /// Keys in responses must be HTTP status codes (or `default`).
+    #[error(
+        "Invalid status code {:?} for response (expected a status code or \"default\")",
+        _0
+    )]
+    InvalidStatusCode(String),
+    /// The path doesn't have any operations.
+    #[error("Path {:?} doesn't have any operations", _0)]
+    MissingOperations(String),
+    /// Path parameter doesn't exist in the path template.
+    #[error("Path parameter {:?} is not in the template of path {:?}", _0, _1)]
+    UnknownPathParameter(String, String),
+    /// File parameters need multipart (or urlencoded) forms.
+    #[error(
+        "File parameter {:?} in path {:?} requires \"multipart/form-data\" or \"application/x-www-form-urlencoded\" in consumes",
+        _0,
+        _1
+    )]
+    InvalidFileParameterConsumes(String, String),
+    /// Operation IDs must be unique across the spec.
+    #[error("Operation ID {:?} is not unique (it's also used at {})", _0, _1)]
+    DuplicateOperationId(String, String),
+    /// Security requirement refers to a scheme which is not defined.
+    #[error("Security scheme {:?} is not defined in securityDefinitions", _0)]
+    UndefinedSecurityScheme(String),
+    /// Security requirement refers to a scope which is not defined by its scheme.
+    #[error("Scope {:?} is not defined for security scheme {:?}", _0, _1)]
+    UndefinedSecurityScope(String, String),
+    /// Security scheme lacks a field required by its type (or OAuth2 flow).
+    #[error("Security scheme {:?} requires {:?}", _0, _1)]
+    MissingSecuritySchemeField(String, &'static str),
+    /// Error at some node in the spec (identified by its JSON pointer).
+    #[error("{} (at {})", _1, _0)]
+    Located(String, Box<ValidationError>),
+}
+
+impl ValidationError {
+    /// Error for the given (unsupported) `$ref` value.
+    pub fn invalid_ref(reference: &str) -> Self {
+        match reference.find('#') {
+            Some(0) => ValidationError::InvalidRefURI(reference.into()),
+            idx => ValidationError::ExternalReference(
+                reference[..idx.unwrap_or(reference.len())].into(),
+                reference.into(),
+            ),
+        }
+    }
+
+    /// Sets the JSON pointer to the node where this error occurred
+    /// (unless it's already set).
+    pub fn at<P: Into<String>>(self, pointer: P) -> Self {
+        match self {
+            ValidationError::Located(..) => self,
+            e => ValidationError::Located(pointer.into(), Box::new(e)),
+        }
+    }
+
+    /// JSON pointer to the node where this error occurred (if known).
+    pub fn pointer(&self) -> Option<&str> {
+        match self {
+            ValidationError::Located(p, _) => Some(p),
+            _ => None,
+        }
+    }
+
+    /// The actual error (without its location).
+    pub fn inner(&self) -> &ValidationError {
+        match self {
+            ValidationError::Located(_, e) => e,
+            e => e,
+        }
+    }
 }
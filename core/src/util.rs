@@ -21,3 +21,35 @@ impl<T> Future for Ready<T> {
 pub fn ready<T>(t: T) -> Ready<T> {
     Ready(Some(t))
 }
+
+/// Appends the given key (escaped as per RFC 6901) to the JSON pointer.
+pub fn join_pointer(pointer: &str, key: &str) -> String {
+    format!("{}/{}", pointer, key.replace('~', "~0").replace('/', "~1"))
+}
+
+/// Checks whether the string matches the glob pattern (where `*` matches
+/// any sequence of characters).
+pub fn glob_match(pattern: &str, s: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+    let mut rest = match s.strip_prefix(first) {
+        Some(r) => r,
+        None => return false,
+    };
+
+    let parts = parts.collect::<Vec<_>>();
+    let (last, middle) = match parts.split_last() {
+        Some(p) => p,
+        // No wildcards.
+        None => return rest.is_empty(),
+    };
+
+    for part in middle {
+        match rest.find(part) {
+            Some(i) => rest = &rest[i + part.len()..],
+            None => return false,
+        }
+    }
+
+    rest.ends_with(last)
+}
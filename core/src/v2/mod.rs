@@ -31,6 +31,10 @@ impl<S: Schema + Default> ResolvableApi<S> {
     /// This walks recursively, collects the referenced schema objects,
     /// substitutes the referenced IDs with the pointer to schema objects
     /// and returns the resolved object or an error if it encountered one.
+    ///
+    /// References to other documents (say, `models.yaml#/User`) aren't loaded,
+    /// so they should be bundled into the spec beforehand (`paperclip::v2::from_path`
+    /// and `paperclip::bundle::load` do this).
     pub fn resolve(self) -> Result<ResolvableApi<S>, ValidationError> {
         let mut resolver = Resolver::from((
             self.definitions,
@@ -58,6 +62,7 @@ impl<S: Schema + Default> ResolvableApi<S> {
             security: self.security,
             security_definitions: self.security_definitions,
             tags: self.tags,
+            extensions: self.extensions,
         })
     }
 }
@@ -14,20 +14,26 @@ use regex::{Captures, Regex};
 use actix_web::http::Method;
 
 use parking_lot::RwLock;
+use serde::de::{Deserialize, Deserializer, Error as _, IgnoredAny, IntoDeserializer, MapAccess};
+use serde::ser::{Serialize, Serializer};
 use std::borrow::Cow;
 use std::collections::{BTreeMap, BTreeSet};
 use std::fmt::{self, Display};
+use std::marker::PhantomData;
 use std::ops::{Deref, DerefMut};
 use std::sync::Arc;
 
 /// Regex that can be used for fetching templated path parameters.
-static PATH_TEMPLATE_REGEX: Lazy<Regex> =
+pub(crate) static PATH_TEMPLATE_REGEX: Lazy<Regex> =
     Lazy::new(|| Regex::new(r"\{(.*?)\}").expect("path template regex"));
 
 // Headers that have special meaning in OpenAPI. These cannot be used in header parameter.
 // Ensure that they're all lowercase for case insensitive check.
 const SPECIAL_HEADERS: &[&str] = &["content-type", "accept", "authorization"];
 
+/// Byte order mark for UTF-8 encoded specs.
+pub const UTF8_BOM: &[u8] = b"\xEF\xBB\xBF";
+
 /// OpenAPI version.
 #[derive(Clone, Copy, Debug, Serialize, Deserialize, Eq, PartialEq)]
 pub enum Version {
@@ -114,7 +120,7 @@ pub struct Api<P, R, S> {
     )]
     pub security_definitions: BTreeMap<String, SecurityScheme>,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
-    pub security: Vec<BTreeMap<String, BTreeSet<String>>>,
+    pub security: Vec<SecurityRequirement>,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub tags: Vec<Tag>,
     #[serde(rename = "externalDocs", skip_serializing_if = "Option::is_none")]
@@ -159,6 +165,9 @@ pub struct Api<P, R, S> {
     #[serde(skip)]
     pub spec_format: SpecFormat,
     pub info: Info,
+    /// Vendor extensions (`x-` fields).
+    #[serde(flatten, deserialize_with = "deserialize_extensions")]
+    pub extensions: Extensions,
 }
 
 /// The format used by spec (JSON/YAML).
@@ -184,6 +193,26 @@ impl SpecFormat {
             SpecFormat::Yaml => &*YAML_MIME,
         }
     }
+
+    /// Guesses the format from the contents of the spec. JSON specs begin
+    /// with `{` (after the byte order mark and whitespaces, if any), and
+    /// everything else is assumed to be YAML.
+    pub fn detect(contents: &[u8]) -> Self {
+        let contents = contents.strip_prefix(UTF8_BOM).unwrap_or(contents);
+        match contents.iter().find(|b| !b.is_ascii_whitespace()) {
+            Some(b'{') => SpecFormat::Json,
+            _ => SpecFormat::Yaml,
+        }
+    }
+
+    /// Returns the format for the given file extension (if it's known).
+    pub fn from_extension(ext: &str) -> Option<Self> {
+        match ext.to_ascii_lowercase().as_str() {
+            "json" => Some(SpecFormat::Json),
+            "yaml" | "yml" => Some(SpecFormat::Yaml),
+            _ => None,
+        }
+    }
 }
 
 impl<P, R, S> Api<P, R, S> {
@@ -206,6 +235,48 @@ use crate as paperclip; // hack for proc macro
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct DefaultSchema;
 
+/// Discriminator of a schema (for polymorphism).
+///
+/// This is the name of the property in v2 specs, whereas v3 specs use an object
+/// with the property name and an (optional) mapping of values to schemas.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Discriminator {
+    Property(String),
+    Object {
+        #[serde(rename = "propertyName")]
+        property_name: String,
+        #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+        mapping: BTreeMap<String, String>,
+    },
+}
+
+impl Discriminator {
+    /// Name of the property which holds the discriminator value.
+    pub fn property_name(&self) -> &str {
+        match self {
+            Discriminator::Property(p) => p,
+            Discriminator::Object { property_name, .. } => property_name,
+        }
+    }
+
+    /// Returns the discriminator value for the definition with the given name.
+    /// This is the name itself, unless it's mapped to some other value.
+    pub fn value_for<'a>(&'a self, name: &'a str) -> &'a str {
+        if let Discriminator::Object { mapping, .. } = self {
+            let suffix = format!("/{}", name);
+            if let Some((value, _)) = mapping.iter().find(|(_, r)| r.ends_with(&suffix)) {
+                return value;
+            }
+        }
+
+        name
+    }
+}
+
+/// Vendor extensions (`x-` fields) of an object.
+pub type Extensions = BTreeMap<String, serde_json::Value>;
+
 /// Info object.
 ///
 /// https://github.com/OAI/OpenAPI-Specification/blob/master/versions/2.0.md#infoObject
@@ -219,6 +290,9 @@ pub struct Info {
     pub contact: Option<Contact>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub license: Option<License>,
+    /// Vendor extensions (`x-` fields).
+    #[serde(flatten, deserialize_with = "deserialize_extensions")]
+    pub extensions: Extensions,
 }
 
 /// Contact object.
@@ -232,6 +306,9 @@ pub struct Contact {
     pub url: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub email: Option<String>,
+    /// Vendor extensions (`x-` fields).
+    #[serde(flatten, deserialize_with = "deserialize_extensions")]
+    pub extensions: Extensions,
 }
 
 /// License object.
@@ -243,21 +320,30 @@ pub struct License {
     pub name: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub url: Option<String>,
+    /// Vendor extensions (`x-` fields).
+    #[serde(flatten, deserialize_with = "deserialize_extensions")]
+    pub extensions: Extensions,
 }
 
+/// Security Requirement object (names of security schemes and the scopes
+/// required from them).
+///
+/// https://github.com/OAI/OpenAPI-Specification/blob/master/versions/2.0.md#security-requirement-object
+pub type SecurityRequirement = BTreeMap<String, Vec<String>>;
+
 /// Security Scheme object.
 ///
 /// https://github.com/OAI/OpenAPI-Specification/blob/master/versions/2.0.md#security-scheme-object
-#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct SecurityScheme {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub name: Option<String>,
     #[serde(rename = "type")]
-    pub type_: String,
+    pub type_: SecuritySchemeType,
     #[serde(rename = "in", skip_serializing_if = "Option::is_none")]
-    pub in_: Option<String>,
+    pub in_: Option<ApiKeyIn>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub flow: Option<String>,
+    pub flow: Option<OAuth2Flow>,
     #[serde(rename = "authorizationUrl", skip_serializing_if = "Option::is_none")]
     pub auth_url: Option<String>,
     #[serde(rename = "tokenUrl", skip_serializing_if = "Option::is_none")]
@@ -266,22 +352,81 @@ pub struct SecurityScheme {
     pub scopes: BTreeMap<String, String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
+    /// Vendor extensions (`x-` fields).
+    #[serde(flatten, deserialize_with = "deserialize_extensions")]
+    pub extensions: Extensions,
 }
 
 impl SecurityScheme {
+    /// Creates a scheme of the given type (without any other fields).
+    pub fn new(type_: SecuritySchemeType) -> Self {
+        SecurityScheme {
+            name: None,
+            type_,
+            in_: None,
+            flow: None,
+            auth_url: None,
+            token_url: None,
+            scopes: BTreeMap::new(),
+            description: None,
+            extensions: Extensions::new(),
+        }
+    }
+
+    /// Checks whether the fields required by the type (and flow) of this
+    /// scheme are present.
+    pub fn check(&self, name: &str) -> Result<(), ValidationError> {
+        let missing = |field: &'static str| {
+            Err(ValidationError::MissingSecuritySchemeField(
+                name.into(),
+                field,
+            ))
+        };
+
+        match self.type_ {
+            SecuritySchemeType::Basic => (),
+            SecuritySchemeType::ApiKey => {
+                if self.name.is_none() {
+                    return missing("name");
+                }
+
+                if self.in_.is_none() {
+                    return missing("in");
+                }
+            }
+            SecuritySchemeType::OAuth2 => {
+                let flow = match self.flow {
+                    Some(f) => f,
+                    None => return missing("flow"),
+                };
+
+                if flow.needs_auth_url() && self.auth_url.is_none() {
+                    return missing("authorizationUrl");
+                }
+
+                if flow.needs_token_url() && self.token_url.is_none() {
+                    return missing("tokenUrl");
+                }
+            }
+        }
+
+        Ok(())
+    }
+
     /// Adds or updates this definition to the map of security definitions.
     pub fn update_definitions(mut self, name: &str, map: &mut BTreeMap<String, SecurityScheme>) {
         if let Some(existing) = map.get_mut(name) {
             existing.name = existing.name.take().or(self.name);
-            if !self.type_.is_empty() {
-                existing.type_ = self.type_;
-            }
+            existing.type_ = self.type_;
             existing.in_ = existing.in_.take().or(self.in_);
             existing.flow = existing.flow.take().or(self.flow);
             existing.auth_url = existing.auth_url.take().or(self.auth_url);
             existing.token_url = existing.token_url.take().or(self.token_url);
             existing.scopes.append(&mut self.scopes);
             existing.description = existing.description.take().or(self.description);
+            for (key, value) in self.extensions {
+                existing.extensions.entry(key).or_insert(value);
+            }
             return;
         }
 
@@ -299,6 +444,47 @@ impl SecurityScheme {
     }
 }
 
+/// Type of a security scheme.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, Eq, PartialEq, Ord, PartialOrd)]
+pub enum SecuritySchemeType {
+    #[serde(rename = "basic")]
+    Basic,
+    #[serde(rename = "apiKey")]
+    ApiKey,
+    #[serde(rename = "oauth2")]
+    OAuth2,
+}
+
+/// Location of the API key.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, Eq, PartialEq, Ord, PartialOrd)]
+#[serde(rename_all = "lowercase")]
+pub enum ApiKeyIn {
+    Query,
+    Header,
+}
+
+/// OAuth2 flow used by a security scheme.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, Eq, PartialEq, Ord, PartialOrd)]
+#[serde(rename_all = "camelCase")]
+pub enum OAuth2Flow {
+    Implicit,
+    Password,
+    Application,
+    AccessCode,
+}
+
+impl OAuth2Flow {
+    /// Whether this flow requires `authorizationUrl`.
+    pub fn needs_auth_url(self) -> bool {
+        matches!(self, OAuth2Flow::Implicit | OAuth2Flow::AccessCode)
+    }
+
+    /// Whether this flow requires `tokenUrl`.
+    pub fn needs_token_url(self) -> bool {
+        !matches!(self, OAuth2Flow::Implicit)
+    }
+}
+
 /// Tag object.
 ///
 /// https://github.com/OAI/OpenAPI-Specification/blob/master/versions/2.0.md#tag-object
@@ -310,6 +496,9 @@ pub struct Tag {
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(rename = "externalDocs")]
     pub external_docs: Option<ExternalDocs>,
+    /// Vendor extensions (`x-` fields).
+    #[serde(flatten, deserialize_with = "deserialize_extensions")]
+    pub extensions: Extensions,
 }
 
 /// External Documentation object.
@@ -320,6 +509,26 @@ pub struct ExternalDocs {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
     pub url: String,
+    /// Vendor extensions (`x-` fields).
+    #[serde(flatten, deserialize_with = "deserialize_extensions")]
+    pub extensions: Extensions,
+}
+
+/// XML object.
+///
+/// https://github.com/OAI/OpenAPI-Specification/blob/master/versions/2.0.md#xmlObject
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct Xml {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub namespace: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prefix: Option<String>,
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub attribute: bool,
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub wrapped: bool,
 }
 
 /// Path item that can be traversed and resolved for codegen.
@@ -333,10 +542,22 @@ pub type DefaultPathItemRaw = PathItem<DefaultParameterRaw, DefaultResponseRaw>;
 /// https://github.com/OAI/OpenAPI-Specification/blob/master/versions/2.0.md#pathItemObject
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct PathItem<P, R> {
-    #[serde(flatten, default = "BTreeMap::default")]
+    #[serde(
+        flatten,
+        default = "BTreeMap::default",
+        deserialize_with = "deserialize_methods",
+        serialize_with = "serialize_methods",
+        bound(
+            serialize = "P: Serialize, R: Serialize",
+            deserialize = "P: Deserialize<'de>, R: Deserialize<'de>"
+        )
+    )]
     pub methods: BTreeMap<HttpMethod, Operation<P, R>>,
     #[serde(default = "Vec::default", skip_serializing_if = "Vec::is_empty")]
     pub parameters: Vec<Either<Reference, P>>,
+    /// Vendor extensions (`x-` fields).
+    #[serde(flatten, deserialize_with = "deserialize_extensions")]
+    pub extensions: Extensions,
 }
 
 impl<S> PathItem<Parameter<S>, Response<S>> {
@@ -424,11 +645,11 @@ pub struct Parameter<S> {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub default: Option<serde_json::Value>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub maximum: Option<f32>,
+    pub maximum: Option<f64>,
     #[serde(rename = "exclusiveMaximum", skip_serializing_if = "Option::is_none")]
     pub exclusive_maximum: Option<bool>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub minimum: Option<f32>,
+    pub minimum: Option<f64>,
     #[serde(rename = "exclusiveMinimum", skip_serializing_if = "Option::is_none")]
     pub exclusive_minimum: Option<bool>,
     #[serde(rename = "maxLength", skip_serializing_if = "Option::is_none")]
@@ -444,9 +665,12 @@ pub struct Parameter<S> {
     #[serde(default, rename = "uniqueItems", skip_serializing_if = "is_false")]
     pub unique_items: bool,
     #[serde(rename = "multipleOf", skip_serializing_if = "Option::is_none")]
-    pub multiple_of: Option<f32>,
+    pub multiple_of: Option<f64>,
     #[serde(default, rename = "enum", skip_serializing_if = "Vec::is_empty")]
     pub enum_: Vec<serde_json::Value>,
+    /// Vendor extensions (`x-` fields).
+    #[serde(flatten, deserialize_with = "deserialize_extensions")]
+    pub extensions: Extensions,
 }
 
 /// Items object.
@@ -463,14 +687,16 @@ pub struct Items {
     pub items: Option<Box<Items>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub collection_format: Option<CollectionFormat>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default: Option<serde_json::Value>,
     #[serde(default, rename = "enum", skip_serializing_if = "Vec::is_empty")]
     pub enum_: Vec<serde_json::Value>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub maximum: Option<f32>,
+    pub maximum: Option<f64>,
     #[serde(rename = "exclusiveMaximum", skip_serializing_if = "Option::is_none")]
     pub exclusive_maximum: Option<bool>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub minimum: Option<f32>,
+    pub minimum: Option<f64>,
     #[serde(rename = "exclusiveMinimum", skip_serializing_if = "Option::is_none")]
     pub exclusive_minimum: Option<bool>,
     #[serde(rename = "maxLength", skip_serializing_if = "Option::is_none")]
@@ -486,7 +712,7 @@ pub struct Items {
     #[serde(rename = "uniqueItems", skip_serializing_if = "Option::is_none")]
     pub unique_items: Option<bool>,
     #[serde(rename = "multipleOf", skip_serializing_if = "Option::is_none")]
-    pub multiple_of: Option<f32>,
+    pub multiple_of: Option<f64>,
 }
 
 impl<S> Parameter<Resolvable<S>>
@@ -547,7 +773,7 @@ where
             }
             // If "file" is specified, then it must be `formData` parameter.
             Some(DataType::File) => {
-                // FIXME: Check against `consumes` and `produces` fields.
+                // NOTE: `consumes` is checked by the validation pass (`paperclip::v2::validate`).
                 if self.in_ != ParameterIn::FormData {
                     is_invalid = true;
                 }
@@ -615,11 +841,13 @@ pub struct Operation<P, R> {
     pub consumes: Option<BTreeSet<MediaRange>>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub produces: Option<BTreeSet<MediaRange>>,
-    #[serde(default, skip_serializing_if = "Vec::is_empty")]
-    pub security: Vec<BTreeMap<String, Vec<String>>>,
+    // *NOTE:* Same goes for security requirements - an empty list
+    // removes the global requirements for this operation.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub security: Option<Vec<SecurityRequirement>>,
     #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
     pub schemes: BTreeSet<OperationProtocol>,
-    // FIXME: Validate using `http::status::StatusCode::from_u16`
+    // NOTE: Status codes are checked by the validation pass (`paperclip::v2::validate`).
     pub responses: BTreeMap<String, Either<Reference, R>>,
     #[serde(default = "Vec::default", skip_serializing_if = "Vec::is_empty")]
     pub parameters: Vec<Either<Reference, P>>,
@@ -627,6 +855,11 @@ pub struct Operation<P, R> {
     pub deprecated: bool,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub tags: Vec<String>,
+    #[serde(rename = "externalDocs", skip_serializing_if = "Option::is_none")]
+    pub external_docs: Option<ExternalDocs>,
+    /// Vendor extensions (`x-` fields).
+    #[serde(flatten, deserialize_with = "deserialize_extensions")]
+    pub extensions: Extensions,
 }
 
 impl<S> Operation<Parameter<S>, Response<S>> {
@@ -690,6 +923,12 @@ pub struct Response<S> {
     pub schema: Option<S>,
     #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
     pub headers: BTreeMap<String, Header>,
+    /// Examples of the response (by their media types).
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub examples: BTreeMap<MediaRange, serde_json::Value>,
+    /// Vendor extensions (`x-` fields).
+    #[serde(flatten, deserialize_with = "deserialize_extensions")]
+    pub extensions: Extensions,
 }
 
 /// Header object.
@@ -712,11 +951,11 @@ pub struct Header {
     #[serde(default, rename = "enum", skip_serializing_if = "Vec::is_empty")]
     pub enum_: Vec<serde_json::Value>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub maximum: Option<f32>,
+    pub maximum: Option<f64>,
     #[serde(rename = "exclusiveMaximum", skip_serializing_if = "Option::is_none")]
     pub exclusive_maximum: Option<bool>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub minimum: Option<f32>,
+    pub minimum: Option<f64>,
     #[serde(rename = "exclusiveMinimum", skip_serializing_if = "Option::is_none")]
     pub exclusive_minimum: Option<bool>,
     #[serde(rename = "maxLength", skip_serializing_if = "Option::is_none")]
@@ -732,7 +971,14 @@ pub struct Header {
     #[serde(rename = "uniqueItems", skip_serializing_if = "Option::is_none")]
     pub unique_items: Option<bool>,
     #[serde(rename = "multipleOf", skip_serializing_if = "Option::is_none")]
-    pub multiple_of: Option<f32>,
+    pub multiple_of: Option<f64>,
+    /// **NOTE:** This isn't in the v2 spec, but it's commonly used (and it's
+    /// carried over to the header when converting to v3).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub example: Option<serde_json::Value>,
+    /// Vendor extensions (`x-` fields).
+    #[serde(flatten, deserialize_with = "deserialize_extensions")]
+    pub extensions: Extensions,
 }
 
 /// The HTTP method used for an operation.
@@ -746,6 +992,9 @@ pub enum HttpMethod {
     Options,
     Head,
     Patch,
+    /// **NOTE:** This is only valid in OpenAPI v3. It's rejected while
+    /// parsing v2 specs and skipped while serializing them.
+    Trace,
 }
 
 impl HttpMethod {
@@ -826,6 +1075,15 @@ where
             Resolvable::Resolved { ref old, .. } => old.read().description().map(String::from),
         }
     }
+
+    /// Fetch the example for this schema (like the description, this is
+    /// the example of the field/parameter and not the referenced object).
+    pub fn get_example(&self) -> Option<serde_json::Value> {
+        match *self {
+            Resolvable::Raw(ref s) => s.read().example().cloned(),
+            Resolvable::Resolved { ref old, .. } => old.read().example().cloned(),
+        }
+    }
 }
 
 /* Common trait impls */
@@ -846,6 +1104,7 @@ impl From<&Method> for HttpMethod {
             "OPTIONS" => HttpMethod::Options,
             "HEAD" => HttpMethod::Head,
             "PATCH" => HttpMethod::Patch,
+            "TRACE" => HttpMethod::Trace,
             _ => HttpMethod::Get,
         }
     }
@@ -946,3 +1205,108 @@ impl Default for ParameterIn {
 fn is_false(val: &bool) -> bool {
     !*val
 }
+
+/// Deserializes the operations in a v2 path item (skipping `x-` extensions).
+fn deserialize_methods<'de, D, O>(deserializer: D) -> Result<BTreeMap<HttpMethod, O>, D::Error>
+where
+    D: Deserializer<'de>,
+    O: Deserialize<'de>,
+{
+    deserializer.deserialize_map(MethodsVisitor(false, PhantomData))
+}
+
+/// Deserializes the operations in a v3 path item (which can also have `trace`).
+pub(crate) fn deserialize_v3_methods<'de, D, O>(
+    deserializer: D,
+) -> Result<BTreeMap<HttpMethod, O>, D::Error>
+where
+    D: Deserializer<'de>,
+    O: Deserialize<'de>,
+{
+    deserializer.deserialize_map(MethodsVisitor(true, PhantomData))
+}
+
+/// Serializes the operations in a v2 path item (skipping `trace`).
+fn serialize_methods<S, O>(
+    methods: &BTreeMap<HttpMethod, O>,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+    O: Serialize,
+{
+    serializer.collect_map(methods.iter().filter(|(m, _)| **m != HttpMethod::Trace))
+}
+
+/// Visitor for operations in path items (whether `trace` is allowed, and operation type).
+struct MethodsVisitor<O>(bool, PhantomData<O>);
+
+impl<'de, O> serde::de::Visitor<'de> for MethodsVisitor<O>
+where
+    O: Deserialize<'de>,
+{
+    type Value = BTreeMap<HttpMethod, O>;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a map of operations")
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let mut methods = BTreeMap::new();
+        while let Some(key) = map.next_key::<String>()? {
+            if key.starts_with("x-") {
+                map.next_value::<IgnoredAny>()?;
+                continue;
+            }
+
+            let method = HttpMethod::deserialize(key.into_deserializer())?;
+            if method == HttpMethod::Trace && !self.0 {
+                return Err(A::Error::custom(
+                    "`trace` operations are only allowed in OpenAPI v3",
+                ));
+            }
+
+            methods.insert(method, map.next_value()?);
+        }
+
+        Ok(methods)
+    }
+}
+
+/// Deserializes the `x-` extensions of an object (skipping other fields).
+/// This is used for the flattened `extensions` fields in models and schemas.
+pub fn deserialize_extensions<'de, D>(deserializer: D) -> Result<Extensions, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    struct Visitor;
+
+    impl<'de> serde::de::Visitor<'de> for Visitor {
+        type Value = Extensions;
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            f.write_str("a map of extensions")
+        }
+
+        fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+        where
+            A: MapAccess<'de>,
+        {
+            let mut extensions = Extensions::new();
+            while let Some(key) = map.next_key::<String>()? {
+                if key.starts_with("x-") {
+                    extensions.insert(key, map.next_value()?);
+                } else {
+                    map.next_value::<IgnoredAny>()?;
+                }
+            }
+
+            Ok(extensions)
+        }
+    }
+
+    deserializer.deserialize_map(Visitor)
+}
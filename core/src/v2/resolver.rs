@@ -6,6 +6,7 @@ use super::{
     Schema,
 };
 use crate::error::ValidationError;
+use crate::util::join_pointer;
 use heck::CamelCase;
 
 use std::cell::RefCell;
@@ -38,6 +39,8 @@ pub(crate) struct Resolver<S> {
     pub params: ParametersMap<S>,
     /// Globally defined responses;
     pub resp: ResponsesMap<S>,
+    /// JSON pointers to the anonymous definitions added to `defs`.
+    anon_defs: BTreeMap<String, String>,
 }
 
 impl<S>
@@ -62,6 +65,7 @@ impl<S>
             paths,
             params,
             resp,
+            anon_defs: BTreeMap::new(),
         }
     }
 }
@@ -89,7 +93,7 @@ where
 
         for (name, schema) in &self.defs {
             log::trace!("Entering: {}", name);
-            self.resolve_definitions_no_root_ref(schema)?;
+            self.resolve_definitions_no_root_ref(schema, &self.definition_pointer(name))?;
 
             for def in self.cyclic_defs.borrow_mut().drain(..) {
                 log::debug!(
@@ -100,6 +104,28 @@ where
             }
         }
 
+        // Subtypes of definitions with discriminators are emitted inside their
+        // parents, so parents reachable from their subtypes are also cyclic.
+        for (name, schema) in &self.defs {
+            if schema.read().discriminator().is_none() {
+                continue;
+            }
+
+            let cyclic = self.defs.values().any(|s| {
+                let s = s.read();
+                let is_subtype = s
+                    .all_of()
+                    .unwrap_or_default()
+                    .iter()
+                    .any(|p| p.read().name() == Some(name));
+                is_subtype && reaches(&*s, name, &mut vec![])
+            });
+            if cyclic {
+                log::debug!("Cyclic definition detected through subtypes: {:?}", name);
+                schema.write().set_cyclic(true);
+            }
+        }
+
         Ok(())
     }
 
@@ -108,6 +134,7 @@ where
     fn resolve_definitions_no_root_ref(
         &self,
         schema: &Resolvable<S>,
+        pointer: &str,
     ) -> Result<(), ValidationError> {
         let mut schema = match schema.try_write() {
             Some(s) => s,
@@ -117,14 +144,22 @@ where
             }
         };
 
+        if let Some(schemas) = schema.all_of_mut() {
+            let all_of_pointer = join_pointer(pointer, "allOf");
+            schemas.iter_mut().enumerate().try_for_each(|(i, s)| {
+                self.resolve_definitions(s, &join_pointer(&all_of_pointer, &i.to_string()))
+            })?;
+        }
+
         if let Some(inner) = schema.items_mut().take() {
-            return self.resolve_definitions(inner);
+            return self.resolve_definitions(inner, &join_pointer(pointer, "items"));
         }
 
         if let Some(props) = schema.properties_mut().take() {
+            let props_pointer = join_pointer(pointer, "properties");
             props.iter_mut().try_for_each(|(k, s)| {
                 log::trace!("Resolving property {:?}", k);
-                self.resolve_definitions(s)
+                self.resolve_definitions(s, &join_pointer(&props_pointer, k))
             })?;
         }
 
@@ -133,7 +168,7 @@ where
             .take()
             .and_then(|s| s.right_mut())
         {
-            self.resolve_definitions(props)?;
+            self.resolve_definitions(props, &join_pointer(pointer, "additionalProperties"))?;
         }
 
         Ok(())
@@ -141,7 +176,11 @@ where
 
     /// Resolve the given definition. If it contains a reference, find and assign it,
     /// otherwise traverse further.
-    fn resolve_definitions(&self, schema: &mut Resolvable<S>) -> Result<(), ValidationError> {
+    fn resolve_definitions(
+        &self,
+        schema: &mut Resolvable<S>,
+        pointer: &str,
+    ) -> Result<(), ValidationError> {
         let ref_def = {
             let s = match schema.try_read() {
                 Some(s) => s,
@@ -153,23 +192,41 @@ where
 
             if let Some(ref_name) = s.reference() {
                 log::trace!("Resolving definition {}", ref_name);
-                Some(self.resolve_definition_reference(ref_name)?)
+                let def = self
+                    .resolve_definition_reference(ref_name)
+                    .map_err(|e| e.at(pointer))?;
+                let name = &ref_name[DEF_REF_PREFIX.len()..];
+                Some((def, self.definition_pointer(name)))
             } else {
                 None
             }
         };
 
-        if let Some(new) = ref_def {
-            *schema = match schema {
-                Resolvable::Raw(old) => Resolvable::Resolved {
-                    old: old.clone(),
-                    new: (&*new).clone(),
-                },
-                _ => unimplemented!("schema already resolved?"),
-            };
-        }
+        // If this refers to some definition, then we're traversing that definition.
+        let pointer = match ref_def {
+            Some((new, def_pointer)) => {
+                *schema = match schema {
+                    Resolvable::Raw(old) => Resolvable::Resolved {
+                        old: old.clone(),
+                        new: (&*new).clone(),
+                    },
+                    _ => unimplemented!("schema already resolved?"),
+                };
+
+                def_pointer
+            }
+            None => pointer.into(),
+        };
+
+        self.resolve_definitions_no_root_ref(&*schema, &pointer)
+    }
 
-        self.resolve_definitions_no_root_ref(&*schema)
+    /// Returns the JSON pointer to the definition with the given name.
+    fn definition_pointer(&self, name: &str) -> String {
+        self.anon_defs
+            .get(name)
+            .cloned()
+            .unwrap_or_else(|| join_pointer("/definitions", name))
     }
 
     /// Resolve a given operation.
@@ -178,12 +235,26 @@ where
         path: &str,
         map: &mut ResolvablePathItem<S>,
     ) -> Result<(), ValidationError> {
+        let path_pointer = join_pointer("/paths", path);
         for (&method, op) in &mut map.methods {
-            self.resolve_parameters(Some(method), path, &mut op.parameters)?;
-            for resp in op.responses.values_mut() {
+            let op_pointer = join_pointer(&path_pointer, &method.to_string().to_lowercase());
+            self.resolve_parameters(
+                Some(method),
+                path,
+                &mut op.parameters,
+                &join_pointer(&op_pointer, "parameters"),
+            )?;
+
+            let responses_pointer = join_pointer(&op_pointer, "responses");
+            for (code, resp) in op.responses.iter_mut() {
+                let mut resp_pointer = join_pointer(&responses_pointer, code);
                 let ref_resp = if let Some(r) = resp.left() {
                     log::trace!("Resolving response {}", r.reference);
-                    Some(self.resolve_response_reference(&r.reference)?)
+                    let new = self
+                        .resolve_response_reference(&r.reference)
+                        .map_err(|e| e.at(&resp_pointer))?;
+                    resp_pointer = r.reference.trim_start_matches('#').into();
+                    Some(new)
                 } else {
                     None
                 };
@@ -198,11 +269,17 @@ where
                     Some(method),
                     path,
                     "Response",
+                    &join_pointer(&resp_pointer, "schema"),
                 )?;
             }
         }
 
-        self.resolve_parameters(None, path, &mut map.parameters)
+        self.resolve_parameters(
+            None,
+            path,
+            &mut map.parameters,
+            &join_pointer(&path_pointer, "parameters"),
+        )
     }
 
     /// Resolve the given bunch of parameters.
@@ -211,11 +288,17 @@ where
         method: Option<HttpMethod>,
         path: &str,
         params: &mut Vec<Either<Reference, ResolvableParameter<S>>>,
+        pointer: &str,
     ) -> Result<(), ValidationError> {
-        for p in params.iter_mut() {
+        for (i, p) in params.iter_mut().enumerate() {
+            let mut param_pointer = format!("{}/{}", pointer, i);
             let ref_param = if let Some(r) = p.left() {
                 log::trace!("Resolving parameter {}", r.reference);
-                Some(self.resolve_parameter_reference(&r.reference)?)
+                let new = self
+                    .resolve_parameter_reference(&r.reference)
+                    .map_err(|e| e.at(&param_pointer))?;
+                param_pointer = r.reference.trim_start_matches('#').into();
+                Some(new)
             } else {
                 None
             };
@@ -225,7 +308,13 @@ where
             }
 
             let mut param = p.write();
-            self.resolve_operation_schema(&mut param.schema, method, path, "Body")?;
+            self.resolve_operation_schema(
+                &mut param.schema,
+                method,
+                path,
+                "Body",
+                &join_pointer(&param_pointer, "schema"),
+            )?;
         }
 
         Ok(())
@@ -238,6 +327,7 @@ where
         method: Option<HttpMethod>,
         path: &str,
         suffix: &str,
+        pointer: &str,
     ) -> Result<(), ValidationError> {
         let schema = match s.as_mut() {
             Some(s) => s,
@@ -253,19 +343,20 @@ where
                 let mut ref_schema = S::default();
                 ref_schema.set_reference(format!("{}{}", DEF_REF_PREFIX, def_name));
                 let old_schema = mem::replace(schema, ref_schema.into());
+                self.anon_defs.insert(def_name.clone(), pointer.into());
                 self.defs.insert(def_name, old_schema);
             }
             _ => (),
         }
 
-        self.resolve_definitions(schema)?;
+        self.resolve_definitions(schema, pointer)?;
         Ok(())
     }
 
     /// Given a name (from `$ref` field), get a reference to the definition.
     fn resolve_definition_reference(&self, name: &str) -> Result<Resolvable<S>, ValidationError> {
         if !name.starts_with(DEF_REF_PREFIX) {
-            return Err(ValidationError::InvalidRefURI(name.into()));
+            return Err(ValidationError::invalid_ref(name));
         }
 
         let name = &name[DEF_REF_PREFIX.len()..];
@@ -282,7 +373,7 @@ where
         name: &str,
     ) -> Result<ResolvableParameter<S>, ValidationError> {
         if !name.starts_with(PARAM_REF_PREFIX) {
-            return Err(ValidationError::InvalidRefURI(name.into()));
+            return Err(ValidationError::invalid_ref(name));
         }
 
         let name = &name[PARAM_REF_PREFIX.len()..];
@@ -299,7 +390,7 @@ where
         name: &str,
     ) -> Result<ResolvableResponse<S>, ValidationError> {
         if !name.starts_with(RESP_REF_PREFIX) {
-            return Err(ValidationError::InvalidRefURI(name.into()));
+            return Err(ValidationError::invalid_ref(name));
         }
 
         let name = &name[RESP_REF_PREFIX.len()..];
@@ -310,3 +401,46 @@ where
         Ok(resp.clone())
     }
 }
+
+/// Checks whether the given (resolved) schema has the definition with the given
+/// name somewhere in its properties. Properties of composed schemas (`allOf`) are
+/// included, but not the composed schemas themselves.
+fn reaches<S: Schema>(schema: &S, name: &str, visited: &mut Vec<String>) -> bool {
+    let mut children = vec![];
+    children.extend(schema.properties().into_iter().flat_map(|p| p.values()));
+    children.extend(schema.items());
+    if let Some(Either::Right(s)) = schema.additional_properties() {
+        children.push(s);
+    }
+
+    for child in children {
+        let child = child.read();
+        match child.name() {
+            Some(n) if n == name => return true,
+            Some(n) if visited.iter().any(|v| v == n) => continue,
+            Some(n) => visited.push(n.into()),
+            None => (),
+        }
+
+        if reaches(&*child, name, visited) {
+            return true;
+        }
+    }
+
+    for composed in schema.all_of().unwrap_or_default() {
+        let composed = composed.read();
+        if let Some(n) = composed.name() {
+            if visited.iter().any(|v| v == n) {
+                continue;
+            }
+
+            visited.push(n.into());
+        }
+
+        if reaches(&*composed, name, visited) {
+            return true;
+        }
+    }
+
+    false
+}
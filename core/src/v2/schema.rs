@@ -1,8 +1,8 @@
 //! Traits used for code and spec generation.
 
 use super::models::{
-    DataType, DataTypeFormat, DefaultOperationRaw, DefaultSchemaRaw, Either, Resolvable,
-    SecurityScheme,
+    DataType, DataTypeFormat, DefaultOperationRaw, DefaultSchemaRaw, Discriminator, Either,
+    ExternalDocs, Resolvable, SecurityScheme, Xml,
 };
 
 use std::collections::{BTreeMap, BTreeSet};
@@ -44,6 +44,59 @@ pub trait Schema: Sized {
     /// Mutable access to `properties` field.
     fn properties_mut(&mut self) -> Option<&mut BTreeMap<String, Resolvable<Self>>>;
 
+    /// Schemas composed into this schema, if any (`allOf` field).
+    fn all_of(&self) -> Option<&[Resolvable<Self>]>;
+
+    /// Mutable access to the `allOf` field, if it's non-empty.
+    fn all_of_mut(&mut self) -> Option<&mut [Resolvable<Self>]>;
+
+    /// Discriminator for the subtypes of this schema, if any (`discriminator` field).
+    fn discriminator(&self) -> Option<&Discriminator>;
+
+    /// Example for this schema, if any (`example` field).
+    fn example(&self) -> Option<&serde_json::Value>;
+
+    /// Default value for this schema, if any (`default` field).
+    fn default_value(&self) -> Option<&serde_json::Value>;
+
+    /// Maximum value (`maximum` field), along with whether it's exclusive
+    /// (`exclusiveMaximum` field).
+    fn maximum(&self) -> Option<(f64, bool)>;
+
+    /// Minimum value (`minimum` field), along with whether it's exclusive
+    /// (`exclusiveMinimum` field).
+    fn minimum(&self) -> Option<(f64, bool)>;
+
+    /// Number which the values should be multiples of, if any (`multipleOf` field).
+    fn multiple_of(&self) -> Option<f64>;
+
+    /// Maximum length of strings, if any (`maxLength` field).
+    fn max_length(&self) -> Option<u32>;
+
+    /// Minimum length of strings, if any (`minLength` field).
+    fn min_length(&self) -> Option<u32>;
+
+    /// Regex for strings, if any (`pattern` field).
+    fn pattern(&self) -> Option<&str>;
+
+    /// Maximum number of array items, if any (`maxItems` field).
+    fn max_items(&self) -> Option<u32>;
+
+    /// Minimum number of array items, if any (`minItems` field).
+    fn min_items(&self) -> Option<u32>;
+
+    /// Whether array items should be unique (`uniqueItems` field).
+    fn unique_items(&self) -> bool;
+
+    /// Whether this (property) schema is only sent in responses (`readOnly` field).
+    fn read_only(&self) -> bool;
+
+    /// XML representation of this schema, if any (`xml` field).
+    fn xml(&self) -> Option<&Xml>;
+
+    /// Additional external documentation, if any (`externalDocs` field).
+    fn external_docs(&self) -> Option<&ExternalDocs>;
+
     /// Returns the required properties (if any) for this object.
     fn required_properties(&self) -> Option<&BTreeSet<String>>;
 
@@ -56,7 +109,7 @@ pub trait Schema: Sized {
 
     /// Returns whether this definition "is" or "has" `Any` type.
     fn contains_any(&self) -> bool {
-        _schema_contains_any(self, vec![])
+        _schema_contains_any(self, vec![], false)
     }
 
     /* MARK: Resolver-specific methods. */
@@ -83,8 +136,14 @@ pub trait Schema: Sized {
     fn set_name(&mut self, name: &str);
 }
 
-fn _schema_contains_any<'a, S: Schema>(schema: &'a S, mut nodes: Vec<&'a str>) -> bool {
-    if schema.data_type().is_none() {
+/// Checks whether the schema "is" or "has" `Any` type. Composed schemas
+/// (and the schemas composing them) usually don't specify the type.
+fn _schema_contains_any<'a, S: Schema>(
+    schema: &'a S,
+    mut nodes: Vec<&'a str>,
+    composed: bool,
+) -> bool {
+    if schema.data_type().is_none() && schema.all_of().is_none() && !composed {
         return true;
     }
 
@@ -100,18 +159,25 @@ fn _schema_contains_any<'a, S: Schema>(schema: &'a S, mut nodes: Vec<&'a str>) -
         .properties()
         .map(|t| {
             t.values()
-                .any(|s| _schema_contains_any(&*s.read(), nodes.clone()))
+                .any(|s| _schema_contains_any(&*s.read(), nodes.clone(), false))
         })
         .unwrap_or(false)
         || schema
             .items()
-            .map(|s| _schema_contains_any(&*s.read(), nodes.clone()))
+            .map(|s| _schema_contains_any(&*s.read(), nodes.clone(), false))
             .unwrap_or(false)
         || schema
             .additional_properties()
             .map(|e| match e {
                 Either::Left(extra_props_allowed) => *extra_props_allowed,
-                Either::Right(s) => _schema_contains_any(&*s.read(), nodes),
+                Either::Right(s) => _schema_contains_any(&*s.read(), nodes.clone(), false),
+            })
+            .unwrap_or(false)
+        || schema
+            .all_of()
+            .map(|t| {
+                t.iter()
+                    .any(|s| _schema_contains_any(&*s.read(), nodes.clone(), true))
             })
             .unwrap_or(false)
 }
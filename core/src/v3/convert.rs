@@ -0,0 +1,787 @@
+//! Conversion of OpenAPI v2 (swagger) specs into OpenAPI v3.
+
+use super::models::{
+    Api, Components, DefaultApiRaw, Either, Header, MediaRange, MediaType, OAuthFlow, OAuthFlows,
+    Operation, Parameter, ParameterIn, ParameterStyle, PathItem, Reference, RequestBody,
+    Resolvable, ResolvableApi, Response, SecurityScheme, Server, Version, JSON_MIME,
+};
+use crate::v2::models::{
+    self as v2, ApiKeyIn, CollectionFormat, DataType, DataTypeFormat, DefaultSchemaRaw,
+    Discriminator, OAuth2Flow, SecuritySchemeType,
+};
+use crate::v2::schema::Schema;
+use parking_lot::RwLock;
+use serde::{de::DeserializeOwned, Serialize};
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::Arc;
+
+const V2_DEFINITIONS: &str = "#/definitions/";
+const V2_PARAMETERS: &str = "#/parameters/";
+const V2_RESPONSES: &str = "#/responses/";
+const V3_SCHEMAS: &str = "#/components/schemas/";
+const V3_PARAMETERS: &str = "#/components/parameters/";
+const V3_REQUEST_BODIES: &str = "#/components/requestBodies/";
+const V3_RESPONSES: &str = "#/components/responses/";
+
+/// Media type used for form parameters when none of the `consumes`
+/// media ranges is suitable for forms.
+const URL_ENCODED_FORM: &str = "application/x-www-form-urlencoded";
+const MULTIPART_FORM: &str = "multipart/form-data";
+
+impl From<v2::DefaultApiRaw> for DefaultApiRaw {
+    /// Converts a v2 spec into an equivalent v3 spec.
+    ///
+    /// - `host`, `basePath` and `schemes` are combined into `servers`.
+    /// - `definitions`, global `parameters`, `responses` and `securityDefinitions`
+    ///   are moved to `components` (and references to them are updated).
+    /// - `body` and `formData` parameters become request bodies, with one
+    ///   media type for each (operation or global) `consumes` media range.
+    /// - Response schemas are added to content for each `produces` media range.
+    fn from(api: v2::DefaultApiRaw) -> Self {
+        convert(api)
+    }
+}
+
+impl<S> From<v2::ResolvableApi<S>> for ResolvableApi<S>
+where
+    S: Schema + Serialize + DeserializeOwned,
+{
+    /// Converts an **unresolved** v2 spec into an equivalent v3 spec. This
+    /// is the same as the conversion for `DefaultApiRaw`, but custom fields
+    /// in schemas are preserved.
+    ///
+    /// **NOTE:** Schemas created for non-body parameters are deserialized
+    /// from raw schemas, so custom fields in schemas must be optional.
+    fn from(api: v2::ResolvableApi<S>) -> Self {
+        convert(api)
+    }
+}
+
+/// Schema which can be converted from v2 to v3.
+trait ConvertSchema: Clone {
+    /// Updates the references to definitions and the `file` types
+    /// (recursively) in this schema.
+    fn upgrade(&mut self);
+
+    /// Creates this schema from the given raw schema.
+    fn from_raw(schema: DefaultSchemaRaw) -> Self;
+}
+
+impl ConvertSchema for DefaultSchemaRaw {
+    fn upgrade(&mut self) {
+        if let Some(r) = self.reference.as_mut() {
+            *r = r.replacen(V2_DEFINITIONS, V3_SCHEMAS, 1);
+        }
+
+        if self.data_type == Some(DataType::File) {
+            self.data_type = Some(DataType::String);
+            self.format = Some(DataTypeFormat::Binary);
+        }
+
+        if let Some(Discriminator::Property(p)) = self.discriminator.take() {
+            self.discriminator = Some(Discriminator::Object {
+                property_name: p,
+                mapping: BTreeMap::new(),
+            });
+        }
+
+        for s in self.properties.values_mut() {
+            s.upgrade();
+        }
+
+        if let Some(s) = self.items.as_mut() {
+            s.upgrade();
+        }
+
+        if let Some(Either::Right(s)) = self.extra_props.as_mut() {
+            s.upgrade();
+        }
+
+        for s in &mut self.all_of {
+            s.upgrade();
+        }
+    }
+
+    fn from_raw(schema: DefaultSchemaRaw) -> Self {
+        schema
+    }
+}
+
+impl<S> ConvertSchema for Resolvable<S>
+where
+    S: Schema + Serialize + DeserializeOwned,
+{
+    fn upgrade(&mut self) {
+        let mut schema = self.write();
+        if let Some(r) = schema.reference() {
+            let r = r.replacen(V2_DEFINITIONS, V3_SCHEMAS, 1);
+            schema.set_reference(r);
+        }
+
+        // `file` type doesn't exist in v3 (and discriminators are objects), and
+        // the `Schema` trait doesn't have setters for those, so we go through serde.
+        let is_file = schema.data_type() == Some(DataType::File);
+        let discriminator = match schema.discriminator() {
+            Some(Discriminator::Property(p)) => Some(p.clone()),
+            _ => None,
+        };
+        if is_file || discriminator.is_some() {
+            let mut value = serde_json::to_value(&*schema).expect("serializing schema");
+            if is_file {
+                value["type"] = "string".into();
+                value["format"] = "binary".into();
+            }
+
+            if let Some(p) = discriminator {
+                value["discriminator"] = serde_json::json!({ "propertyName": p });
+            }
+
+            *schema = serde_json::from_value(value).expect("deserializing schema");
+        }
+
+        if let Some(props) = schema.properties_mut() {
+            for s in props.values_mut() {
+                s.upgrade();
+            }
+        }
+
+        if let Some(s) = schema.items_mut() {
+            s.upgrade();
+        }
+
+        if let Some(Either::Right(s)) = schema.additional_properties_mut() {
+            s.upgrade();
+        }
+
+        if let Some(schemas) = schema.all_of_mut() {
+            for s in schemas {
+                s.upgrade();
+            }
+        }
+    }
+
+    fn from_raw(schema: DefaultSchemaRaw) -> Self {
+        let value = serde_json::to_value(schema).expect("serializing raw schema");
+        Resolvable::from(serde_json::from_value::<S>(value).expect("deserializing raw schema"))
+    }
+}
+
+/// Parameters and responses which are (or aren't) shared for resolution.
+trait Shared<T> {
+    fn from_inner(inner: T) -> Self;
+
+    fn into_inner(self) -> T;
+}
+
+macro_rules! impl_shared {
+    ($ty:ident) => {
+        impl<S> Shared<$ty<S>> for $ty<S> {
+            fn from_inner(inner: $ty<S>) -> Self {
+                inner
+            }
+
+            fn into_inner(self) -> $ty<S> {
+                self
+            }
+        }
+
+        impl<S: Clone> Shared<$ty<S>> for Arc<RwLock<$ty<S>>> {
+            fn from_inner(inner: $ty<S>) -> Self {
+                Arc::new(RwLock::new(inner))
+            }
+
+            fn into_inner(self) -> $ty<S> {
+                Arc::try_unwrap(self)
+                    .map(RwLock::into_inner)
+                    .unwrap_or_else(|a| a.read().clone())
+            }
+        }
+    };
+}
+
+use self::v2::{Parameter as V2Parameter, Response as V2Response};
+impl_shared!(V2Parameter);
+impl_shared!(V2Response);
+impl_shared!(Parameter);
+impl_shared!(RequestBody);
+impl_shared!(Response);
+
+fn convert<S, P, R, NP, NB, NR>(api: v2::Api<P, R, S>) -> Api<NP, NB, NR, S>
+where
+    S: ConvertSchema,
+    P: Shared<v2::Parameter<S>>,
+    R: Shared<v2::Response<S>>,
+    NP: Shared<Parameter<S>>,
+    NB: Shared<RequestBody<S>>,
+    NR: Shared<Response<S>>,
+{
+    let mut converter = Converter {
+        consumes: api.consumes,
+        produces: api.produces,
+        body_params: BTreeSet::new(),
+    };
+
+    let mut components = Components::default();
+    for (name, param) in api.parameters {
+        let param = param.into_inner();
+        if param.in_ == v2::ParameterIn::Body {
+            converter.body_params.insert(name.clone());
+            let body = converter.request_body(vec![param], None);
+            components.request_bodies.insert(name, NB::from_inner(body));
+        } else if let Some(p) = converter.parameter(param) {
+            components.parameters.insert(name, NP::from_inner(p));
+        }
+    }
+
+    components.responses = api
+        .responses
+        .into_iter()
+        .map(|(name, resp)| {
+            let resp = converter.response(resp.into_inner(), None);
+            (name, NR::from_inner(resp))
+        })
+        .collect();
+    components.schemas = api
+        .definitions
+        .into_iter()
+        .map(|(name, mut schema)| {
+            schema.upgrade();
+            (name, schema)
+        })
+        .collect();
+    components.security_schemes = api
+        .security_definitions
+        .into_iter()
+        .map(|(name, scheme)| (name, security_scheme(scheme)))
+        .collect();
+
+    let paths = api
+        .paths
+        .into_iter()
+        .map(|(path, item)| (path, converter.path_item(item)))
+        .collect();
+
+    Api {
+        openapi: Version::default(),
+        info: api.info,
+        servers: servers(api.host.as_deref(), api.base_path.as_deref(), &api.schemes),
+        paths,
+        components,
+        security: api.security,
+        tags: api.tags,
+        external_docs: api.external_docs,
+        coders: api.coders,
+        support_crates: api.support_crates,
+        spec_format: api.spec_format,
+        extensions: api.extensions,
+    }
+}
+
+/// Holds the global state of the v2 spec required for converting paths.
+struct Converter {
+    consumes: BTreeSet<MediaRange>,
+    produces: BTreeSet<MediaRange>,
+    /// Names of global parameters which have been turned into request bodies.
+    body_params: BTreeSet<String>,
+}
+
+impl Converter {
+    fn path_item<S, P, R, NP, NB, NR>(&self, item: v2::PathItem<P, R>) -> PathItem<NP, NB, NR>
+    where
+        S: ConvertSchema,
+        P: Shared<v2::Parameter<S>>,
+        R: Shared<v2::Response<S>>,
+        NP: Shared<Parameter<S>>,
+        NB: Shared<RequestBody<S>>,
+        NR: Shared<Response<S>>,
+    {
+        // Body and form parameters can't be specified at the path level
+        // in v3, so we push them down to the operations.
+        let (shared_body, parameters) = self.split_parameters(item.parameters);
+        let methods = item
+            .methods
+            .into_iter()
+            .map(|(method, op)| (method, self.operation(op, &shared_body)))
+            .collect();
+
+        PathItem {
+            summary: None,
+            description: None,
+            methods,
+            servers: vec![],
+            parameters,
+            extensions: item.extensions,
+        }
+    }
+
+    fn operation<S, P, R, NP, NB, NR>(
+        &self,
+        op: v2::Operation<P, R>,
+        shared_body: &[Either<Reference, v2::Parameter<S>>],
+    ) -> Operation<NP, NB, NR>
+    where
+        S: ConvertSchema,
+        P: Shared<v2::Parameter<S>>,
+        R: Shared<v2::Response<S>>,
+        NP: Shared<Parameter<S>>,
+        NB: Shared<RequestBody<S>>,
+        NR: Shared<Response<S>>,
+    {
+        let (mut body, parameters) = self.split_parameters(op.parameters);
+        // Operation-level parameters override the ones in the path.
+        let has_body = body.iter().any(|p| match p {
+            Either::Left(_) => true,
+            Either::Right(p) => p.in_ == v2::ParameterIn::Body,
+        });
+        for p in shared_body {
+            let is_overridden = match p {
+                Either::Right(p) if p.in_ == v2::ParameterIn::FormData => body
+                    .iter()
+                    .filter_map(Either::right)
+                    .any(|b| b.name == p.name && b.in_ == p.in_),
+                _ => has_body,
+            };
+
+            if !is_overridden {
+                body.push(p.clone());
+            }
+        }
+
+        let request_body = match body.first() {
+            Some(Either::Left(r)) => Some(Either::Left(Reference {
+                reference: r.reference.replacen(V2_PARAMETERS, V3_REQUEST_BODIES, 1),
+            })),
+            Some(Either::Right(_)) => {
+                let params = body
+                    .into_iter()
+                    .filter_map(|p| match p {
+                        Either::Right(p) => Some(p),
+                        Either::Left(_) => None,
+                    })
+                    .collect();
+                let body = self.request_body(params, op.consumes.as_ref());
+                Some(Either::Right(NB::from_inner(body)))
+            }
+            None => None,
+        };
+
+        let produces = op.produces;
+        Operation {
+            tags: op.tags,
+            summary: op.summary,
+            description: op.description,
+            external_docs: op.external_docs,
+            operation_id: op.operation_id,
+            parameters,
+            request_body,
+            responses: op
+                .responses
+                .into_iter()
+                .map(|(code, resp)| {
+                    let resp = match resp {
+                        Either::Left(r) => Either::Left(Reference {
+                            reference: r.reference.replacen(V2_RESPONSES, V3_RESPONSES, 1),
+                        }),
+                        Either::Right(r) => {
+                            let r = self.response(r.into_inner(), produces.as_ref());
+                            Either::Right(NR::from_inner(r))
+                        }
+                    };
+
+                    (code, resp)
+                })
+                .collect(),
+            callbacks: BTreeMap::new(),
+            deprecated: op.deprecated,
+            security: op.security,
+            // Operation-level schemes can't be represented without the host,
+            // so the operation uses the global servers.
+            servers: vec![],
+            extensions: op.extensions,
+        }
+    }
+
+    /// Splits the given parameters into body/form parameters (which should
+    /// be converted into a request body) and the remaining parameters.
+    #[allow(clippy::type_complexity)]
+    fn split_parameters<S, P, NP>(
+        &self,
+        params: Vec<Either<Reference, P>>,
+    ) -> (
+        Vec<Either<Reference, v2::Parameter<S>>>,
+        Vec<Either<Reference, NP>>,
+    )
+    where
+        S: ConvertSchema,
+        P: Shared<v2::Parameter<S>>,
+        NP: Shared<Parameter<S>>,
+    {
+        let mut body = vec![];
+        let mut rest = vec![];
+        for param in params {
+            let p = match param {
+                Either::Left(r) => {
+                    let name = r.reference.trim_start_matches(V2_PARAMETERS);
+                    if self.body_params.contains(name) {
+                        body.push(Either::Left(r));
+                    } else {
+                        rest.push(Either::Left(Reference {
+                            reference: r.reference.replacen(V2_PARAMETERS, V3_PARAMETERS, 1),
+                        }));
+                    }
+
+                    continue;
+                }
+                Either::Right(p) => p.into_inner(),
+            };
+
+            if p.in_ == v2::ParameterIn::Body || p.in_ == v2::ParameterIn::FormData {
+                body.push(Either::Right(p));
+            } else if let Some(p) = self.parameter(p) {
+                rest.push(Either::Right(NP::from_inner(p)));
+            }
+        }
+
+        (body, rest)
+    }
+
+    /// Converts non-body parameters.
+    fn parameter<S: ConvertSchema>(&self, param: v2::Parameter<S>) -> Option<Parameter<S>> {
+        let in_ = match param.in_ {
+            v2::ParameterIn::Query => ParameterIn::Query,
+            v2::ParameterIn::Header => ParameterIn::Header,
+            v2::ParameterIn::Path => ParameterIn::Path,
+            v2::ParameterIn::Body | v2::ParameterIn::FormData => return None,
+        };
+
+        let (style, explode) = match (param.collection_format, in_) {
+            (None, _) => (None, None),
+            (Some(CollectionFormat::Multi), _) => (Some(ParameterStyle::Form), Some(true)),
+            (Some(CollectionFormat::Ssv), _) => (Some(ParameterStyle::SpaceDelimited), None),
+            (Some(CollectionFormat::Pipes), _) => (Some(ParameterStyle::PipeDelimited), None),
+            // There's no equivalent for TSV in v3, so we fallback to the default (CSV).
+            (Some(_), ParameterIn::Query) => (Some(ParameterStyle::Form), Some(false)),
+            (Some(_), _) => (Some(ParameterStyle::Simple), Some(false)),
+        };
+
+        let schema = simple_schema(parameter_items(&param));
+        Some(Parameter {
+            name: param.name,
+            in_,
+            description: param.description,
+            required: param.required,
+            deprecated: false,
+            allow_empty_value: param.allow_empty_value,
+            style,
+            explode,
+            allow_reserved: false,
+            schema: Some(S::from_raw(schema)),
+            example: None,
+            examples: BTreeMap::new(),
+            content: BTreeMap::new(),
+            extensions: param.extensions,
+        })
+    }
+
+    /// Converts the given body parameter (or form parameters) into a request body.
+    fn request_body<S: ConvertSchema>(
+        &self,
+        params: Vec<v2::Parameter<S>>,
+        consumes: Option<&BTreeSet<MediaRange>>,
+    ) -> RequestBody<S> {
+        let consumes = consumes.unwrap_or(&self.consumes);
+        let mut body = RequestBody {
+            description: None,
+            content: BTreeMap::new(),
+            required: false,
+            extensions: BTreeMap::new(),
+        };
+
+        let (schema, media_types) = match params.iter().position(|p| p.in_ == v2::ParameterIn::Body)
+        {
+            Some(idx) => {
+                let param = &params[idx];
+                body.description = param.description.clone();
+                body.required = param.required;
+                body.extensions = param.extensions.clone();
+                let mut schema = param
+                    .schema
+                    .clone()
+                    .unwrap_or_else(|| S::from_raw(DefaultSchemaRaw::default()));
+                schema.upgrade();
+                (schema, media_types_or_json(consumes))
+            }
+            None => {
+                let mut schema = DefaultSchemaRaw {
+                    data_type: Some(DataType::Object),
+                    ..Default::default()
+                };
+
+                let mut has_file = false;
+                for param in params {
+                    has_file |= param.data_type == Some(DataType::File);
+                    body.required |= param.required;
+                    if param.required {
+                        schema.required.insert(param.name.clone());
+                    }
+
+                    let mut prop = simple_schema(parameter_items(&param));
+                    prop.description = param.description;
+                    prop.extensions = param.extensions;
+                    schema.properties.insert(param.name, Box::new(prop));
+                }
+
+                let forms = consumes
+                    .iter()
+                    .filter(|m| is_form(m))
+                    .cloned()
+                    .collect::<Vec<_>>();
+                let media_types = if !forms.is_empty() {
+                    forms
+                } else if has_file {
+                    vec![media_range(MULTIPART_FORM)]
+                } else {
+                    vec![media_range(URL_ENCODED_FORM)]
+                };
+
+                (S::from_raw(schema), media_types)
+            }
+        };
+
+        body.content = media_types
+            .into_iter()
+            .map(|m| (m, media_type(schema.clone())))
+            .collect();
+        body
+    }
+
+    fn response<S: ConvertSchema>(
+        &self,
+        resp: v2::Response<S>,
+        produces: Option<&BTreeSet<MediaRange>>,
+    ) -> Response<S> {
+        let examples = resp.examples;
+        let content = match resp.schema {
+            Some(mut schema) => {
+                schema.upgrade();
+                media_types_or_json(produces.unwrap_or(&self.produces))
+                    .into_iter()
+                    .map(|m| {
+                        let mut media = media_type(schema.clone());
+                        media.example = examples.get(&m).cloned();
+                        (m, media)
+                    })
+                    .collect()
+            }
+            None => BTreeMap::new(),
+        };
+
+        Response {
+            description: resp.description.unwrap_or_default(),
+            headers: resp
+                .headers
+                .into_iter()
+                .map(|(name, h)| (name, Either::Right(header(h))))
+                .collect(),
+            content,
+            links: BTreeMap::new(),
+            extensions: resp.extensions,
+        }
+    }
+}
+
+/// Builds the servers from the host, base path and schemes of a v2 spec.
+fn servers(
+    host: Option<&str>,
+    base_path: Option<&str>,
+    schemes: &BTreeSet<v2::OperationProtocol>,
+) -> Vec<Server> {
+    let base_path = base_path.unwrap_or("");
+    match host {
+        Some(host) if schemes.is_empty() => vec![Server {
+            // Scheme defaults to that of the spec, so we use a
+            // protocol-relative URL.
+            url: format!("//{}{}", host, base_path),
+            ..Default::default()
+        }],
+        Some(host) => schemes
+            .iter()
+            .map(|s| Server {
+                url: format!("{}://{}{}", scheme_name(*s), host, base_path),
+                ..Default::default()
+            })
+            .collect(),
+        None if base_path.is_empty() || base_path == "/" => vec![],
+        None => vec![Server {
+            url: base_path.into(),
+            ..Default::default()
+        }],
+    }
+}
+
+fn scheme_name(scheme: v2::OperationProtocol) -> &'static str {
+    match scheme {
+        v2::OperationProtocol::Http => "http",
+        v2::OperationProtocol::Https => "https",
+        v2::OperationProtocol::Ws => "ws",
+        v2::OperationProtocol::Wss => "wss",
+    }
+}
+
+fn security_scheme(scheme: v2::SecurityScheme) -> SecurityScheme {
+    let mut new = SecurityScheme {
+        description: scheme.description,
+        extensions: scheme.extensions,
+        ..Default::default()
+    };
+
+    match scheme.type_ {
+        SecuritySchemeType::Basic => {
+            new.type_ = "http".into();
+            new.scheme = Some("basic".into());
+        }
+        SecuritySchemeType::OAuth2 => {
+            new.type_ = "oauth2".into();
+            let flow = OAuthFlow {
+                authorization_url: scheme.auth_url,
+                token_url: scheme.token_url,
+                refresh_url: None,
+                scopes: scheme.scopes,
+            };
+
+            let mut flows = OAuthFlows::default();
+            match scheme.flow {
+                Some(OAuth2Flow::Implicit) => flows.implicit = Some(flow),
+                Some(OAuth2Flow::Password) => flows.password = Some(flow),
+                Some(OAuth2Flow::Application) => flows.client_credentials = Some(flow),
+                _ => flows.authorization_code = Some(flow),
+            }
+
+            new.flows = Some(flows);
+        }
+        SecuritySchemeType::ApiKey => {
+            new.type_ = "apiKey".into();
+            new.name = scheme.name;
+            new.in_ = scheme.in_.map(|i| match i {
+                ApiKeyIn::Query => "query".into(),
+                ApiKeyIn::Header => "header".into(),
+            });
+        }
+    }
+
+    new
+}
+
+fn header<S: ConvertSchema>(header: v2::Header) -> Header<S> {
+    let schema = simple_schema(v2::Items {
+        data_type: header.data_type,
+        format: header.format,
+        items: header.items.map(Box::new),
+        collection_format: None,
+        default: header.default,
+        enum_: header.enum_,
+        maximum: header.maximum,
+        exclusive_maximum: header.exclusive_maximum,
+        minimum: header.minimum,
+        exclusive_minimum: header.exclusive_minimum,
+        max_length: header.max_length,
+        min_length: header.min_length,
+        pattern: header.pattern,
+        max_items: header.max_items,
+        min_items: header.min_items,
+        unique_items: header.unique_items,
+        multiple_of: header.multiple_of,
+    });
+    Header {
+        description: header.description,
+        required: false,
+        deprecated: false,
+        style: header.collection_format.map(|_| ParameterStyle::Simple),
+        explode: None,
+        schema: Some(S::from_raw(schema)),
+        example: header.example,
+        examples: BTreeMap::new(),
+        content: BTreeMap::new(),
+        extensions: header.extensions,
+    }
+}
+
+fn media_type<S>(schema: S) -> MediaType<S> {
+    MediaType {
+        schema: Some(schema),
+        example: None,
+        examples: BTreeMap::new(),
+        encoding: BTreeMap::new(),
+    }
+}
+
+/// Returns the type information and constraints of a non-body parameter.
+fn parameter_items<S>(param: &v2::Parameter<S>) -> v2::Items {
+    v2::Items {
+        data_type: param.data_type,
+        format: param.format.clone(),
+        items: param.items.clone().map(Box::new),
+        collection_format: None,
+        default: param.default.clone(),
+        enum_: param.enum_.clone(),
+        maximum: param.maximum,
+        exclusive_maximum: param.exclusive_maximum,
+        minimum: param.minimum,
+        exclusive_minimum: param.exclusive_minimum,
+        max_length: param.max_length,
+        min_length: param.min_length,
+        pattern: param.pattern.clone(),
+        max_items: param.max_items,
+        min_items: param.min_items,
+        unique_items: Some(true).filter(|_| param.unique_items),
+        multiple_of: param.multiple_of,
+    }
+}
+
+/// Builds a schema from the type information and constraints of a non-body
+/// parameter (or items/header).
+fn simple_schema(items: v2::Items) -> DefaultSchemaRaw {
+    let (data_type, format) = match items.data_type {
+        // `file` type doesn't exist in v3.
+        Some(DataType::File) => (Some(DataType::String), Some(DataTypeFormat::Binary)),
+        ty => (ty, items.format),
+    };
+
+    DefaultSchemaRaw {
+        data_type,
+        format,
+        items: items.items.map(|i| Box::new(simple_schema(*i))),
+        default: items.default,
+        enum_: items.enum_,
+        maximum: items.maximum,
+        exclusive_maximum: items.exclusive_maximum,
+        minimum: items.minimum,
+        exclusive_minimum: items.exclusive_minimum,
+        max_length: items.max_length,
+        min_length: items.min_length,
+        pattern: items.pattern,
+        max_items: items.max_items,
+        min_items: items.min_items,
+        unique_items: items.unique_items,
+        multiple_of: items.multiple_of,
+        ..Default::default()
+    }
+}
+
+fn media_range(s: &str) -> MediaRange {
+    MediaRange(s.parse().expect("invalid media range"))
+}
+
+fn is_form(m: &MediaRange) -> bool {
+    m.0.essence_str() == URL_ENCODED_FORM || m.0.essence_str() == MULTIPART_FORM
+}
+
+/// Returns the given media ranges (or JSON if there aren't any).
+fn media_types_or_json(media: &BTreeSet<MediaRange>) -> Vec<MediaRange> {
+    if media.is_empty() {
+        vec![JSON_MIME.clone()]
+    } else {
+        media.iter().cloned().collect()
+    }
+}
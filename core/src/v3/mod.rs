@@ -0,0 +1,47 @@
+//! Core types and traits associated with the
+//! [OpenAPI v3 specification](https://github.com/OAI/OpenAPI-Specification/blob/master/versions/3.0.3.md).
+//!
+//! Schema objects are shared with v2, so the same `Schema` implementors
+//! (including the ones generated by `#[api_v2_schema]`) can be used here.
+
+mod convert;
+pub mod models;
+#[cfg(feature = "codegen")]
+mod resolver;
+
+pub use self::models::{DefaultSchema, ResolvableApi};
+pub use crate::v2::schema::Schema;
+
+#[cfg(feature = "codegen")]
+use self::resolver::Resolver;
+#[cfg(feature = "codegen")]
+use crate::error::ValidationError;
+
+#[cfg(feature = "codegen")]
+impl<S: Schema + Default> ResolvableApi<S> {
+    /// Consumes this API schema, resolves the references and returns
+    /// the resolved schema.
+    ///
+    /// This is the v3 equivalent of `v2::ResolvableApi::resolve`. Anonymous
+    /// schemas in request bodies and responses are added to `components/schemas`.
+    /// Like v2, references to other documents should be bundled into the spec
+    /// beforehand.
+    pub fn resolve(self) -> Result<ResolvableApi<S>, ValidationError> {
+        let mut resolver = Resolver::from((self.paths, self.components));
+        resolver.resolve()?;
+        Ok(ResolvableApi {
+            openapi: self.openapi,
+            info: self.info,
+            servers: self.servers,
+            paths: resolver.paths,
+            components: resolver.components,
+            security: self.security,
+            tags: self.tags,
+            external_docs: self.external_docs,
+            coders: self.coders,
+            support_crates: self.support_crates,
+            spec_format: self.spec_format,
+            extensions: self.extensions,
+        })
+    }
+}
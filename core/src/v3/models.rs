@@ -0,0 +1,571 @@
+//! Models used by OpenAPI v3.
+
+pub use crate::v2::models::{
+    Coder, Coders, Contact, DefaultSchema, Either, Extensions, ExternalDocs, HttpMethod, Info,
+    License, MediaRange, Reference, Resolvable, SecurityRequirement, SpecFormat, Tag, JSON_CODER,
+    JSON_MIME, YAML_CODER, YAML_MIME,
+};
+
+use crate::v2::models::{
+    deserialize_extensions, deserialize_v3_methods, DefaultSchemaRaw, PATH_TEMPLATE_REGEX,
+};
+use parking_lot::RwLock;
+use regex::Captures;
+use serde::Deserialize;
+
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::ops::Deref;
+use std::sync::Arc;
+
+/// OpenAPI version.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub enum Version {
+    #[serde(rename = "3.0.0")]
+    V3_0_0,
+    #[serde(rename = "3.0.1")]
+    V3_0_1,
+    #[serde(rename = "3.0.2")]
+    V3_0_2,
+    #[serde(rename = "3.0.3")]
+    V3_0_3,
+}
+
+/// OpenAPI v3 spec which can be traversed and resolved for codegen.
+pub type ResolvableApi<S> =
+    Api<ResolvableParameter<S>, ResolvableRequestBody<S>, ResolvableResponse<S>, Resolvable<S>>;
+
+/// OpenAPI v3 spec with defaults.
+pub type DefaultApiRaw =
+    Api<DefaultParameterRaw, DefaultRequestBodyRaw, DefaultResponseRaw, DefaultSchemaRaw>;
+
+/// OpenAPI v3 spec generic over parameter, request body, response and schema.
+///
+/// https://github.com/OAI/OpenAPI-Specification/blob/master/versions/3.0.3.md#openapi-object
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Api<P, B, R, S> {
+    pub openapi: Version,
+    pub info: Info,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub servers: Vec<Server>,
+    pub paths: BTreeMap<String, PathItem<P, B, R>>,
+    #[serde(
+        default = "Components::default",
+        skip_serializing_if = "Components::is_empty"
+    )]
+    pub components: Components<P, B, R, S>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub security: Vec<SecurityRequirement>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<Tag>,
+    #[serde(rename = "externalDocs", skip_serializing_if = "Option::is_none")]
+    pub external_docs: Option<ExternalDocs>,
+    /// Extension for custom coders to be used for decoding API objects.
+    ///
+    /// This is the same as `x-rust-coders` in v2.
+    #[serde(
+        default,
+        rename = "x-rust-coders",
+        skip_serializing_if = "<Coders as Deref>::Target::is_empty"
+    )]
+    pub coders: Coders,
+    /// Additional crates that need to be added to the manifest.
+    ///
+    /// This is the same as `x-rust-dependencies` in v2.
+    #[serde(
+        default,
+        rename = "x-rust-dependencies",
+        skip_serializing_if = "BTreeMap::is_empty"
+    )]
+    pub support_crates: BTreeMap<String, String>,
+    /// This field is set manually, because we don't know the format in which
+    /// the spec was provided and we need to use this as the fallback encoding.
+    #[serde(skip)]
+    pub spec_format: SpecFormat,
+    /// Vendor extensions (`x-` fields).
+    #[serde(flatten, deserialize_with = "deserialize_extensions")]
+    pub extensions: Extensions,
+}
+
+impl<P, B, R, S> Api<P, B, R, S> {
+    /// Gets the parameters from the given path template and calls
+    /// the given function with the parameter names.
+    pub fn path_parameters_map(
+        path: &str,
+        mut f: impl FnMut(&str) -> Cow<'static, str>,
+    ) -> Cow<'_, str> {
+        PATH_TEMPLATE_REGEX.replace_all(path, |c: &Captures| f(&c[1]))
+    }
+}
+
+/// Server object.
+///
+/// https://github.com/OAI/OpenAPI-Specification/blob/master/versions/3.0.3.md#server-object
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Server {
+    pub url: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub variables: BTreeMap<String, ServerVariable>,
+}
+
+impl Server {
+    /// Returns the URL of this server after substituting the
+    /// default values of its variables.
+    pub fn default_url(&self) -> String {
+        let mut url = self.url.clone();
+        for (name, var) in &self.variables {
+            url = url.replace(&format!("{{{}}}", name), &var.default);
+        }
+
+        url
+    }
+}
+
+/// Server variable object.
+///
+/// https://github.com/OAI/OpenAPI-Specification/blob/master/versions/3.0.3.md#server-variable-object
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct ServerVariable {
+    #[serde(default, rename = "enum", skip_serializing_if = "Vec::is_empty")]
+    pub enum_: Vec<String>,
+    pub default: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+/// Components object.
+///
+/// https://github.com/OAI/OpenAPI-Specification/blob/master/versions/3.0.3.md#components-object
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Components<P, B, R, S> {
+    #[serde(default = "BTreeMap::new", skip_serializing_if = "BTreeMap::is_empty")]
+    pub schemas: BTreeMap<String, S>,
+    #[serde(default = "BTreeMap::new", skip_serializing_if = "BTreeMap::is_empty")]
+    pub responses: BTreeMap<String, R>,
+    #[serde(default = "BTreeMap::new", skip_serializing_if = "BTreeMap::is_empty")]
+    pub parameters: BTreeMap<String, P>,
+    #[serde(default = "BTreeMap::new", skip_serializing_if = "BTreeMap::is_empty")]
+    pub examples: BTreeMap<String, Example>,
+    #[serde(default = "BTreeMap::new", skip_serializing_if = "BTreeMap::is_empty")]
+    pub request_bodies: BTreeMap<String, B>,
+    #[serde(default = "BTreeMap::new", skip_serializing_if = "BTreeMap::is_empty")]
+    pub headers: BTreeMap<String, Header<S>>,
+    #[serde(default = "BTreeMap::new", skip_serializing_if = "BTreeMap::is_empty")]
+    pub security_schemes: BTreeMap<String, SecurityScheme>,
+    #[serde(default = "BTreeMap::new", skip_serializing_if = "BTreeMap::is_empty")]
+    pub links: BTreeMap<String, Link>,
+    #[serde(default = "BTreeMap::new", skip_serializing_if = "BTreeMap::is_empty")]
+    pub callbacks: BTreeMap<String, Callback<P, B, R>>,
+}
+
+impl<P, B, R, S> Components<P, B, R, S> {
+    /// Checks whether this object has any components.
+    pub fn is_empty(&self) -> bool {
+        self.schemas.is_empty()
+            && self.responses.is_empty()
+            && self.parameters.is_empty()
+            && self.examples.is_empty()
+            && self.request_bodies.is_empty()
+            && self.headers.is_empty()
+            && self.security_schemes.is_empty()
+            && self.links.is_empty()
+            && self.callbacks.is_empty()
+    }
+}
+
+/// Security Scheme object.
+///
+/// https://github.com/OAI/OpenAPI-Specification/blob/master/versions/3.0.3.md#security-scheme-object
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SecurityScheme {
+    #[serde(rename = "type")]
+    pub type_: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(rename = "in", skip_serializing_if = "Option::is_none")]
+    pub in_: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scheme: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bearer_format: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub flows: Option<OAuthFlows>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub open_id_connect_url: Option<String>,
+    /// Vendor extensions (`x-` fields).
+    #[serde(flatten, deserialize_with = "deserialize_extensions")]
+    pub extensions: Extensions,
+}
+
+/// OAuth Flows object.
+///
+/// https://github.com/OAI/OpenAPI-Specification/blob/master/versions/3.0.3.md#oauth-flows-object
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OAuthFlows {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub implicit: Option<OAuthFlow>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub password: Option<OAuthFlow>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub client_credentials: Option<OAuthFlow>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub authorization_code: Option<OAuthFlow>,
+}
+
+/// OAuth Flow object.
+///
+/// https://github.com/OAI/OpenAPI-Specification/blob/master/versions/3.0.3.md#oauth-flow-object
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OAuthFlow {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub authorization_url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub token_url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub refresh_url: Option<String>,
+    #[serde(default)]
+    pub scopes: BTreeMap<String, String>,
+}
+
+/// Path item that can be traversed and resolved for codegen.
+pub type ResolvablePathItem<S> =
+    PathItem<ResolvableParameter<S>, ResolvableRequestBody<S>, ResolvableResponse<S>>;
+
+/// Path item with default parameter, request body and response.
+pub type DefaultPathItemRaw =
+    PathItem<DefaultParameterRaw, DefaultRequestBodyRaw, DefaultResponseRaw>;
+
+/// Path item object.
+///
+/// https://github.com/OAI/OpenAPI-Specification/blob/master/versions/3.0.3.md#path-item-object
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct PathItem<P, B, R> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(
+        flatten,
+        default = "BTreeMap::default",
+        deserialize_with = "deserialize_v3_methods",
+        bound(deserialize = "P: Deserialize<'de>, B: Deserialize<'de>, R: Deserialize<'de>")
+    )]
+    pub methods: BTreeMap<HttpMethod, Operation<P, B, R>>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub servers: Vec<Server>,
+    #[serde(default = "Vec::default", skip_serializing_if = "Vec::is_empty")]
+    pub parameters: Vec<Either<Reference, P>>,
+    /// Vendor extensions (`x-` fields).
+    #[serde(flatten, deserialize_with = "deserialize_extensions")]
+    pub extensions: Extensions,
+}
+
+/// Parameter that can be traversed and resolved for codegen.
+pub type ResolvableParameter<S> = Arc<RwLock<Parameter<Resolvable<S>>>>;
+
+/// Parameter with the default raw schema.
+pub type DefaultParameterRaw = Parameter<DefaultSchemaRaw>;
+
+/// Request parameter object.
+///
+/// https://github.com/OAI/OpenAPI-Specification/blob/master/versions/3.0.3.md#parameter-object
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Parameter<S> {
+    pub name: String,
+    #[serde(rename = "in")]
+    pub in_: ParameterIn,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub required: bool,
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub deprecated: bool,
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub allow_empty_value: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub style: Option<ParameterStyle>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub explode: Option<bool>,
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub allow_reserved: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub schema: Option<S>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub example: Option<serde_json::Value>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub examples: BTreeMap<String, Either<Reference, Example>>,
+    #[serde(default = "BTreeMap::new", skip_serializing_if = "BTreeMap::is_empty")]
+    pub content: BTreeMap<MediaRange, MediaType<S>>,
+    /// Vendor extensions (`x-` fields).
+    #[serde(flatten, deserialize_with = "deserialize_extensions")]
+    pub extensions: Extensions,
+}
+
+/// The location of the parameter.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, Eq, PartialEq, Ord, PartialOrd)]
+#[serde(rename_all = "camelCase")]
+pub enum ParameterIn {
+    Query,
+    Header,
+    Path,
+    Cookie,
+}
+
+/// Describes how the parameter value will be serialized.
+///
+/// https://github.com/OAI/OpenAPI-Specification/blob/master/versions/3.0.3.md#style-values
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, Eq, PartialEq, Ord, PartialOrd)]
+#[serde(rename_all = "camelCase")]
+pub enum ParameterStyle {
+    Matrix,
+    Label,
+    Form,
+    Simple,
+    SpaceDelimited,
+    PipeDelimited,
+    DeepObject,
+}
+
+/// Header object.
+///
+/// https://github.com/OAI/OpenAPI-Specification/blob/master/versions/3.0.3.md#header-object
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Header<S> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub required: bool,
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub deprecated: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub style: Option<ParameterStyle>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub explode: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub schema: Option<S>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub example: Option<serde_json::Value>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub examples: BTreeMap<String, Either<Reference, Example>>,
+    #[serde(default = "BTreeMap::new", skip_serializing_if = "BTreeMap::is_empty")]
+    pub content: BTreeMap<MediaRange, MediaType<S>>,
+    /// Vendor extensions (`x-` fields).
+    #[serde(flatten, deserialize_with = "deserialize_extensions")]
+    pub extensions: Extensions,
+}
+
+/// Request body that can be traversed and resolved for codegen.
+pub type ResolvableRequestBody<S> = Arc<RwLock<RequestBody<Resolvable<S>>>>;
+
+/// Request body with the default raw schema.
+pub type DefaultRequestBodyRaw = RequestBody<DefaultSchemaRaw>;
+
+/// Request body object.
+///
+/// https://github.com/OAI/OpenAPI-Specification/blob/master/versions/3.0.3.md#request-body-object
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct RequestBody<S> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub content: BTreeMap<MediaRange, MediaType<S>>,
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub required: bool,
+    /// Vendor extensions (`x-` fields).
+    #[serde(flatten, deserialize_with = "deserialize_extensions")]
+    pub extensions: Extensions,
+}
+
+/// Media type object.
+///
+/// https://github.com/OAI/OpenAPI-Specification/blob/master/versions/3.0.3.md#media-type-object
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct MediaType<S> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub schema: Option<S>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub example: Option<serde_json::Value>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub examples: BTreeMap<String, Either<Reference, Example>>,
+    #[serde(default = "BTreeMap::new", skip_serializing_if = "BTreeMap::is_empty")]
+    pub encoding: BTreeMap<String, Encoding<S>>,
+}
+
+/// Encoding object.
+///
+/// https://github.com/OAI/OpenAPI-Specification/blob/master/versions/3.0.3.md#encoding-object
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Encoding<S> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content_type: Option<String>,
+    #[serde(default = "BTreeMap::new", skip_serializing_if = "BTreeMap::is_empty")]
+    pub headers: BTreeMap<String, Either<Reference, Header<S>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub style: Option<ParameterStyle>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub explode: Option<bool>,
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub allow_reserved: bool,
+}
+
+/// Example object.
+///
+/// https://github.com/OAI/OpenAPI-Specification/blob/master/versions/3.0.3.md#example-object
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Example {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub external_value: Option<String>,
+}
+
+/// Operation that can be traversed and resolved for codegen.
+pub type ResolvableOperation<S> =
+    Operation<ResolvableParameter<S>, ResolvableRequestBody<S>, ResolvableResponse<S>>;
+
+/// Operation with default raw parameter, request body and response.
+pub type DefaultOperationRaw =
+    Operation<DefaultParameterRaw, DefaultRequestBodyRaw, DefaultResponseRaw>;
+
+/// Operation object.
+///
+/// https://github.com/OAI/OpenAPI-Specification/blob/master/versions/3.0.3.md#operation-object
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Operation<P, B, R> {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub external_docs: Option<ExternalDocs>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub operation_id: Option<String>,
+    #[serde(default = "Vec::default", skip_serializing_if = "Vec::is_empty")]
+    pub parameters: Vec<Either<Reference, P>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_body: Option<Either<Reference, B>>,
+    pub responses: BTreeMap<String, Either<Reference, R>>,
+    #[serde(default = "BTreeMap::new", skip_serializing_if = "BTreeMap::is_empty")]
+    pub callbacks: BTreeMap<String, Either<Reference, Callback<P, B, R>>>,
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub deprecated: bool,
+    // *NOTE:* Unlike v2, an empty list here is meaningful, because
+    // it removes the top-level security requirements for this operation.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub security: Option<Vec<SecurityRequirement>>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub servers: Vec<Server>,
+    /// Vendor extensions (`x-` fields).
+    #[serde(flatten, deserialize_with = "deserialize_extensions")]
+    pub extensions: Extensions,
+}
+
+/// Callback object, which maps runtime expressions to path items.
+///
+/// https://github.com/OAI/OpenAPI-Specification/blob/master/versions/3.0.3.md#callback-object
+pub type Callback<P, B, R> = BTreeMap<String, PathItem<P, B, R>>;
+
+/// Callback that can be traversed and resolved for codegen.
+pub type ResolvableCallback<S> =
+    Callback<ResolvableParameter<S>, ResolvableRequestBody<S>, ResolvableResponse<S>>;
+
+/// Response that can be traversed and resolved for codegen.
+pub type ResolvableResponse<S> = Arc<RwLock<Response<Resolvable<S>>>>;
+
+/// Response with the default raw schema.
+pub type DefaultResponseRaw = Response<DefaultSchemaRaw>;
+
+/// Response object.
+///
+/// https://github.com/OAI/OpenAPI-Specification/blob/master/versions/3.0.3.md#response-object
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Response<S> {
+    pub description: String,
+    #[serde(default = "BTreeMap::new", skip_serializing_if = "BTreeMap::is_empty")]
+    pub headers: BTreeMap<String, Either<Reference, Header<S>>>,
+    #[serde(default = "BTreeMap::new", skip_serializing_if = "BTreeMap::is_empty")]
+    pub content: BTreeMap<MediaRange, MediaType<S>>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub links: BTreeMap<String, Either<Reference, Link>>,
+    /// Vendor extensions (`x-` fields).
+    #[serde(flatten, deserialize_with = "deserialize_extensions")]
+    pub extensions: Extensions,
+}
+
+/// Link object.
+///
+/// https://github.com/OAI/OpenAPI-Specification/blob/master/versions/3.0.3.md#link-object
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Link {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub operation_ref: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub operation_id: Option<String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub parameters: BTreeMap<String, serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_body: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub server: Option<Server>,
+}
+
+/* Common trait impls */
+
+/// **NOTE:** This is just a stub. This is usually set explicitly.
+#[allow(clippy::derivable_impls)]
+impl Default for Version {
+    fn default() -> Self {
+        Version::V3_0_3
+    }
+}
+
+#[allow(clippy::derivable_impls)]
+impl Default for ParameterIn {
+    fn default() -> Self {
+        ParameterIn::Query
+    }
+}
+
+impl<P, B, R, S> Default for Components<P, B, R, S> {
+    fn default() -> Self {
+        Components {
+            schemas: BTreeMap::new(),
+            responses: BTreeMap::new(),
+            parameters: BTreeMap::new(),
+            examples: BTreeMap::new(),
+            request_bodies: BTreeMap::new(),
+            headers: BTreeMap::new(),
+            security_schemes: BTreeMap::new(),
+            links: BTreeMap::new(),
+            callbacks: BTreeMap::new(),
+        }
+    }
+}
+
+/* Serde helpers */
+
+#[allow(clippy::trivially_copy_pass_by_ref)]
+fn is_false(val: &bool) -> bool {
+    !*val
+}
@@ -0,0 +1,608 @@
+use super::models::{
+    Callback, Components, Either, Example, Header, HttpMethod, Link, MediaRange, MediaType,
+    Reference, Resolvable, ResolvableParameter, ResolvablePathItem, ResolvableRequestBody,
+    ResolvableResponse,
+};
+use crate::error::ValidationError;
+use crate::util::join_pointer;
+use crate::v2::Schema;
+use heck::CamelCase;
+
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::mem;
+
+const SCHEMA_REF_PREFIX: &str = "#/components/schemas/";
+const PARAM_REF_PREFIX: &str = "#/components/parameters/";
+const BODY_REF_PREFIX: &str = "#/components/requestBodies/";
+const RESP_REF_PREFIX: &str = "#/components/responses/";
+const HEADER_REF_PREFIX: &str = "#/components/headers/";
+const EXAMPLE_REF_PREFIX: &str = "#/components/examples/";
+const LINK_REF_PREFIX: &str = "#/components/links/";
+const CALLBACK_REF_PREFIX: &str = "#/components/callbacks/";
+
+type OperationsMap<S> = BTreeMap<String, ResolvablePathItem<S>>;
+type ResolvableComponents<S> = Components<
+    ResolvableParameter<S>,
+    ResolvableRequestBody<S>,
+    ResolvableResponse<S>,
+    Resolvable<S>,
+>;
+
+/// API schema resolver for v3. This is the counterpart of the v2 resolver.
+/// It visits each path and component, resolves `$ref` fields by finding the
+/// associated component and replaces the fields with references to the actual
+/// components.
+pub(crate) struct Resolver<S> {
+    /// List of schemas that must be marked as cyclic while resolving a schema.
+    cyclic_defs: RefCell<Vec<Resolvable<S>>>,
+    /// Paths and the corresponding operations.
+    pub paths: OperationsMap<S>,
+    /// Globally defined components.
+    pub components: ResolvableComponents<S>,
+    /// JSON pointers to the anonymous schemas added to the components.
+    anon_schemas: BTreeMap<String, String>,
+}
+
+impl<S> From<(OperationsMap<S>, ResolvableComponents<S>)> for Resolver<S> {
+    fn from((paths, components): (OperationsMap<S>, ResolvableComponents<S>)) -> Self {
+        Resolver {
+            cyclic_defs: vec![].into(),
+            paths,
+            components,
+            anon_schemas: BTreeMap::new(),
+        }
+    }
+}
+
+impl<S> Resolver<S>
+where
+    S: Schema + Default,
+{
+    /// Visit paths and components and resolve them!
+    pub fn resolve(&mut self) -> Result<(), ValidationError> {
+        // Resolve path operations first. We may encounter anonymous
+        // schemas along the way, which we'll insert into `components/schemas`
+        // and we'll have to resolve them anyway.
+        let mut paths = mem::take(&mut self.paths);
+        paths.iter_mut().try_for_each(|(path, map)| {
+            log::trace!("Checking path: {}", path);
+            self.resolve_path_item(path, map, &join_pointer("/paths", path))
+        })?;
+        self.paths = paths;
+
+        // Components which haven't been referenced by any operation
+        // should also be resolved.
+        self.resolve_components()?;
+
+        // Set the names of all schemas.
+        for (name, schema) in &self.components.schemas {
+            schema.write().set_name(name);
+        }
+
+        for (name, schema) in &self.components.schemas {
+            log::trace!("Entering: {}", name);
+            self.resolve_definitions_no_root_ref(schema, &self.schema_pointer(name))?;
+
+            for def in self.cyclic_defs.borrow_mut().drain(..) {
+                log::debug!(
+                    "Cyclic definition detected: {:?}",
+                    def.read().name().unwrap()
+                );
+                def.write().set_cyclic(true);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Resolve the schemas in parameters, request bodies, responses and headers
+    /// defined in the components.
+    fn resolve_components(&mut self) -> Result<(), ValidationError> {
+        let mut params = mem::take(&mut self.components.parameters);
+        for (name, param) in params.iter_mut() {
+            let pointer = join_pointer("/components/parameters", name);
+            let mut param = param.write();
+            if let Some(s) = param.schema.as_mut() {
+                self.resolve_definitions(s, &join_pointer(&pointer, "schema"))?;
+            }
+
+            let content_pointer = join_pointer(&pointer, "content");
+            self.resolve_content(None, "", "", &mut param.content, &content_pointer)?;
+        }
+        self.components.parameters = params;
+
+        let mut bodies = mem::take(&mut self.components.request_bodies);
+        for (name, body) in bodies.iter_mut() {
+            let pointer = join_pointer("/components/requestBodies", name);
+            let content_pointer = join_pointer(&pointer, "content");
+            self.resolve_content(None, "", "", &mut body.write().content, &content_pointer)?;
+        }
+        self.components.request_bodies = bodies;
+
+        let mut responses = mem::take(&mut self.components.responses);
+        for (name, resp) in responses.iter_mut() {
+            let pointer = join_pointer("/components/responses", name);
+            self.resolve_response(None, "", &mut Either::Right(resp.clone()), &pointer)?;
+        }
+        self.components.responses = responses;
+
+        let mut headers = mem::take(&mut self.components.headers);
+        for (name, header) in headers.iter_mut() {
+            self.resolve_header_schema(header, &join_pointer("/components/headers", name))?;
+        }
+        self.components.headers = headers;
+
+        Ok(())
+    }
+
+    /// We've passed some definition. Resolve it assuming that it doesn't
+    /// contain any reference.
+    fn resolve_definitions_no_root_ref(
+        &self,
+        schema: &Resolvable<S>,
+        pointer: &str,
+    ) -> Result<(), ValidationError> {
+        let mut schema = match schema.try_write() {
+            Some(s) => s,
+            None => {
+                self.cyclic_defs.borrow_mut().push(schema.clone());
+                return Ok(());
+            }
+        };
+
+        if let Some(schemas) = schema.all_of_mut() {
+            let all_of_pointer = join_pointer(pointer, "allOf");
+            schemas.iter_mut().enumerate().try_for_each(|(i, s)| {
+                self.resolve_definitions(s, &join_pointer(&all_of_pointer, &i.to_string()))
+            })?;
+        }
+
+        if let Some(inner) = schema.items_mut() {
+            return self.resolve_definitions(inner, &join_pointer(pointer, "items"));
+        }
+
+        if let Some(props) = schema.properties_mut() {
+            let props_pointer = join_pointer(pointer, "properties");
+            props.iter_mut().try_for_each(|(k, s)| {
+                log::trace!("Resolving property {:?}", k);
+                self.resolve_definitions(s, &join_pointer(&props_pointer, k))
+            })?;
+        }
+
+        if let Some(props) = schema
+            .additional_properties_mut()
+            .and_then(|s| s.right_mut())
+        {
+            self.resolve_definitions(props, &join_pointer(pointer, "additionalProperties"))?;
+        }
+
+        Ok(())
+    }
+
+    /// Resolve the given definition. If it contains a reference, find and assign it,
+    /// otherwise traverse further.
+    fn resolve_definitions(
+        &self,
+        schema: &mut Resolvable<S>,
+        pointer: &str,
+    ) -> Result<(), ValidationError> {
+        let ref_def = {
+            let s = match schema.try_read() {
+                Some(s) => s,
+                None => {
+                    self.cyclic_defs.borrow_mut().push(schema.clone());
+                    return Ok(());
+                }
+            };
+
+            if let Some(ref_name) = s.reference() {
+                log::trace!("Resolving definition {}", ref_name);
+                let def = self
+                    .resolve_definition_reference(ref_name)
+                    .map_err(|e| e.at(pointer))?;
+                let name = &ref_name[SCHEMA_REF_PREFIX.len()..];
+                Some((def, self.schema_pointer(name)))
+            } else {
+                None
+            }
+        };
+
+        // If this refers to some schema, then we're traversing that schema.
+        let pointer = match ref_def {
+            Some((new, def_pointer)) => {
+                *schema = match schema {
+                    Resolvable::Raw(old) => Resolvable::Resolved {
+                        old: old.clone(),
+                        new: (*new).clone(),
+                    },
+                    _ => unimplemented!("schema already resolved?"),
+                };
+
+                def_pointer
+            }
+            None => pointer.into(),
+        };
+
+        self.resolve_definitions_no_root_ref(&*schema, &pointer)
+    }
+
+    /// Returns the JSON pointer to the schema component with the given name.
+    fn schema_pointer(&self, name: &str) -> String {
+        self.anon_schemas
+            .get(name)
+            .cloned()
+            .unwrap_or_else(|| join_pointer("/components/schemas", name))
+    }
+
+    /// Resolve the operations in the given path item.
+    fn resolve_path_item(
+        &mut self,
+        path: &str,
+        map: &mut ResolvablePathItem<S>,
+        pointer: &str,
+    ) -> Result<(), ValidationError> {
+        for (&method, op) in &mut map.methods {
+            let op_pointer = join_pointer(pointer, &method.to_string().to_lowercase());
+            self.resolve_parameters(&mut op.parameters, &join_pointer(&op_pointer, "parameters"))?;
+
+            if let Some(body) = op.request_body.as_mut() {
+                let mut body_pointer = join_pointer(&op_pointer, "requestBody");
+                let ref_body = if let Some(r) = body.left() {
+                    log::trace!("Resolving request body {}", r.reference);
+                    let new = self
+                        .resolve_reference(
+                            &r.reference,
+                            BODY_REF_PREFIX,
+                            &self.components.request_bodies,
+                        )
+                        .map_err(|e| e.at(&body_pointer))?;
+                    body_pointer = r.reference.trim_start_matches('#').into();
+                    Some(new)
+                } else {
+                    None
+                };
+
+                if let Some(new) = ref_body {
+                    *body = Either::Right(new);
+                }
+
+                let mut body = body.write();
+                self.resolve_content(
+                    Some(method),
+                    path,
+                    "Body",
+                    &mut body.content,
+                    &join_pointer(&body_pointer, "content"),
+                )?;
+            }
+
+            let responses_pointer = join_pointer(&op_pointer, "responses");
+            for (code, resp) in op.responses.iter_mut() {
+                let resp_pointer = join_pointer(&responses_pointer, code);
+                self.resolve_response(Some(method), path, resp, &resp_pointer)?;
+            }
+
+            let callbacks_pointer = join_pointer(&op_pointer, "callbacks");
+            for (name, callback) in op.callbacks.iter_mut() {
+                let mut callback_pointer = join_pointer(&callbacks_pointer, name);
+                let ref_callback = if let Some(r) = callback.left() {
+                    log::trace!("Resolving callback {}", r.reference);
+                    let new = self
+                        .resolve_reference(
+                            &r.reference,
+                            CALLBACK_REF_PREFIX,
+                            &self.components.callbacks,
+                        )
+                        .map_err(|e| e.at(&callback_pointer))?;
+                    callback_pointer = r.reference.trim_start_matches('#').into();
+                    Some(new)
+                } else {
+                    None
+                };
+
+                if let Some(new) = ref_callback {
+                    *callback = Either::Right(new);
+                }
+
+                self.resolve_callback(callback, &callback_pointer)?;
+            }
+        }
+
+        self.resolve_parameters(&mut map.parameters, &join_pointer(pointer, "parameters"))
+    }
+
+    /// Resolve the path items in the given callback.
+    fn resolve_callback(
+        &mut self,
+        callback: &mut Callback<
+            ResolvableParameter<S>,
+            ResolvableRequestBody<S>,
+            ResolvableResponse<S>,
+        >,
+        pointer: &str,
+    ) -> Result<(), ValidationError> {
+        for (expr, map) in callback {
+            log::trace!("Checking callback expression: {}", expr);
+            self.resolve_path_item(expr, map, &join_pointer(pointer, expr))?;
+        }
+
+        Ok(())
+    }
+
+    /// Resolve the given bunch of parameters.
+    fn resolve_parameters(
+        &mut self,
+        params: &mut [Either<Reference, ResolvableParameter<S>>],
+        pointer: &str,
+    ) -> Result<(), ValidationError> {
+        for (i, p) in params.iter_mut().enumerate() {
+            let mut param_pointer = format!("{}/{}", pointer, i);
+            let ref_param = if let Some(r) = p.left() {
+                log::trace!("Resolving parameter {}", r.reference);
+                let new = self
+                    .resolve_reference(&r.reference, PARAM_REF_PREFIX, &self.components.parameters)
+                    .map_err(|e| e.at(&param_pointer))?;
+                param_pointer = r.reference.trim_start_matches('#').into();
+                Some(new)
+            } else {
+                None
+            };
+
+            if let Some(new) = ref_param {
+                *p = Either::Right(new);
+            }
+
+            // Parameter schemas are usually primitives, so we don't
+            // hoist them into components.
+            let mut param = p.write();
+            if let Some(s) = param.schema.as_mut() {
+                self.resolve_definitions(s, &join_pointer(&param_pointer, "schema"))?;
+            }
+
+            let examples_pointer = join_pointer(&param_pointer, "examples");
+            self.resolve_examples(&mut param.examples, &examples_pointer)?;
+            let content_pointer = join_pointer(&param_pointer, "content");
+            self.resolve_content(None, "", "", &mut param.content, &content_pointer)?;
+        }
+
+        Ok(())
+    }
+
+    /// Resolve the given response (and the stuff it references).
+    fn resolve_response(
+        &mut self,
+        method: Option<HttpMethod>,
+        path: &str,
+        resp: &mut Either<Reference, ResolvableResponse<S>>,
+        pointer: &str,
+    ) -> Result<(), ValidationError> {
+        let mut pointer = String::from(pointer);
+        let ref_resp = if let Some(r) = resp.left() {
+            log::trace!("Resolving response {}", r.reference);
+            let new = self
+                .resolve_reference(&r.reference, RESP_REF_PREFIX, &self.components.responses)
+                .map_err(|e| e.at(&pointer))?;
+            pointer = r.reference.trim_start_matches('#').into();
+            Some(new)
+        } else {
+            None
+        };
+
+        if let Some(new) = ref_resp {
+            *resp = Either::Right(new);
+        }
+
+        let mut response = resp.write();
+        let headers_pointer = join_pointer(&pointer, "headers");
+        for (name, header) in response.headers.iter_mut() {
+            self.resolve_header(header, &join_pointer(&headers_pointer, name))?;
+        }
+
+        let links_pointer = join_pointer(&pointer, "links");
+        for (name, link) in response.links.iter_mut() {
+            let ref_link = if let Some(r) = link.left() {
+                Some(
+                    self.resolve_reference::<Link>(
+                        &r.reference,
+                        LINK_REF_PREFIX,
+                        &self.components.links,
+                    )
+                    .map_err(|e| e.at(join_pointer(&links_pointer, name)))?,
+                )
+            } else {
+                None
+            };
+
+            if let Some(new) = ref_link {
+                *link = Either::Right(new);
+            }
+        }
+
+        let content_pointer = join_pointer(&pointer, "content");
+        self.resolve_content(
+            method,
+            path,
+            "Response",
+            &mut response.content,
+            &content_pointer,
+        )
+    }
+
+    /// Resolve the given header reference and its schema.
+    fn resolve_header(
+        &mut self,
+        header: &mut Either<Reference, Header<Resolvable<S>>>,
+        pointer: &str,
+    ) -> Result<(), ValidationError> {
+        let mut pointer = String::from(pointer);
+        let ref_header = if let Some(r) = header.left() {
+            log::trace!("Resolving header {}", r.reference);
+            let new = self
+                .resolve_reference(&r.reference, HEADER_REF_PREFIX, &self.components.headers)
+                .map_err(|e| e.at(&pointer))?;
+            pointer = r.reference.trim_start_matches('#').into();
+            Some(new)
+        } else {
+            None
+        };
+
+        if let Some(new) = ref_header {
+            *header = Either::Right(new);
+        }
+
+        self.resolve_header_schema(header, &pointer)
+    }
+
+    /// Resolve the schemas in the given header.
+    fn resolve_header_schema(
+        &mut self,
+        header: &mut Header<Resolvable<S>>,
+        pointer: &str,
+    ) -> Result<(), ValidationError> {
+        if let Some(s) = header.schema.as_mut() {
+            self.resolve_definitions(s, &join_pointer(pointer, "schema"))?;
+        }
+
+        self.resolve_examples(&mut header.examples, &join_pointer(pointer, "examples"))?;
+        let content_pointer = join_pointer(pointer, "content");
+        self.resolve_content(None, "", "", &mut header.content, &content_pointer)
+    }
+
+    /// Resolve the schemas (and examples) for all media types in some content.
+    ///
+    /// If a method is given, then anonymous schemas are added to the components
+    /// using names derived from the method, path and suffix.
+    fn resolve_content(
+        &mut self,
+        method: Option<HttpMethod>,
+        path: &str,
+        suffix: &str,
+        content: &mut BTreeMap<MediaRange, MediaType<Resolvable<S>>>,
+        pointer: &str,
+    ) -> Result<(), ValidationError> {
+        for (range, media) in content.iter_mut() {
+            let media_pointer = join_pointer(pointer, range.0.as_ref());
+            self.resolve_examples(
+                &mut media.examples,
+                &join_pointer(&media_pointer, "examples"),
+            )?;
+            let encoding_pointer = join_pointer(&media_pointer, "encoding");
+            for (name, encoding) in media.encoding.iter_mut() {
+                let headers_pointer =
+                    join_pointer(&join_pointer(&encoding_pointer, name), "headers");
+                for (header_name, header) in encoding.headers.iter_mut() {
+                    self.resolve_header(header, &join_pointer(&headers_pointer, header_name))?;
+                }
+            }
+
+            let schema_pointer = join_pointer(&media_pointer, "schema");
+            if method.is_some() {
+                self.resolve_operation_schema(
+                    &mut media.schema,
+                    method,
+                    path,
+                    suffix,
+                    &schema_pointer,
+                )?;
+            } else if let Some(s) = media.schema.as_mut() {
+                self.resolve_definitions(s, &schema_pointer)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Resolve the given bunch of examples.
+    fn resolve_examples(
+        &self,
+        examples: &mut BTreeMap<String, Either<Reference, Example>>,
+        pointer: &str,
+    ) -> Result<(), ValidationError> {
+        for (name, example) in examples.iter_mut() {
+            let ref_example = if let Some(r) = example.left() {
+                Some(
+                    self.resolve_reference(
+                        &r.reference,
+                        EXAMPLE_REF_PREFIX,
+                        &self.components.examples,
+                    )
+                    .map_err(|e| e.at(join_pointer(pointer, name)))?,
+                )
+            } else {
+                None
+            };
+
+            if let Some(new) = ref_example {
+                *example = Either::Right(new);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Resolves request/response schema in operation.
+    fn resolve_operation_schema(
+        &mut self,
+        s: &mut Option<Resolvable<S>>,
+        method: Option<HttpMethod>,
+        path: &str,
+        suffix: &str,
+        pointer: &str,
+    ) -> Result<(), ValidationError> {
+        let schema = match s.as_mut() {
+            Some(s) => s,
+            _ => return Ok(()),
+        };
+
+        match schema {
+            Resolvable::Raw(ref s) if s.read().reference().is_none() => {
+                // We've encountered an anonymous schema in some request body
+                // or response. Give it a name and add it to the components.
+                let prefix = method.map(|s| s.to_string()).unwrap_or_default();
+                let base_name = (prefix + path + suffix).to_camel_case();
+                // Multiple media types (or responses) could have anonymous schemas.
+                let mut def_name = base_name.clone();
+                let mut idx = 1;
+                while self.components.schemas.contains_key(&def_name) {
+                    idx += 1;
+                    def_name = format!("{}{}", base_name, idx);
+                }
+
+                let mut ref_schema = S::default();
+                ref_schema.set_reference(format!("{}{}", SCHEMA_REF_PREFIX, def_name));
+                let old_schema = mem::replace(schema, ref_schema.into());
+                self.anon_schemas.insert(def_name.clone(), pointer.into());
+                self.components.schemas.insert(def_name, old_schema);
+            }
+            _ => (),
+        }
+
+        self.resolve_definitions(schema, pointer)?;
+        Ok(())
+    }
+
+    /// Given a name (from `$ref` field), get a reference to the schema.
+    fn resolve_definition_reference(&self, name: &str) -> Result<Resolvable<S>, ValidationError> {
+        self.resolve_reference(name, SCHEMA_REF_PREFIX, &self.components.schemas)
+    }
+
+    /// Given a name (from `$ref` field), get the matching component
+    /// from the given map.
+    fn resolve_reference<T: Clone>(
+        &self,
+        name: &str,
+        prefix: &str,
+        map: &BTreeMap<String, T>,
+    ) -> Result<T, ValidationError> {
+        if !name.starts_with(prefix) {
+            return Err(ValidationError::invalid_ref(name));
+        }
+
+        let name = &name[prefix.len()..];
+        let component = map
+            .get(name)
+            .ok_or_else(|| ValidationError::MissingReference(name.into()))?;
+        Ok(component.clone())
+    }
+}
@@ -309,6 +309,10 @@ fn parse_operation_attrs(attrs: TokenStream) -> (Vec<Ident>, Vec<proc_macro2::To
                             values.push(quote!(vec![ #( #tags.to_string() ),* ]));
                         }
                     }
+                    "extensions" => {
+                        params.push(ident.clone());
+                        values.push(parse_extensions(nested));
+                    }
                     x => emit_error!(ident.span(), "Unknown list ident {}", x),
                 }
             }
@@ -319,6 +323,57 @@ fn parse_operation_attrs(attrs: TokenStream) -> (Vec<Ident>, Vec<proc_macro2::To
     (params, values)
 }
 
+/// Parses vendor extensions (say, `extensions(x_internal = true, x_owner = "pets")`)
+/// into an expression for the extensions map. Underscores in names become hyphens.
+fn parse_extensions(
+    nested: &Punctuated<NestedMeta, syn::token::Comma>,
+) -> proc_macro2::TokenStream {
+    let mut names = Vec::new();
+    let mut values = Vec::new();
+    for meta in nested {
+        match meta {
+            NestedMeta::Meta(Meta::NameValue(MetaNameValue { path, lit, .. })) => {
+                let name = match path.get_ident() {
+                    Some(i) if i.to_string().starts_with("x_") => i.to_string().replace('_', "-"),
+                    _ => {
+                        emit_error!(
+                            path.span(),
+                            "Expected extension name beginning with \"x_\": {:?}",
+                            path
+                        );
+                        continue;
+                    }
+                };
+
+                match lit {
+                    Lit::Str(_) | Lit::Int(_) | Lit::Float(_) | Lit::Bool(_) => {
+                        names.push(name);
+                        values.push(lit);
+                    }
+                    _ => emit_error!(
+                        lit.span(),
+                        "Expected string, number or boolean literal: {:?}",
+                        lit
+                    ),
+                }
+            }
+            _ => emit_error!(
+                meta.span(),
+                "Expected comma separated list of named extensions: {:?}",
+                meta
+            ),
+        }
+    }
+
+    quote!({
+        let mut tmp = paperclip::v2::models::Extensions::new();
+        #(
+            tmp.insert(#names.into(), serde_json::json!(#values));
+        )*
+        tmp
+    })
+}
+
 /// Extracts summary from top line doc comment and description from the rest
 fn extract_fn_documentation(
     item_ast: &ItemFn,
@@ -480,6 +535,17 @@ pub fn emit_v2_definition(input: TokenStream) -> TokenStream {
 
     // FIXME: Use attr path segments to find flattening, skipping, etc.
     let mut props_gen = quote! {};
+    let mut example_gen = quote! {};
+    for nested in extract_openapi_attrs(&item_ast.attrs) {
+        for attr in nested {
+            if let NestedMeta::Meta(Meta::NameValue(nv)) = &attr {
+                if nv.path.is_ident("example") {
+                    let example = example_value(&nv.lit, quote!(schema));
+                    example_gen.extend(quote!(schema.example = Some(#example);));
+                }
+            }
+        }
+    }
 
     match &item_ast.data {
         Data::Struct(ref s) => {
@@ -515,6 +581,9 @@ pub fn emit_v2_definition(input: TokenStream) -> TokenStream {
         ),
     };
 
+    // Examples depend on the type of the schema, so they're set at the end.
+    props_gen.extend(example_gen);
+
     let schema_name = name.to_string();
     let gen = quote! {
         impl #impl_generics paperclip::v2::schema::Apiv2Schema for #name #ty_generics #where_clause {
@@ -563,7 +632,9 @@ pub fn emit_v2_security(input: TokenStream) -> TokenStream {
     let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
 
     let mut security_attrs = HashMap::new();
+    let mut attr_spans = HashMap::new();
     let mut scopes = Vec::new();
+    let mut extensions = None;
 
     let valid_attrs = vec![
         "alias",
@@ -575,7 +646,7 @@ pub fn emit_v2_security(input: TokenStream) -> TokenStream {
         "token_url",
         "parent",
     ];
-    let invalid_attr_msg = format!("Invalid macro attribute. Should be bare security type [\"apiKey\", \"oauth2\"] or named attribute {:?}", valid_attrs);
+    let invalid_attr_msg = format!("Invalid macro attribute. Should be bare security type [\"basic\", \"apiKey\", \"oauth2\"] or named attribute {:?}", valid_attrs);
 
     // Read security params from openapi attr.
     for nested in extract_openapi_attrs(&item_ast.attrs) {
@@ -585,6 +656,7 @@ pub fn emit_v2_security(input: TokenStream) -> TokenStream {
                 // Read bare attribute.
                 NestedMeta::Meta(Meta::Path(attr_path)) => {
                     if let Some(type_) = attr_path.get_ident() {
+                        attr_spans.insert("type".to_string(), span);
                         if security_attrs
                             .insert("type".to_string(), type_.to_string())
                             .is_some()
@@ -601,6 +673,7 @@ pub fn emit_v2_security(input: TokenStream) -> TokenStream {
                     if let Some(attr_name) = attr_name {
                         if valid_attrs.contains(&attr_name.as_str()) {
                             if let Lit::Str(attr_value) = attr_value {
+                                attr_spans.insert(attr_name.clone(), span);
                                 if security_attrs
                                     .insert(attr_name.clone(), attr_value.value())
                                     .is_some()
@@ -646,6 +719,9 @@ pub fn emit_v2_security(input: TokenStream) -> TokenStream {
                                 }
                             }
                         }
+                        Some("extensions") => {
+                            extensions = Some(parse_extensions(&list_attr.nested))
+                        }
                         Some(path) => emit_error!(span, "Invalid list attribute: {}", path),
                         _ => emit_error!(span, "Invalid list attribute"),
                     }
@@ -682,15 +758,16 @@ pub fn emit_v2_security(input: TokenStream) -> TokenStream {
             let alias = security_attrs.get("alias").unwrap_or(type_);
             let quoted_description = quote_option(security_attrs.get("description"));
             let quoted_name = quote_option(security_attrs.get("name"));
-            let quoted_in = quote_option(security_attrs.get("in"));
-            let quoted_flow = quote_option(security_attrs.get("flow"));
+            let (quoted_type, quoted_in, quoted_flow) =
+                check_security_attrs(&security_attrs, &attr_spans, item_ast.span().unwrap());
             let quoted_auth_url = quote_option(security_attrs.get("auth_url"));
             let quoted_token_url = quote_option(security_attrs.get("token_url"));
+            let quoted_extensions = extensions.unwrap_or_else(|| quote!(Default::default()));
 
             (
                 Some(quote! {
                     Some(paperclip::v2::models::SecurityScheme {
-                        type_: #type_.to_string(),
+                        type_: #quoted_type,
                         name: #quoted_name,
                         in_: #quoted_in,
                         flow: #quoted_flow,
@@ -698,6 +775,7 @@ pub fn emit_v2_security(input: TokenStream) -> TokenStream {
                         token_url: #quoted_token_url,
                         scopes: std::collections::BTreeMap::new(),
                         description: #quoted_description,
+                        extensions: #quoted_extensions,
                     })
                 }),
                 Some(quote!(Some(#alias))),
@@ -705,6 +783,9 @@ pub fn emit_v2_security(input: TokenStream) -> TokenStream {
         }
         (None, Some(parent)) => {
             let parent_ident = Ident::new(parent, proc_macro2::Span::call_site());
+            let extensions_stream = extensions
+                .map(|e| quote!(scheme.extensions.extend(#e);))
+                .unwrap_or_default();
             // Child of security definition (Scopes will be glued to parent definition).
             (
                 Some(quote! {
@@ -713,6 +794,7 @@ pub fn emit_v2_security(input: TokenStream) -> TokenStream {
                     let mut scheme = #parent_ident::security_scheme()
                         .expect("empty schema. did you derive `Apiv2Security` for parent struct?");
                     scheme.scopes = oauth2_scopes;
+                    #extensions_stream
                     Some(scheme)
                 }),
                 Some(quote!(<#parent_ident as paperclip::v2::schema::Apiv2Schema>::NAME)),
@@ -753,6 +835,131 @@ pub fn emit_v2_security(input: TokenStream) -> TokenStream {
     gen.into()
 }
 
+/// Checks the combination of attributes for a new security scheme (emitting
+/// errors for invalid ones), and returns the quoted type, location and flow.
+fn check_security_attrs(
+    attrs: &HashMap<String, String>,
+    spans: &HashMap<String, proc_macro::Span>,
+    default_span: proc_macro::Span,
+) -> (
+    proc_macro2::TokenStream,
+    proc_macro2::TokenStream,
+    proc_macro2::TokenStream,
+) {
+    let span = |attr: &str| spans.get(attr).copied().unwrap_or(default_span);
+    let type_ = attrs["type"].as_str();
+    let (quoted_type, allowed): (_, &[&str]) = match type_ {
+        "basic" => (
+            quote!(paperclip::v2::models::SecuritySchemeType::Basic),
+            &[],
+        ),
+        "apiKey" => (
+            quote!(paperclip::v2::models::SecuritySchemeType::ApiKey),
+            &["name", "in"],
+        ),
+        "oauth2" => (
+            quote!(paperclip::v2::models::SecuritySchemeType::OAuth2),
+            &["flow", "auth_url", "token_url"],
+        ),
+        _ => {
+            emit_error!(
+                span("type"),
+                "Invalid security type {:?}. Should be one of [\"basic\", \"apiKey\", \"oauth2\"]",
+                type_
+            );
+            return (
+                quote!(paperclip::v2::models::SecuritySchemeType::Basic),
+                quote!(None),
+                quote!(None),
+            );
+        }
+    };
+
+    for attr in &["name", "in", "flow", "auth_url", "token_url"] {
+        if attrs.contains_key(*attr) && !allowed.contains(attr) {
+            emit_error!(
+                span(attr),
+                "Attribute {} is not allowed for security type {:?}.",
+                attr,
+                type_
+            );
+        }
+    }
+
+    let quoted_in = match attrs.get("in").map(String::as_str) {
+        Some("query") => quote!(Some(paperclip::v2::models::ApiKeyIn::Query)),
+        Some("header") => quote!(Some(paperclip::v2::models::ApiKeyIn::Header)),
+        Some(in_) => {
+            emit_error!(
+                span("in"),
+                "Invalid API key location {:?}. Should be one of [\"query\", \"header\"]",
+                in_
+            );
+            quote!(None)
+        }
+        None => quote!(None),
+    };
+
+    // Flow variant along with whether it needs `auth_url` and `token_url`.
+    let flow = match attrs.get("flow").map(String::as_str) {
+        Some("implicit") => Some((quote!(Implicit), true, false)),
+        Some("password") => Some((quote!(Password), false, true)),
+        Some("application") => Some((quote!(Application), false, true)),
+        Some("accessCode") => Some((quote!(AccessCode), true, true)),
+        Some(flow) => {
+            emit_error!(
+                span("flow"),
+                "Invalid OAuth2 flow {:?}. Should be one of [\"implicit\", \"password\", \"application\", \"accessCode\"]",
+                flow
+            );
+            None
+        }
+        None => None,
+    };
+
+    match type_ {
+        "apiKey" => {
+            for attr in &["name", "in"] {
+                if !attrs.contains_key(*attr) {
+                    emit_error!(
+                        span("type"),
+                        "Security type \"apiKey\" requires attribute {}.",
+                        attr
+                    );
+                }
+            }
+        }
+        "oauth2" if !attrs.contains_key("flow") => {
+            emit_error!(
+                span("type"),
+                "Security type \"oauth2\" requires attribute flow."
+            );
+        }
+        _ => (),
+    }
+
+    let quoted_flow = match flow {
+        Some((variant, needs_auth_url, needs_token_url)) => {
+            let required = [("auth_url", needs_auth_url), ("token_url", needs_token_url)];
+            for (attr, needed) in required.iter() {
+                if type_ == "oauth2" && *needed && !attrs.contains_key(*attr) {
+                    emit_error!(
+                        span("flow"),
+                        "OAuth2 flow {:?} requires attribute {}.",
+                        attrs["flow"],
+                        attr
+                    );
+                }
+            }
+
+            quote!(Some(paperclip::v2::models::OAuth2Flow::#variant))
+        }
+        None => quote!(None),
+    };
+
+    (quoted_type, quoted_in, quoted_flow)
+}
+
 #[cfg(feature = "nightly")]
 fn add_optional_impl(_: &Ident, _: &Generics) -> proc_macro2::TokenStream {
     // Empty impl for "nightly" feature because specialization helps us there.
@@ -880,12 +1087,14 @@ fn handle_field_struct(
         let docs = extract_documentation(&field.attrs);
         let docs = docs.trim();
 
+        let constraints = field_constraints(&field.attrs);
         let mut gen = if !SerdeFlatten::exists(&field.attrs) {
             quote!({
                 let mut s = #ty_ref::raw_schema();
                 if !#docs.is_empty() {
                     s.description = Some(#docs.to_string());
                 }
+                #constraints
                 schema.properties.insert(#field_name.into(), s.into());
             })
         } else {
@@ -905,6 +1114,108 @@ fn handle_field_struct(
     }
 }
 
+/// Generates code for the JSON value of an example in `#[openapi(example = ...)]`.
+/// String literals are parsed as JSON (falling back to strings), unless the given schema
+/// is a string.
+fn example_value(lit: &Lit, schema: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+    match lit {
+        Lit::Str(s) => quote!(
+            if #schema.data_type == Some(DataType::String) {
+                serde_json::Value::from(#s)
+            } else {
+                serde_json::from_str(#s).unwrap_or_else(|_| serde_json::Value::from(#s))
+            }
+        ),
+        Lit::Int(_) | Lit::Float(_) | Lit::Bool(_) => quote!(serde_json::json!(#lit)),
+        _ => {
+            emit_error!(
+                lit.span(),
+                "Expected string, number or boolean literal: {:?}",
+                lit
+            );
+            quote!(serde_json::Value::Null)
+        }
+    }
+}
+
+/// Generates code for setting the validation keywords (along with `read_only` and `example`)
+/// in `#[openapi(...)]` field attributes, say, `#[openapi(min_length = 1, pattern = "^[a-z]+$")]`.
+fn field_constraints(attrs: &[Attribute]) -> proc_macro2::TokenStream {
+    let mut gen = quote!();
+    for nested in extract_openapi_attrs(attrs) {
+        for attr in nested {
+            let span = attr.span().unwrap();
+            let (name, lit) = match &attr {
+                NestedMeta::Meta(Meta::Path(p)) => match p.get_ident() {
+                    Some(i) => (i.to_string(), None),
+                    None => {
+                        emit_error!(span, "Invalid field attribute.");
+                        continue;
+                    }
+                },
+                NestedMeta::Meta(Meta::NameValue(nv)) => match nv.path.get_ident() {
+                    Some(i) => (i.to_string(), Some(&nv.lit)),
+                    None => {
+                        emit_error!(span, "Invalid field attribute.");
+                        continue;
+                    }
+                },
+                _ => {
+                    emit_error!(span, "Invalid field attribute.");
+                    continue;
+                }
+            };
+
+            let field = Ident::new(&name, proc_macro2::Span::call_site());
+            match (name.as_str(), lit) {
+                ("read_only", None)
+                | ("unique_items", None)
+                | ("exclusive_maximum", None)
+                | ("exclusive_minimum", None) => gen.extend(quote!(s.#field = Some(true);)),
+                ("maximum", Some(lit)) | ("minimum", Some(lit)) | ("multiple_of", Some(lit)) => {
+                    match lit {
+                        Lit::Int(i) => {
+                            let value = i.base10_digits();
+                            let value: proc_macro2::TokenStream =
+                                format!("{}f64", value).parse().expect("parsing number");
+                            gen.extend(quote!(s.#field = Some(#value);))
+                        }
+                        Lit::Float(f) => {
+                            let value: f64 = f.base10_parse().expect("parsing number");
+                            gen.extend(quote!(s.#field = Some(#value);))
+                        }
+                        _ => emit_error!(span, "Expected a number for {:?}.", name),
+                    }
+                }
+                ("max_length", Some(lit))
+                | ("min_length", Some(lit))
+                | ("max_items", Some(lit))
+                | ("min_items", Some(lit)) => match lit {
+                    Lit::Int(i) => match i.base10_parse::<u32>() {
+                        Ok(value) => gen.extend(quote!(s.#field = Some(#value);)),
+                        Err(_) => emit_error!(span, "Expected an unsigned integer for {:?}.", name),
+                    },
+                    _ => emit_error!(span, "Expected an unsigned integer for {:?}.", name),
+                },
+                ("pattern", Some(Lit::Str(p))) => gen.extend(quote!(s.pattern = Some(#p.into());)),
+                ("example", Some(lit)) => {
+                    let example = example_value(lit, quote!(s));
+                    gen.extend(quote!(s.example = Some(#example);))
+                }
+                _ => emit_error!(
+                    span,
+                    "Invalid field attribute. Should be a flag [\"read_only\", \"unique_items\", \
+                     \"exclusive_maximum\", \"exclusive_minimum\"] or a named attribute \
+                     [\"maximum\", \"minimum\", \"multiple_of\", \"max_length\", \"min_length\", \
+                     \"max_items\", \"min_items\", \"pattern\", \"example\"]"
+                ),
+            }
+        }
+    }
+
+    gen
+}
+
 /// Generates code for an enum (if supported).
 fn handle_enum(e: &DataEnum, serde: &SerdeProps, props_gen: &mut proc_macro2::TokenStream) {
     props_gen.extend(quote!(
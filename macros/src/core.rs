@@ -64,6 +64,7 @@ pub fn emit_v2_schema_struct(input: TokenStream) -> TokenStream {
                 self.properties.values_mut().for_each(|s| s.remove_refs());
                 self.items.as_mut().map(|s| s.remove_refs());
                 self.extra_props.as_mut().and_then(|s| s.right_mut()).map(|s| s.remove_refs());
+                self.all_of.iter_mut().for_each(|s| s.remove_refs());
                 self.reference = None;
             }
 
@@ -78,6 +79,7 @@ pub fn emit_v2_schema_struct(input: TokenStream) -> TokenStream {
                     self.properties.values_mut().for_each(|s| s.retain_ref());
                     self.items.as_mut().map(|s| s.retain_ref());
                     self.extra_props.as_mut().and_then(|s| s.right_mut()).map(|s| s.retain_ref());
+                    self.all_of.iter_mut().for_each(|s| s.retain_ref());
                 }
             }
         }
@@ -166,6 +168,99 @@ pub fn emit_v2_schema_struct(input: TokenStream) -> TokenStream {
                 }
             }
 
+            #[inline]
+            fn all_of(&self) -> Option<&[paperclip::v2::models::Resolvable<Self>]> {
+                if self.all_of.is_empty() {
+                    None
+                } else {
+                    Some(&self.all_of)
+                }
+            }
+
+            #[inline]
+            fn all_of_mut(&mut self) -> Option<&mut [paperclip::v2::models::Resolvable<Self>]> {
+                if self.all_of.is_empty() {
+                    None
+                } else {
+                    Some(&mut self.all_of)
+                }
+            }
+
+            #[inline]
+            fn discriminator(&self) -> Option<&paperclip::v2::models::Discriminator> {
+                self.discriminator.as_ref()
+            }
+
+            #[inline]
+            fn example(&self) -> Option<&serde_json::Value> {
+                self.example.as_ref()
+            }
+
+            #[inline]
+            fn default_value(&self) -> Option<&serde_json::Value> {
+                self.default.as_ref()
+            }
+
+            #[inline]
+            fn maximum(&self) -> Option<(f64, bool)> {
+                self.maximum.map(|m| (m, self.exclusive_maximum.unwrap_or(false)))
+            }
+
+            #[inline]
+            fn minimum(&self) -> Option<(f64, bool)> {
+                self.minimum.map(|m| (m, self.exclusive_minimum.unwrap_or(false)))
+            }
+
+            #[inline]
+            fn multiple_of(&self) -> Option<f64> {
+                self.multiple_of
+            }
+
+            #[inline]
+            fn max_length(&self) -> Option<u32> {
+                self.max_length
+            }
+
+            #[inline]
+            fn min_length(&self) -> Option<u32> {
+                self.min_length
+            }
+
+            #[inline]
+            fn pattern(&self) -> Option<&str> {
+                self.pattern.as_ref().map(String::as_str)
+            }
+
+            #[inline]
+            fn max_items(&self) -> Option<u32> {
+                self.max_items
+            }
+
+            #[inline]
+            fn min_items(&self) -> Option<u32> {
+                self.min_items
+            }
+
+            #[inline]
+            fn unique_items(&self) -> bool {
+                self.unique_items.unwrap_or(false)
+            }
+
+            #[inline]
+            fn read_only(&self) -> bool {
+                self.read_only.unwrap_or(false)
+            }
+
+            #[inline]
+            fn xml(&self) -> Option<&paperclip::v2::models::Xml> {
+                self.xml.as_ref()
+            }
+
+            #[inline]
+            fn external_docs(&self) -> Option<&paperclip::v2::models::ExternalDocs> {
+                self.external_docs.as_ref()
+            }
+
             #[inline]
             fn required_properties(&self) -> Option<&std::collections::BTreeSet<String>> {
                 if self.required.is_empty() {
@@ -287,7 +382,11 @@ fn schema_fields(name: &Ident, is_ref: bool) -> proc_macro2::TokenStream {
     ));
     gen.extend(quote!(
         #[serde(skip_serializing_if = "Option::is_none")]
-        pub example: Option<String>,
+        pub example: Option<serde_json::Value>,
+    ));
+    gen.extend(quote!(
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub default: Option<serde_json::Value>,
     ));
 
     gen.extend(quote!(
@@ -316,11 +415,57 @@ fn schema_fields(name: &Ident, is_ref: bool) -> proc_macro2::TokenStream {
     add_self(&mut gen);
     gen.extend(quote!(>>,));
 
+    gen.extend(quote!(
+        #[serde(default, rename = "allOf", skip_serializing_if = "Vec::is_empty")]
+        pub all_of: Vec<
+    ));
+    add_self(&mut gen);
+    gen.extend(quote!(>,));
+
+    gen.extend(quote!(
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub discriminator: Option<paperclip::v2::models::Discriminator>,
+    ));
+
     gen.extend(quote!(
         #[serde(default, skip_serializing_if = "std::collections::BTreeSet::is_empty")]
         pub required: std::collections::BTreeSet<String>,
     ));
 
+    gen.extend(quote!(
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub maximum: Option<f64>,
+        #[serde(rename = "exclusiveMaximum", skip_serializing_if = "Option::is_none")]
+        pub exclusive_maximum: Option<bool>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub minimum: Option<f64>,
+        #[serde(rename = "exclusiveMinimum", skip_serializing_if = "Option::is_none")]
+        pub exclusive_minimum: Option<bool>,
+        #[serde(rename = "maxLength", skip_serializing_if = "Option::is_none")]
+        pub max_length: Option<u32>,
+        #[serde(rename = "minLength", skip_serializing_if = "Option::is_none")]
+        pub min_length: Option<u32>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub pattern: Option<String>,
+        #[serde(rename = "maxItems", skip_serializing_if = "Option::is_none")]
+        pub max_items: Option<u32>,
+        #[serde(rename = "minItems", skip_serializing_if = "Option::is_none")]
+        pub min_items: Option<u32>,
+        #[serde(rename = "uniqueItems", skip_serializing_if = "Option::is_none")]
+        pub unique_items: Option<bool>,
+        #[serde(rename = "multipleOf", skip_serializing_if = "Option::is_none")]
+        pub multiple_of: Option<f64>,
+        #[serde(rename = "readOnly", skip_serializing_if = "Option::is_none")]
+        pub read_only: Option<bool>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub xml: Option<paperclip::v2::models::Xml>,
+        #[serde(rename = "externalDocs", skip_serializing_if = "Option::is_none")]
+        pub external_docs: Option<paperclip::v2::models::ExternalDocs>,
+        /// Vendor extensions (`x-` fields).
+        #[serde(flatten, deserialize_with = "paperclip::v2::models::deserialize_extensions")]
+        pub extensions: paperclip::v2::models::Extensions,
+    ));
+
     if is_ref {
         gen.extend(quote!(
             #[serde(skip)]
@@ -17,6 +17,7 @@ use paperclip_core::v2::models::{
     DefaultApiRaw, DefaultOperationRaw, DefaultPathItemRaw, DefaultSchemaRaw, HttpMethod,
     SecurityScheme,
 };
+use paperclip_core::v3;
 use parking_lot::RwLock;
 
 use std::collections::BTreeMap;
@@ -265,6 +266,21 @@ where
         self
     }
 
+    /// Same as `with_json_spec_at`, but the specification is converted to
+    /// OpenAPI v3 before it's served. This can be used alongside `with_json_spec_at`
+    /// (with a different path) for serving both versions of the spec.
+    pub fn with_json_spec_v3_at(mut self, path: &str) -> Self {
+        self.inner = self.inner.take().map(|a| {
+            a.service(
+                actix_web::web::resource(path).route(actix_web::web::get().to(SpecHandlerV3 {
+                    spec: self.spec.clone(),
+                    converted: Arc::default(),
+                })),
+            )
+        });
+        self
+    }
+
     /// Calls the given function with `App` and JSON `Value` representing your API
     /// specification **built until now**.
     ///
@@ -279,6 +295,17 @@ where
         call(self, spec)
     }
 
+    /// Same as `with_raw_json_spec`, but the given `Value` represents the
+    /// OpenAPI v3 specification.
+    pub fn with_raw_json_spec_v3<F>(self, mut call: F) -> Self
+    where
+        F: FnMut(Self, serde_json::Value) -> Self,
+    {
+        let spec = v3::models::DefaultApiRaw::from(self.spec.read().clone());
+        let spec = serde_json::to_value(&spec).expect("generating json spec");
+        call(self, spec)
+    }
+
     /// Builds and returns the `actix_web::App`.
     pub fn build(self) -> actix_web::App<T, B> {
         self.inner.expect("missing app?")
@@ -314,3 +341,26 @@ impl actix_web::dev::Factory<(), Ready<Result<HttpResponse, Error>>, Result<Http
         fut_ok(HttpResponse::Ok().json(&*self.0.read()))
     }
 }
+
+#[derive(Clone)]
+struct SpecHandlerV3 {
+    spec: Arc<RwLock<DefaultApiRaw>>,
+    /// Spec converted on the first request (the spec doesn't change
+    /// once the app has been built).
+    converted: Arc<RwLock<Option<v3::models::DefaultApiRaw>>>,
+}
+
+impl actix_web::dev::Factory<(), Ready<Result<HttpResponse, Error>>, Result<HttpResponse, Error>>
+    for SpecHandlerV3
+{
+    fn call(&self, _: ()) -> Ready<Result<HttpResponse, Error>> {
+        if let Some(spec) = &*self.converted.read() {
+            return fut_ok(HttpResponse::Ok().json(spec));
+        }
+
+        let mut converted = self.converted.write();
+        let spec = converted
+            .get_or_insert_with(|| v3::models::DefaultApiRaw::from(self.spec.read().clone()));
+        fut_ok(HttpResponse::Ok().json(&*spec))
+    }
+}
@@ -1,14 +1,24 @@
 use anyhow::Error;
+use paperclip::bundle;
+use paperclip::diagnostics::{Diagnostic, Report, Severity, SourceMap};
+use paperclip::overlay::Overlay;
+use paperclip::remote::{self, SpecLoader};
 use paperclip::v2::{
     self,
     codegen::{CrateMeta, DefaultEmitter, EmitMode, Emitter, EmitterState},
-    models::{DefaultSchema, ResolvableApi},
+    diff::{self, Compatibility},
+    filter::Filter,
+    lint::{LintConfig, Linter},
+    merge::{Merger, Policy},
+    models::{DefaultApiRaw, DefaultSchema, HttpMethod, ResolvableApi},
 };
+use paperclip::v3::{self, models::SpecFormat};
 use paperclip::PaperClipError;
-use structopt::StructOpt;
+use structopt::{clap, StructOpt};
 
 use std::fs::{self, File};
-use std::path::PathBuf;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
 
 fn parse_version(s: &str) -> Result<OApiVersion, Error> {
     match s {
@@ -18,9 +28,69 @@ fn parse_version(s: &str) -> Result<OApiVersion, Error> {
     }
 }
 
-fn parse_spec(s: &str) -> Result<ResolvableApi<DefaultSchema>, Error> {
-    let fd = File::open(s)?;
-    Ok(v2::from_reader(fd)?)
+fn parse_format(s: &str) -> Result<SpecFormat, Error> {
+    match s {
+        "json" => Ok(SpecFormat::Json),
+        "yaml" => Ok(SpecFormat::Yaml),
+        _ => Err(anyhow::anyhow!(
+            "Unknown format {:?} (expected json or yaml)",
+            s
+        )),
+    }
+}
+
+fn parse_policy(s: &str) -> Result<Policy, Error> {
+    match s {
+        "error" => Ok(Policy::Error),
+        "keep" => Ok(Policy::Keep),
+        "replace" => Ok(Policy::Replace),
+        "merge" => Ok(Policy::Merge),
+        _ => Err(anyhow::anyhow!(
+            "Unknown policy {:?} (expected error, keep, replace or merge)",
+            s
+        )),
+    }
+}
+
+fn parse_method(s: &str) -> Result<HttpMethod, Error> {
+    serde_json::from_value(serde_json::Value::String(s.to_lowercase()))
+        .map_err(|_| anyhow::anyhow!("Unknown HTTP method {:?}", s))
+}
+
+fn parse_header(s: &str) -> Result<(String, String), Error> {
+    let mut split = s.splitn(2, ':');
+    match (split.next(), split.next()) {
+        (Some(name), Some(value)) if !name.trim().is_empty() => {
+            Ok((name.trim().into(), value.trim().into()))
+        }
+        _ => Err(anyhow::anyhow!(
+            "Invalid header {:?} (expected NAME: VALUE)",
+            s
+        )),
+    }
+}
+
+/// Loads the spec (file or URL) along with the documents it references.
+fn load_spec(s: &Path, loader: &SpecLoader) -> Result<(serde_json::Value, SpecFormat), Error> {
+    let url = bundle::location_url(s)?;
+    Ok(bundle::load(&url, &loader.clone().auth_origin(&url))?)
+}
+
+fn parse_spec(s: &Path, loader: &SpecLoader) -> Result<ResolvableApi<DefaultSchema>, Error> {
+    let (spec, format) = load_spec(s, loader)?;
+    let mut api: ResolvableApi<DefaultSchema> = serde_json::from_value(spec)?;
+    api.spec_format = format;
+    Ok(api)
+}
+
+fn parse_spec_v3(
+    s: &Path,
+    loader: &SpecLoader,
+) -> Result<v3::ResolvableApi<v3::DefaultSchema>, Error> {
+    let (spec, format) = load_spec(s, loader)?;
+    let mut api: v3::ResolvableApi<v3::DefaultSchema> = serde_json::from_value(spec)?;
+    api.spec_format = format;
+    Ok(api)
 }
 
 #[derive(Debug)]
@@ -31,12 +101,14 @@ enum OApiVersion {
 
 #[derive(Debug, StructOpt)]
 struct Opt {
+    #[structopt(subcommand)]
+    cmd: Option<Command>,
     /// Path to OpenAPI spec in JSON/YAML format (also supports publicly accessible URLs).
-    #[structopt(parse(try_from_str = parse_spec))]
-    spec: ResolvableApi<DefaultSchema>,
+    #[structopt(parse(from_os_str))]
+    spec: Option<PathBuf>,
     /// OpenAPI version (e.g., v2).
     #[structopt(long = "api", parse(try_from_str = parse_version))]
-    api: OApiVersion,
+    api: Option<OApiVersion>,
     /// Output directory to write code (default: current working directory).
     #[structopt(short = "o", long = "out", parse(from_os_str))]
     output: Option<PathBuf>,
@@ -50,16 +122,416 @@ struct Opt {
     /// Version (defaults to 0.1.0)
     #[structopt(long = "version")]
     pub version: Option<String>,
+    /// Don't generate code if the spec has validation errors.
+    #[structopt(long = "strict")]
+    strict: bool,
+    #[structopt(flatten)]
+    remote: RemoteOpt,
+    #[structopt(flatten)]
+    filter: FilterOpt,
 }
 
-fn parse_args_and_run() -> Result<(), Error> {
-    let opt = Opt::from_args();
-    if let OApiVersion::V3 = opt.api {
-        return Err(PaperClipError::UnsupportedOpenAPIVersion.into());
+// Options for generating code for some of the operations.
+#[derive(Debug, StructOpt)]
+struct FilterOpt {
+    /// Generate operations having this tag. This can be repeated.
+    #[structopt(long = "include-tag", number_of_values = 1)]
+    include_tags: Vec<String>,
+    /// Skip operations having this tag. This can be repeated.
+    #[structopt(long = "exclude-tag", number_of_values = 1)]
+    exclude_tags: Vec<String>,
+    /// Generate operations in paths matching this glob (e.g., "/api/v1/pods*"). This can be repeated.
+    #[structopt(long = "include-path", number_of_values = 1)]
+    include_paths: Vec<String>,
+    /// Skip operations in paths matching this glob. This can be repeated.
+    #[structopt(long = "exclude-path", number_of_values = 1)]
+    exclude_paths: Vec<String>,
+    /// Generate the operation with this ID. This can be repeated.
+    #[structopt(long = "include-operation", number_of_values = 1)]
+    include_operations: Vec<String>,
+    /// Skip the operation with this ID. This can be repeated.
+    #[structopt(long = "exclude-operation", number_of_values = 1)]
+    exclude_operations: Vec<String>,
+    /// Generate operations with this HTTP method (e.g., get). This can be repeated.
+    #[structopt(long = "include-method", number_of_values = 1, parse(try_from_str = parse_method))]
+    include_methods: Vec<HttpMethod>,
+    /// Skip operations with this HTTP method. This can be repeated.
+    #[structopt(long = "exclude-method", number_of_values = 1, parse(try_from_str = parse_method))]
+    exclude_methods: Vec<HttpMethod>,
+}
+
+impl From<FilterOpt> for Filter {
+    fn from(opt: FilterOpt) -> Self {
+        Filter {
+            include_tags: opt.include_tags,
+            exclude_tags: opt.exclude_tags,
+            include_paths: opt.include_paths,
+            exclude_paths: opt.exclude_paths,
+            include_operations: opt.include_operations,
+            exclude_operations: opt.exclude_operations,
+            include_methods: opt.include_methods,
+            exclude_methods: opt.exclude_methods,
+        }
     }
+}
+
+// Options for fetching specs from URLs.
+#[derive(Debug, StructOpt)]
+struct RemoteOpt {
+    /// Header added to requests for remote specs (e.g., "Authorization: Bearer <token>").
+    #[structopt(long = "auth-header", global = true, parse(try_from_str = parse_header))]
+    auth_header: Option<(String, String)>,
+    /// Directory for caching remote specs. Cached specs are revalidated
+    /// and used when the server is unreachable.
+    #[structopt(long = "cache-dir", global = true, parse(from_os_str))]
+    cache_dir: Option<PathBuf>,
+}
+
+impl RemoteOpt {
+    fn loader(&self) -> SpecLoader {
+        let mut loader = SpecLoader::default();
+        if let Some((name, value)) = self.auth_header.as_ref() {
+            loader = loader.auth_header(name, value);
+        }
+
+        if let Some(dir) = self.cache_dir.as_ref() {
+            loader = loader.cache_dir(dir);
+        }
+
+        loader
+    }
+}
+
+#[derive(Debug, StructOpt)]
+enum Command {
+    /// Convert an OpenAPI v2 spec to OpenAPI v3.
+    Convert {
+        /// Path or URL to OpenAPI v2 spec in JSON/YAML format.
+        #[structopt(parse(from_os_str))]
+        spec: PathBuf,
+        #[structopt(flatten)]
+        out: OutputOpt,
+    },
+    /// Bundle a spec split across multiple files into a single spec.
+    Bundle {
+        /// Path or URL to OpenAPI spec in JSON/YAML format.
+        #[structopt(parse(from_os_str))]
+        spec: PathBuf,
+        #[structopt(flatten)]
+        out: OutputOpt,
+    },
+    /// Check an OpenAPI v2 spec and report all problems in it.
+    Validate {
+        /// Path or URL to OpenAPI v2 spec in JSON/YAML format.
+        #[structopt(parse(from_os_str))]
+        spec: PathBuf,
+        #[structopt(flatten)]
+        report: ReportOpt,
+    },
+    /// Check an OpenAPI v2 spec against style rules.
+    Lint {
+        /// Path or URL to OpenAPI v2 spec in JSON/YAML format.
+        #[structopt(parse(from_os_str))]
+        spec: PathBuf,
+        /// Configuration (JSON/YAML) with levels of rules (off, warning or error).
+        #[structopt(short = "c", long = "config", parse(from_os_str))]
+        config: Option<PathBuf>,
+        #[structopt(flatten)]
+        report: ReportOpt,
+    },
+    /// Compare two versions of an OpenAPI v2 spec and report breaking changes.
+    Diff {
+        /// Path or URL to the old OpenAPI v2 spec in JSON/YAML format.
+        #[structopt(parse(from_os_str))]
+        old: PathBuf,
+        /// Path or URL to the new OpenAPI v2 spec in JSON/YAML format.
+        #[structopt(parse(from_os_str))]
+        new: PathBuf,
+        /// Print the changes as JSON.
+        #[structopt(long = "json")]
+        json: bool,
+    },
+    /// Merge OpenAPI v2 specs into one and apply overlays.
+    Merge {
+        /// Paths or URLs to OpenAPI v2 specs in JSON/YAML format (merged into the first one).
+        #[structopt(parse(from_os_str), required = true)]
+        specs: Vec<PathBuf>,
+        /// Overlay (JSON/YAML) applied to the merged spec. This can be repeated.
+        #[structopt(long = "overlay", parse(from_os_str), number_of_values = 1)]
+        overlays: Vec<PathBuf>,
+        /// Policy for conflicting definitions, parameters and responses
+        /// (error, keep, replace or merge).
+        #[structopt(long = "definitions", default_value = "error", parse(try_from_str = parse_policy))]
+        definitions: Policy,
+        /// Policy for conflicting paths (error, keep, replace or merge).
+        #[structopt(long = "paths", default_value = "merge", parse(try_from_str = parse_policy))]
+        paths: Policy,
+        /// Policy for conflicting security definitions (error, keep, replace or merge).
+        #[structopt(
+            long = "security-definitions",
+            default_value = "merge",
+            parse(try_from_str = parse_policy)
+        )]
+        security_definitions: Policy,
+        #[structopt(flatten)]
+        out: OutputOpt,
+    },
+}
+
+// Options for writing specs.
+#[derive(Debug, StructOpt)]
+struct OutputOpt {
+    /// Output file (default: stdout).
+    #[structopt(short = "o", long = "out", parse(from_os_str))]
+    output: Option<PathBuf>,
+    /// Output format (json or yaml). Defaults to the format of the input spec.
+    #[structopt(long = "format", parse(try_from_str = parse_format))]
+    format: Option<SpecFormat>,
+}
+
+// Options for reporting problems in specs.
+#[derive(Debug, StructOpt)]
+struct ReportOpt {
+    /// Print the report as JSON.
+    #[structopt(long = "json")]
+    json: bool,
+    /// Fail on warnings too.
+    #[structopt(long = "strict")]
+    strict: bool,
+}
+
+impl ReportOpt {
+    /// Prints the report and exits (with a non-zero code) if it has errors.
+    fn finish(&self, report: &Report, spec: &Path) -> Result<(), Error> {
+        let (name, source) = source_map(Some(spec));
+        if self.json {
+            serde_json::to_writer_pretty(io::stdout(), &report.to_json(&name, source.as_ref()))?;
+            println!();
+        } else if report.issues.is_empty() {
+            println!("No problems found in {}", name);
+        } else {
+            print!("{}", report.render(&name, source.as_ref()));
+        }
+
+        if report.has_errors() || (self.strict && !report.issues.is_empty()) {
+            std::process::exit(1);
+        }
 
-    let spec = opt.spec.resolve()?;
+        Ok(())
+    }
+}
+
+impl OutputOpt {
+    /// Writes the spec to the output file (or stdout).
+    fn write(&self, spec: &serde_json::Value, spec_format: SpecFormat) -> Result<(), Error> {
+        let mut writer: Box<dyn Write> = match self.output.as_ref() {
+            Some(path) => Box::new(File::create(path)?),
+            None => Box::new(io::stdout()),
+        };
+
+        match self.format.unwrap_or(spec_format) {
+            SpecFormat::Json => {
+                serde_json::to_writer_pretty(&mut writer, spec)?;
+                writeln!(writer)?;
+            }
+            SpecFormat::Yaml => serde_yaml::to_writer(&mut writer, spec)?,
+        }
+
+        Ok(())
+    }
+}
+
+fn run_command(cmd: Command, loader: &SpecLoader) -> Result<(), Error> {
+    match cmd {
+        Command::Convert { spec, out } => {
+            let (spec, format) = load_spec(&spec, loader)?;
+            out.write(&v3::convert::from_v2_value(spec)?, format)
+        }
+        Command::Bundle { spec, out } => {
+            let (spec, format) = load_spec(&spec, loader)?;
+            out.write(&spec, format)
+        }
+        Command::Validate { spec, report } => {
+            report.finish(&v2::validate(&parse_spec(&spec, loader)?), &spec)
+        }
+        Command::Lint {
+            spec,
+            config,
+            report,
+        } => {
+            let mut linter = Linter::<DefaultSchema>::default();
+            if let Some(path) = config {
+                linter = linter.configure(&LintConfig::from_path(path)?)?;
+            }
+
+            let (value, _) = load_spec(&spec, loader)?;
+            report.finish(&linter.lint(&value)?, &spec)
+        }
+        Command::Diff { old, new, json } => diff_specs(&old, &new, json, loader),
+        Command::Merge {
+            specs,
+            overlays,
+            definitions,
+            paths,
+            security_definitions,
+            out,
+        } => {
+            let merger = Merger::default()
+                .definitions(definitions)
+                .paths(paths)
+                .security_definitions(security_definitions);
+            let mut format = None;
+            let mut apis = vec![];
+            for spec in &specs {
+                let (spec, fmt) = load_spec(spec, loader)?;
+                format.get_or_insert(fmt);
+                apis.push(serde_json::from_value::<DefaultApiRaw>(spec)?);
+            }
+
+            let api = merger.merge_all(apis)?.expect("specs are required");
+            let mut spec = serde_json::to_value(&api)?;
+            for path in overlays {
+                Overlay::from_path(path)?.apply(&mut spec)?;
+            }
+
+            out.write(&spec, format.unwrap_or_default())
+        }
+    }
+}
+
+/// Prints the changes between the specs along with the suggested version. This
+/// exits with a non-zero code if there are breaking changes, unless the version
+/// of the new spec has already been bumped accordingly.
+fn diff_specs(old: &Path, new: &Path, json: bool, loader: &SpecLoader) -> Result<(), Error> {
+    let (old, new) = (
+        parse_spec(old, loader)?.resolve()?,
+        parse_spec(new, loader)?.resolve()?,
+    );
+    let diff = diff::diff(&old, &new);
+    let bump = diff.bump();
+    let next = semver::Version::parse(&old.info.version)
+        .ok()
+        .map(|v| diff.next_version(&v));
+    let is_bumped = match (&next, semver::Version::parse(&new.info.version)) {
+        (Some(next), Ok(v)) => v >= *next,
+        _ => false,
+    };
+
+    if json {
+        let value = serde_json::json!({
+            "breaking": diff.is_breaking(),
+            "bump": bump,
+            "version": next.as_ref().map(ToString::to_string),
+            "changes": diff.changes,
+        });
+        serde_json::to_writer_pretty(io::stdout(), &value)?;
+        println!();
+    } else {
+        for change in &diff.changes {
+            let kind = match change.compatibility {
+                Compatibility::Breaking => "breaking",
+                Compatibility::NonBreaking => "non-breaking",
+            };
+            println!("{}: {} (at {})", kind, change.message, change.pointer);
+        }
+
+        let breaking = diff.breaking().count();
+        println!(
+            "{} breaking and {} non-breaking change(s) found",
+            breaking,
+            diff.changes.len() - breaking
+        );
+        let bump = bump.map_or_else(|| "no".into(), |b| b.to_string());
+        match next {
+            Some(v) => println!("Suggested version: {} ({} bump)", v, bump),
+            None => println!("Suggested bump: {}", bump),
+        }
+    }
+
+    if diff.is_breaking() && !is_bumped {
+        std::process::exit(1);
+    }
+
+    Ok(())
+}
+
+impl Opt {
+    /// Path or URL to the spec used by the command.
+    fn spec_location(&self) -> Option<&Path> {
+        match self.cmd.as_ref() {
+            Some(Command::Convert { spec, .. })
+            | Some(Command::Bundle { spec, .. })
+            | Some(Command::Validate { spec, .. })
+            | Some(Command::Lint { spec, .. }) => Some(spec),
+            // Errors could be in any of the specs.
+            Some(Command::Diff { .. }) | Some(Command::Merge { .. }) => None,
+            None => self.spec.as_deref(),
+        }
+    }
+}
+
+/// Returns the name of the spec and its source map (for local files).
+fn source_map(spec: Option<&Path>) -> (String, Option<SourceMap>) {
+    let name = spec.map(|p| p.display().to_string()).unwrap_or_default();
+    let source = spec
+        .filter(|p| remote::remote_url(p).is_none())
+        .and_then(|p| fs::read_to_string(p).ok())
+        .map(|s| SourceMap::new(&s));
+    (name, source)
+}
+
+/// Prints the error (along with the offending line in the spec, if it's known).
+fn report(err: &Error, spec: Option<&Path>) {
+    let diagnostic = Diagnostic::from_error(err.as_ref());
+    let (name, source) = source_map(spec);
+    eprint!("{}", diagnostic.render(&name, source.as_ref()));
+}
+
+/// Parses and validates the v2 spec. All problems in the spec are printed
+/// (instead of failing at the first one). In strict mode, this exits if the
+/// spec has errors. Otherwise, they're printed as warnings and resolving the
+/// spec fails (like before) if it can't be used for codegen.
+fn validate_spec(
+    s: &Path,
+    loader: &SpecLoader,
+    strict: bool,
+) -> Result<ResolvableApi<DefaultSchema>, Error> {
+    let api = parse_spec(s, loader)?;
+    let mut report = v2::validate(&api);
+    if !strict {
+        for issue in &mut report.issues {
+            issue.severity = Severity::Warning;
+        }
+    }
+
+    if !report.issues.is_empty() {
+        let (name, source) = source_map(Some(s));
+        eprint!("{}", report.render(&name, source.as_ref()));
+    }
+
+    if strict && report.has_errors() {
+        std::process::exit(1);
+    }
+
+    Ok(api)
+}
+
+fn parse_args_and_run(opt: Opt) -> Result<(), Error> {
+    let loader = opt.remote.loader();
+    if let Some(cmd) = opt.cmd {
+        return run_command(cmd, &loader);
+    }
+
+    let (spec, api) = match (opt.spec, opt.api) {
+        (Some(s), Some(a)) => (s, a),
+        _ => clap::Error::with_description(
+            "The spec and --api arguments are required for generating code.",
+            clap::ErrorKind::MissingRequiredArgument,
+        )
+        .exit(),
+    };
     let mut state = EmitterState::default();
+    state.filter = opt.filter.into();
 
     if let Some(o) = opt.output {
         fs::create_dir_all(&o)?;
@@ -81,12 +553,18 @@ fn parse_args_and_run() -> Result<(), Error> {
 
     state.set_meta(meta);
     let emitter = DefaultEmitter::from(state);
-    emitter.generate(&spec)
+    match api {
+        OApiVersion::V2 => emitter.generate(&validate_spec(&spec, &loader, opt.strict)?.resolve()?),
+        OApiVersion::V3 => emitter.generate_v3(&parse_spec_v3(&spec, &loader)?.resolve()?),
+    }
 }
 
 fn main() {
     env_logger::init();
-    if let Err(e) = parse_args_and_run() {
-        eprintln!("{}", e);
+    let opt = Opt::from_args();
+    let spec = opt.spec_location().map(PathBuf::from);
+    if let Err(e) = parse_args_and_run(opt) {
+        report(&e, spec.as_deref());
+        std::process::exit(1);
     }
 }
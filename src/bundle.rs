@@ -0,0 +1,509 @@
+//! Loading specs split across multiple documents.
+//!
+//! References to other documents (say, `./models/user.yaml#/User` or
+//! `common.yaml#/parameters/PageSize`) are loaded (relative to the document
+//! having the reference) and the referenced objects are added to the root spec
+//! (to `definitions`, `parameters` and `responses` in v2, and the corresponding
+//! `components` in v3), so that the bundled spec only has local references
+//! and can be resolved as usual.
+//!
+//! ```rust,no_run
+//! use paperclip::bundle::{self, FileLoader};
+//! use paperclip::v2::{DefaultSchema, ResolvableApi};
+//!
+//! let url = bundle::location_url("specs/api.yaml").unwrap();
+//! let (spec, _format) = bundle::load(&url, &FileLoader).unwrap();
+//! let api: ResolvableApi<DefaultSchema> = serde_json::from_value(spec).unwrap();
+//! let api = api.resolve().unwrap();
+//! ```
+//!
+//! `v2::from_path` and `v3::from_path` bundle the specs they load, so the
+//! referenced files are already in the spec when it's resolved.
+//!
+//! Documents are loaded using a [`RefLoader`](trait.RefLoader.html). Only
+//! local files are supported by `FileLoader`. URLs are supported by
+//! `remote::SpecLoader` (with the `remote` feature).
+
+use crate::error::PaperClipError;
+use crate::util::join_pointer;
+use paperclip_core::v2::models::SpecFormat;
+use paperclip_core::ValidationError;
+use percent_encoding::percent_decode_str;
+use serde_json::{Map, Value};
+use url::Url;
+
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+/// Loader for the documents referenced by specs.
+pub trait RefLoader {
+    /// Loads the (JSON or YAML) document from the given URL.
+    fn load(&self, url: &Url) -> Result<(Value, SpecFormat), PaperClipError>;
+}
+
+/// Loader for documents in the local filesystem.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FileLoader;
+
+impl RefLoader for FileLoader {
+    fn load(&self, url: &Url) -> Result<(Value, SpecFormat), PaperClipError> {
+        let path = match url.scheme() {
+            "file" => url
+                .to_file_path()
+                .map_err(|_| PaperClipError::UnsupportedLocation(url.to_string()))?,
+            _ => return Err(PaperClipError::UnsupportedLocation(url.to_string())),
+        };
+
+        crate::v2::decode_path(&path)
+    }
+}
+
+/// Returns the URL for the given location (URL or file path).
+pub fn location_url<P: AsRef<Path>>(location: P) -> Result<Url, PaperClipError> {
+    let location = location.as_ref();
+    if let Some(url) = location.to_str().and_then(|s| Url::parse(s).ok()) {
+        if ["http", "https", "file"].contains(&url.scheme()) {
+            return Ok(url);
+        }
+    }
+
+    let path = std::env::current_dir()?.join(location);
+    Url::from_file_path(&path)
+        .map_err(|_| PaperClipError::UnsupportedLocation(path.display().to_string()))
+}
+
+/// Loads the spec from the given URL along with the documents it references,
+/// and returns the bundled spec (along with the format of the root document).
+pub fn load<L>(url: &Url, loader: &L) -> Result<(Value, SpecFormat), PaperClipError>
+where
+    L: RefLoader,
+{
+    let (spec, format) = loader
+        .load(url)
+        .map_err(|e| PaperClipError::DocumentLoad(url.to_string(), Box::new(e)))?;
+    Ok((bundle(spec, url, loader)?, format))
+}
+
+/// Bundles the given spec (located at the given URL) by adding the objects
+/// from the referenced documents to the spec.
+pub fn bundle<L>(mut spec: Value, url: &Url, loader: &L) -> Result<Value, PaperClipError>
+where
+    L: RefLoader,
+{
+    let mut root = url.clone();
+    root.set_fragment(None);
+    let mut bundler = Bundler {
+        loader,
+        v3: spec.get("openapi").is_some(),
+        root,
+        docs: HashMap::new(),
+        names: HashMap::new(),
+        taken: HashSet::new(),
+        chain: vec![],
+        added: vec![],
+    };
+
+    // Names already in the spec cannot be used for the added objects.
+    for &kind in KINDS {
+        if let Some(section) = bundler.section(kind) {
+            let names = spec.pointer(section).and_then(Value::as_object);
+            for name in names.into_iter().flat_map(Map::keys) {
+                bundler.taken.insert((section, name.clone()));
+            }
+        }
+    }
+
+    // Objects in the spec which refer to objects in other documents
+    // (say, `User: {$ref: "models.yaml#/User"}`) take their place.
+    for &kind in KINDS {
+        let section = match bundler.section(kind) {
+            Some(s) => s,
+            None => continue,
+        };
+
+        let map = spec.pointer_mut(section).and_then(Value::as_object_mut);
+        for (name, value) in map.into_iter().flat_map(|m| m.iter_mut()) {
+            bundler
+                .expand(value, kind, name)
+                .map_err(|e| e.at(join_pointer(section, name)))?;
+        }
+    }
+
+    let base = bundler.root.clone();
+    bundler.walk(&mut spec, Kind::Other, &base, "")?;
+    for (section, name, value) in bundler.added {
+        let mut target = &mut spec;
+        for key in section.split('/').skip(1) {
+            target = target
+                .as_object_mut()
+                .map(|o| o.entry(key).or_insert_with(|| Value::Object(Map::new())))
+                .ok_or_else(|| ValidationError::InvalidRefURI(format!("#{}", section)))?;
+        }
+
+        if let Some(map) = target.as_object_mut() {
+            map.insert(name, value);
+        }
+    }
+
+    Ok(spec)
+}
+
+/// The kind of object (decides where the referenced objects are added).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Schema,
+    Parameter,
+    Response,
+    RequestBody,
+    Header,
+    Example,
+    Link,
+    Callback,
+    PathItem,
+    Other,
+}
+
+/// Kinds of objects which can be added to the spec.
+const KINDS: &[Kind] = &[
+    Kind::Schema,
+    Kind::Parameter,
+    Kind::Response,
+    Kind::RequestBody,
+    Kind::Header,
+    Kind::Example,
+    Kind::Link,
+    Kind::Callback,
+];
+
+/// Replacement for a `$ref` field.
+enum Replacement {
+    /// Local reference to the added (or existing) object.
+    Ref(String),
+    /// The actual object (for objects which cannot be added to the spec).
+    Inline(Value),
+}
+
+struct Bundler<'a, L> {
+    loader: &'a L,
+    /// Whether this is an OpenAPI v3 spec.
+    v3: bool,
+    /// URL of the root document.
+    root: Url,
+    /// Loaded documents.
+    docs: HashMap<Url, Value>,
+    /// Names of the added objects (by their absolute URL).
+    names: HashMap<String, String>,
+    /// Names used in each section.
+    taken: HashSet<(&'static str, String)>,
+    /// References being followed (for detecting cycles).
+    chain: Vec<String>,
+    /// Objects to be added to the spec.
+    added: Vec<(&'static str, String, Value)>,
+}
+
+impl<'a, L> Bundler<'a, L>
+where
+    L: RefLoader,
+{
+    /// Visits the given value (in the document at the given URL) and
+    /// replaces the references to other documents.
+    ///
+    /// Errors are located using the given JSON pointer only if the value
+    /// is in the root document.
+    fn walk(
+        &mut self,
+        value: &mut Value,
+        kind: Kind,
+        base: &Url,
+        pointer: &str,
+    ) -> Result<(), PaperClipError> {
+        let map = match value {
+            Value::Object(m) => m,
+            // Arrays of objects (say, `allOf` or `items` with multiple schemas).
+            Value::Array(a) => {
+                for (i, v) in a.iter_mut().enumerate() {
+                    self.walk(v, kind, base, &format!("{}/{}", pointer, i))?;
+                }
+
+                return Ok(());
+            }
+            _ => return Ok(()),
+        };
+
+        if let Some(Value::String(r)) = map.get("$ref") {
+            let replacement = self.reference(r, kind, base).map_err(|e| {
+                if *base == self.root {
+                    e.at(pointer)
+                } else {
+                    e
+                }
+            })?;
+
+            match replacement {
+                Some(Replacement::Ref(r)) => {
+                    map.insert("$ref".into(), r.into());
+                }
+                Some(Replacement::Inline(v)) => *value = v,
+                None => (),
+            }
+
+            return Ok(());
+        }
+
+        for (key, child) in map.iter_mut() {
+            let pointer = join_pointer(pointer, key);
+            match child_kind(kind, key) {
+                Some((kind, true)) => match child {
+                    Value::Object(m) => {
+                        for (k, v) in m.iter_mut() {
+                            self.walk(v, kind, base, &join_pointer(&pointer, k))?;
+                        }
+                    }
+                    Value::Array(a) => {
+                        for (i, v) in a.iter_mut().enumerate() {
+                            self.walk(v, kind, base, &format!("{}/{}", pointer, i))?;
+                        }
+                    }
+                    _ => (),
+                },
+                Some((kind, false)) => self.walk(child, kind, base, &pointer)?,
+                None => (),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Replaces the given object in the root spec with the object it refers to
+    /// (if it's in another document), so that we don't end up with aliases.
+    fn expand(&mut self, value: &mut Value, kind: Kind, name: &str) -> Result<(), PaperClipError> {
+        let target = match value.get("$ref").and_then(Value::as_str) {
+            Some(r) => self
+                .root
+                .join(r)
+                .map_err(|_| ValidationError::InvalidRefURI(r.into()))?,
+            None => return Ok(()),
+        };
+
+        let mut doc = target.clone();
+        doc.set_fragment(None);
+        let key = target.to_string();
+        if doc == self.root || self.names.contains_key(&key) {
+            return Ok(());
+        }
+
+        let pointer = percent_decode_str(target.fragment().unwrap_or_default())
+            .decode_utf8_lossy()
+            .into_owned();
+        let mut new = self
+            .document(&doc)?
+            .pointer(&pointer)
+            .cloned()
+            .ok_or_else(|| ValidationError::MissingReference(key.clone()))?;
+        // References to references are followed later.
+        if new.get("$ref").is_some() {
+            return Ok(());
+        }
+
+        self.names.insert(key.clone(), name.into());
+        self.chain.push(key);
+        self.walk(&mut new, kind, &doc, &pointer)?;
+        self.chain.pop();
+        *value = new;
+        Ok(())
+    }
+
+    /// Returns the replacement for the given reference (if it has to be replaced).
+    fn reference(
+        &mut self,
+        reference: &str,
+        kind: Kind,
+        base: &Url,
+    ) -> Result<Option<Replacement>, PaperClipError> {
+        let target = base
+            .join(reference)
+            .map_err(|_| ValidationError::InvalidRefURI(reference.into()))?;
+        let mut doc = target.clone();
+        doc.set_fragment(None);
+        let pointer = percent_decode_str(target.fragment().unwrap_or_default())
+            .decode_utf8_lossy()
+            .into_owned();
+
+        let section = self.section(kind);
+        if doc == self.root {
+            if *base == self.root {
+                // Local references are resolved later.
+                return Ok(None);
+            }
+
+            if let Some(s) = section {
+                let is_direct = pointer
+                    .strip_prefix(s)
+                    .and_then(|n| n.strip_prefix('/'))
+                    .filter(|n| !n.is_empty() && !n.contains('/'))
+                    .is_some();
+                if is_direct {
+                    return Ok(Some(Replacement::Ref(format!("#{}", pointer))));
+                }
+            }
+        }
+
+        let key = target.to_string();
+        if let (Some(s), Some(name)) = (section, self.names.get(&key)) {
+            return Ok(Some(Replacement::Ref(local_ref(s, name))));
+        }
+
+        if self.chain.contains(&key) {
+            return Err(PaperClipError::CyclicReference(key));
+        }
+
+        let mut value = {
+            let document = self.document(&doc)?;
+            document
+                .pointer(&pointer)
+                .cloned()
+                .ok_or_else(|| ValidationError::MissingReference(key.clone()))?
+        };
+
+        self.chain.push(key.clone());
+        let result = self.import(&mut value, &key, &pointer, kind, &doc);
+        self.chain.pop();
+        result.map(Some)
+    }
+
+    /// Imports the referenced value (from the document at the given URL).
+    fn import(
+        &mut self,
+        value: &mut Value,
+        key: &str,
+        pointer: &str,
+        kind: Kind,
+        doc: &Url,
+    ) -> Result<Replacement, PaperClipError> {
+        // Follow references to references, so that we don't add aliases.
+        if let Some(Value::String(r)) = value.get("$ref") {
+            let r = r.clone();
+            let new = self.reference(&r, kind, doc)?;
+            return Ok(match new {
+                Some(Replacement::Ref(r)) => {
+                    if let Some(name) = r.rsplit('/').next() {
+                        self.names.insert(key.into(), unescape(name));
+                    }
+
+                    Replacement::Ref(r)
+                }
+                Some(v) => v,
+                None => Replacement::Ref(r),
+            });
+        }
+
+        let section = match self.section(kind) {
+            Some(s) => s,
+            None => {
+                self.walk(value, kind, doc, pointer)?;
+                return Ok(Replacement::Inline(value.take()));
+            }
+        };
+
+        let name = match pointer.rsplit('/').next().filter(|n| !n.is_empty()) {
+            Some(n) => unescape(n),
+            None => {
+                let file = doc.path_segments().and_then(|mut s| s.next_back());
+                let file = file.unwrap_or_default();
+                file.split('.').next().unwrap_or_default().to_owned()
+            }
+        };
+
+        let mut unique = name.clone();
+        let mut i = 1;
+        while self.taken.contains(&(section, unique.clone())) {
+            i += 1;
+            unique = format!("{}{}", name, i);
+        }
+
+        self.taken.insert((section, unique.clone()));
+        self.names.insert(key.into(), unique.clone());
+        self.walk(value, kind, doc, pointer)?;
+        self.added.push((section, unique.clone(), value.take()));
+        Ok(Replacement::Ref(local_ref(section, &unique)))
+    }
+
+    /// Returns the (cached) document at the given URL.
+    fn document(&mut self, url: &Url) -> Result<&Value, PaperClipError> {
+        if !self.docs.contains_key(url) {
+            let (doc, _) = self
+                .loader
+                .load(url)
+                .map_err(|e| PaperClipError::DocumentLoad(url.to_string(), Box::new(e)))?;
+            self.docs.insert(url.clone(), doc);
+        }
+
+        Ok(&self.docs[url])
+    }
+
+    /// Returns the JSON pointer to the section for objects of the given kind.
+    fn section(&self, kind: Kind) -> Option<&'static str> {
+        if !self.v3 {
+            return match kind {
+                Kind::Schema => Some("/definitions"),
+                Kind::Parameter => Some("/parameters"),
+                Kind::Response => Some("/responses"),
+                _ => None,
+            };
+        }
+
+        match kind {
+            Kind::Schema => Some("/components/schemas"),
+            Kind::Parameter => Some("/components/parameters"),
+            Kind::Response => Some("/components/responses"),
+            Kind::RequestBody => Some("/components/requestBodies"),
+            Kind::Header => Some("/components/headers"),
+            Kind::Example => Some("/components/examples"),
+            Kind::Link => Some("/components/links"),
+            Kind::Callback => Some("/components/callbacks"),
+            Kind::PathItem | Kind::Other => None,
+        }
+    }
+}
+
+/// Returns the kind of the value in the given field of an object (and whether
+/// it's a collection of such values), or `None` if it shouldn't be visited.
+fn child_kind(parent: Kind, key: &str) -> Option<(Kind, bool)> {
+    if key.starts_with("x-") {
+        return None;
+    }
+
+    match (parent, key) {
+        (Kind::Schema, "properties") => Some((Kind::Schema, true)),
+        (Kind::Schema, "allOf") | (Kind::Schema, "anyOf") | (Kind::Schema, "oneOf") => {
+            Some((Kind::Schema, true))
+        }
+        (_, "example") | (_, "default") | (_, "enum") | (Kind::Example, _) => None,
+        (Kind::Schema, _) => Some((Kind::Schema, false)),
+        (Kind::Callback, _) => Some((Kind::PathItem, false)),
+        (_, "schema") => Some((Kind::Schema, false)),
+        (_, "definitions") | (_, "schemas") => Some((Kind::Schema, true)),
+        (_, "parameters") => Some((Kind::Parameter, true)),
+        (_, "responses") => Some((Kind::Response, true)),
+        (_, "requestBody") => Some((Kind::RequestBody, false)),
+        (_, "requestBodies") => Some((Kind::RequestBody, true)),
+        (_, "headers") => Some((Kind::Header, true)),
+        (_, "examples") => Some((Kind::Example, true)),
+        (_, "links") => Some((Kind::Link, true)),
+        (_, "callbacks") => Some((Kind::Callback, true)),
+        (_, "paths") => Some((Kind::PathItem, true)),
+        _ => Some((Kind::Other, false)),
+    }
+}
+
+fn local_ref(section: &str, name: &str) -> String {
+    format!(
+        "#{}/{}",
+        section,
+        name.replace('~', "~0").replace('/', "~1")
+    )
+}
+
+fn unescape(name: &str) -> String {
+    name.replace("~1", "/").replace("~0", "~")
+}
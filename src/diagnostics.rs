@@ -0,0 +1,401 @@
+//! Diagnostics for problems in specs.
+//!
+//! Validation and resolver errors carry the JSON pointer to the node where
+//! they occurred (see `PaperClipError::pointer`). [`SourceMap`](struct.SourceMap.html)
+//! maps those pointers to lines and columns in the spec (JSON or YAML), so that
+//! the errors can be rendered along with the offending line.
+//!
+//! ```rust
+//! use paperclip::diagnostics::{Diagnostic, SourceMap};
+//! use paperclip::v2::{self, DefaultSchema, ResolvableApi};
+//!
+//! let source = "swagger: '2.0'
+//! info: {title: Pets, version: '1.0'}
+//! paths:
+//!   /pets:
+//!     get:
+//!       responses:
+//!         '200':
+//!           schema:
+//!             $ref: '#/definitions/Pet'
+//! ";
+//!
+//! let api: ResolvableApi<DefaultSchema> = v2::from_str(source).unwrap();
+//! let err = api.resolve().unwrap_err();
+//! let diagnostic = Diagnostic::from_error(&err);
+//! assert_eq!(
+//!     diagnostic.render("api.yaml", Some(&SourceMap::new(source))),
+//!     "error: Reference missing in spec: Pet
+//!  --> api.yaml:8:11
+//!   |
+//! 8 |           schema:
+//!   |           ^
+//!   = at /paths/~1pets/get/responses/200/schema
+//! "
+//! );
+//! ```
+
+use crate::error::PaperClipError;
+use paperclip_core::util::join_pointer;
+use paperclip_core::ValidationError;
+use serde_derive::Serialize;
+use serde_json::{json, Value};
+use yaml_rust::parser::{Event, MarkedEventReceiver, Parser};
+use yaml_rust::scanner::Marker;
+
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt::{self, Display, Write};
+
+/// Position of a node in the source (line and column start from 1).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Position {
+    pub line: usize,
+    pub column: usize,
+}
+
+/// Positions of nodes (identified by their JSON pointers) in a spec.
+#[derive(Debug, Clone, Default)]
+pub struct SourceMap {
+    lines: Vec<String>,
+    positions: HashMap<String, Position>,
+}
+
+impl SourceMap {
+    /// Builds the map for the given source (JSON or YAML).
+    ///
+    /// If the source has syntax errors, then the nodes after the
+    /// error won't have positions.
+    pub fn new(source: &str) -> Self {
+        let source = source.trim_start_matches('\u{feff}');
+        let mut builder = Builder {
+            stack: vec![],
+            positions: HashMap::new(),
+        };
+
+        // Syntax errors are reported when the spec is parsed.
+        let _ = Parser::new(source.chars()).load(&mut builder, false);
+
+        SourceMap {
+            lines: source.lines().map(String::from).collect(),
+            positions: builder.positions,
+        }
+    }
+
+    /// Returns the position of the node for the given JSON pointer. If the
+    /// node doesn't exist in the source, then the position of the closest
+    /// ancestor (other than the root) is returned.
+    ///
+    /// Nodes in mappings are positioned at their keys.
+    pub fn position(&self, pointer: &str) -> Option<Position> {
+        let mut pointer = pointer;
+        while !pointer.is_empty() {
+            if let Some(p) = self.positions.get(pointer) {
+                return Some(*p);
+            }
+
+            pointer = &pointer[..pointer.rfind('/')?];
+        }
+
+        None
+    }
+
+    /// Returns the line (if it exists) for the given line number.
+    pub fn line(&self, number: usize) -> Option<&str> {
+        self.lines.get(number.checked_sub(1)?).map(String::as_str)
+    }
+}
+
+/// Collection being visited while building the source map.
+enum Frame {
+    /// Mapping (with its pointer) and the key of the value expected next (if any).
+    Mapping(String, Option<String>),
+    /// Sequence (with its pointer) and the index of the next item.
+    Sequence(String, usize),
+}
+
+/// Receiver which records the positions of nodes from the YAML parser.
+struct Builder {
+    stack: Vec<Frame>,
+    positions: HashMap<String, Position>,
+}
+
+impl MarkedEventReceiver for Builder {
+    fn on_event(&mut self, event: Event, mark: Marker) {
+        let pos = Position {
+            line: mark.line(),
+            column: mark.col() + 1,
+        };
+
+        match event {
+            Event::MappingEnd | Event::SequenceEnd => {
+                self.stack.pop();
+                return;
+            }
+            Event::Scalar(..)
+            | Event::Alias(_)
+            | Event::MappingStart(_)
+            | Event::SequenceStart(_) => {}
+            _ => return,
+        }
+
+        let pointer = match self.stack.last_mut() {
+            None => String::new(),
+            Some(Frame::Sequence(parent, idx)) => {
+                *idx += 1;
+                format!("{}/{}", parent, *idx - 1)
+            }
+            Some(Frame::Mapping(parent, key)) => match key.take() {
+                Some(k) => join_pointer(parent, &k),
+                // Values are positioned at their keys, because they're
+                // easier to spot (especially for nested collections).
+                None => {
+                    let k = match &event {
+                        Event::Scalar(s, ..) => s.clone(),
+                        _ => String::new(),
+                    };
+
+                    // Block mappings start after their first key, but they
+                    // should be positioned at the key (say, in sequences).
+                    if let Some(p) = self.positions.get_mut(parent.as_str()) {
+                        if pos < *p {
+                            *p = pos;
+                        }
+                    }
+
+                    let pointer = join_pointer(parent, &k);
+                    *key = Some(k);
+                    pointer
+                }
+            },
+        };
+
+        self.positions.entry(pointer.clone()).or_insert(pos);
+
+        match event {
+            Event::MappingStart(_) => self.stack.push(Frame::Mapping(pointer, None)),
+            Event::SequenceStart(_) => self.stack.push(Frame::Sequence(pointer, 0)),
+            _ => (),
+        }
+    }
+}
+
+/// Severity of a problem in a spec.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    /// The spec is valid, but this could be a mistake.
+    Warning,
+    /// The spec is invalid.
+    Error,
+}
+
+/// Problem found in a spec.
+#[derive(Debug)]
+pub struct Issue {
+    pub severity: Severity,
+    /// The actual problem (usually located using its JSON pointer).
+    pub error: PaperClipError,
+}
+
+/// Problems found in a spec (in the order they were found).
+#[derive(Debug, Default)]
+pub struct Report {
+    pub issues: Vec<Issue>,
+}
+
+impl Report {
+    /// Adds the given problem to this report.
+    pub fn add<E: Into<PaperClipError>>(&mut self, severity: Severity, error: E) {
+        self.issues.push(Issue {
+            severity,
+            error: error.into(),
+        });
+    }
+
+    /// Whether this report has errors (warnings don't count).
+    pub fn has_errors(&self) -> bool {
+        self.errors().next().is_some()
+    }
+
+    /// Problems which make the spec invalid.
+    pub fn errors(&self) -> impl Iterator<Item = &PaperClipError> {
+        self.with_severity(Severity::Error)
+    }
+
+    /// Problems which don't make the spec invalid.
+    pub fn warnings(&self) -> impl Iterator<Item = &PaperClipError> {
+        self.with_severity(Severity::Warning)
+    }
+
+    /// Renders all problems (compiler-style) followed by a summary. See
+    /// [`Diagnostic::render`](struct.Diagnostic.html#method.render).
+    pub fn render(&self, name: &str, source: Option<&SourceMap>) -> String {
+        let mut out = String::new();
+        if self.issues.is_empty() {
+            return out;
+        }
+
+        for issue in &self.issues {
+            out.push_str(&Diagnostic::from_issue(issue).render(name, source));
+            out.push('\n');
+        }
+
+        let count = |n: usize, what: &str| match n {
+            1 => format!("1 {}", what),
+            n => format!("{} {}s", n, what),
+        };
+        let _ = writeln!(
+            out,
+            "{}: {} and {} found in {}",
+            if self.has_errors() {
+                Severity::Error
+            } else {
+                Severity::Warning
+            },
+            count(self.errors().count(), "error"),
+            count(self.warnings().count(), "warning"),
+            name
+        );
+        out
+    }
+
+    /// Returns this report as JSON (for tools like CI). Issues have their
+    /// severity, message, pointer and position (if the source map is given).
+    ///
+    /// ```json
+    /// {
+    ///   "spec": "api.yaml",
+    ///   "valid": false,
+    ///   "errors": 1,
+    ///   "warnings": 0,
+    ///   "issues": [{
+    ///     "severity": "error",
+    ///     "message": "Reference missing in spec: Pet",
+    ///     "pointer": "/paths/~1pets/get/responses/200/schema",
+    ///     "line": 8,
+    ///     "column": 11
+    ///   }]
+    /// }
+    /// ```
+    pub fn to_json(&self, name: &str, source: Option<&SourceMap>) -> Value {
+        let issues = self
+            .issues
+            .iter()
+            .map(|issue| {
+                let diagnostic = Diagnostic::from_issue(issue);
+                let pos = diagnostic
+                    .pointer
+                    .as_ref()
+                    .and_then(|p| source.and_then(|s| s.position(p)));
+                json!({
+                    "severity": diagnostic.severity,
+                    "message": diagnostic.message,
+                    "pointer": diagnostic.pointer,
+                    "line": pos.map(|p| p.line),
+                    "column": pos.map(|p| p.column),
+                })
+            })
+            .collect::<Vec<_>>();
+
+        json!({
+            "spec": name,
+            "valid": !self.has_errors(),
+            "errors": self.errors().count(),
+            "warnings": self.warnings().count(),
+            "issues": issues,
+        })
+    }
+
+    fn with_severity(&self, severity: Severity) -> impl Iterator<Item = &PaperClipError> {
+        self.issues
+            .iter()
+            .filter(move |i| i.severity == severity)
+            .map(|i| &i.error)
+    }
+}
+
+/// Problem in a spec, along with its location.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    /// Description of the problem.
+    pub message: String,
+    /// JSON pointer to the node where the problem occurred (if known).
+    pub pointer: Option<String>,
+}
+
+impl Diagnostic {
+    /// Creates a diagnostic for the given error. Locations are obtained
+    /// from `PaperClipError` and `ValidationError`.
+    pub fn from_error(err: &(dyn Error + 'static)) -> Self {
+        let (pointer, message) = if let Some(e) = err.downcast_ref::<PaperClipError>() {
+            match e {
+                PaperClipError::Located(p, e) => (Some(p.as_str()), e.to_string()),
+                PaperClipError::Validation(e) => (e.pointer(), e.inner().to_string()),
+                e => (None, e.to_string()),
+            }
+        } else if let Some(e) = err.downcast_ref::<ValidationError>() {
+            (e.pointer(), e.inner().to_string())
+        } else {
+            (None, err.to_string())
+        };
+
+        Diagnostic {
+            severity: Severity::Error,
+            message,
+            pointer: pointer.map(String::from),
+        }
+    }
+
+    /// Creates a diagnostic for the given problem.
+    pub fn from_issue(issue: &Issue) -> Self {
+        Diagnostic {
+            severity: issue.severity,
+            ..Diagnostic::from_error(&issue.error)
+        }
+    }
+
+    /// Renders this diagnostic (compiler-style) for the spec with the given name.
+    /// If the source map for the spec is given, then the line with the node is
+    /// shown along with its position.
+    pub fn render(&self, name: &str, source: Option<&SourceMap>) -> String {
+        let mut out = format!("{}: {}\n", self.severity, self.message);
+        let pointer = match self.pointer.as_ref() {
+            Some(p) => p,
+            None => return out,
+        };
+
+        let pos = match source.and_then(|s| s.position(pointer)) {
+            Some(p) => p,
+            None => {
+                let _ = writeln!(out, "  --> {}#{}", name, pointer);
+                return out;
+            }
+        };
+
+        let line = source.and_then(|s| s.line(pos.line)).unwrap_or_default();
+        // Keep the tabs, so that the marker is aligned with the node.
+        let indent = line
+            .chars()
+            .take(pos.column - 1)
+            .map(|c| if c == '\t' { '\t' } else { ' ' })
+            .collect::<String>();
+        let pad = " ".repeat(pos.line.to_string().len());
+        let _ = writeln!(out, "{}--> {}:{}:{}", pad, name, pos.line, pos.column);
+        let _ = writeln!(out, "{} |", pad);
+        let _ = writeln!(out, "{} | {}", pos.line, line);
+        let _ = writeln!(out, "{} | {}^", pad, indent);
+        let _ = writeln!(out, "{} = at {}", pad, pointer);
+        out
+    }
+}
+
+impl Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        })
+    }
+}
@@ -49,6 +49,36 @@ pub enum PaperClipError {
     /// A valid path cannot be obtained for the given definition.
     #[error("Invalid path for definition: {:?}", _0)]
     InvalidDefinitionPath(PathBuf),
+    /// The location of the spec (or a referenced document) is not supported.
+    #[error("Unsupported location {:?}", _0)]
+    UnsupportedLocation(String),
+    /// Error loading a referenced document.
+    #[error("Cannot load {}: {}", _0, _1)]
+    DocumentLoad(String, Box<PaperClipError>),
+    /// The reference (in another document) eventually refers to itself.
+    #[error("Cyclic reference {:?}", _0)]
+    CyclicReference(String),
+    /// The server responded with an error for the remote spec.
+    #[error("Cannot fetch spec from {:?} (HTTP {})", _0, _1)]
+    RemoteSpec(String, u16),
+    /// Style problem found by a lint rule (name of the rule and the problem).
+    #[error("{} [{}]", _1, _0)]
+    Lint(String, String),
+    /// The lint rule is not known to the linter.
+    #[error("Unknown lint rule {:?}", _0)]
+    UnknownLintRule(String),
+    /// The object (kind and name) is different in the merged specs.
+    #[error("{} {:?} is different in the merged specs", _0, _1)]
+    MergeConflict(String, String),
+    /// The path in the merged spec isn't under the base path of the spec it's merged into.
+    #[error("Path {:?} is not under the base path {:?}", _0, _1)]
+    MergeBasePath(String, String),
+    /// The target of an overlay action doesn't match any node.
+    #[error("Overlay target {:?} doesn't match any node", _0)]
+    OverlayTarget(String),
+    /// Error at some node in the spec (identified by its JSON pointer).
+    #[error("{} (at {})", _1, _0)]
+    Located(String, Box<PaperClipError>),
     /// I/O errors.
     #[error("I/O error: {}", _0)]
     Io(std::io::Error),
@@ -58,6 +88,10 @@ pub enum PaperClipError {
     /// YAML coding errors.
     #[error("YAML error: {}", _0)]
     Yaml(serde_yaml::Error),
+    #[cfg(feature = "remote")]
+    /// HTTP errors while fetching remote specs.
+    #[error("HTTP error: {}", _0)]
+    Http(reqwest::Error),
     #[cfg(feature = "codegen-fmt")]
     /// Errors from rustfmt.
     #[error("Rustfmt formatting error: {}", _0)]
@@ -68,10 +102,33 @@ pub enum PaperClipError {
     Templating(tinytemplate::error::Error),
 }
 
+impl PaperClipError {
+    /// Sets the JSON pointer to the node where this error occurred
+    /// (unless it's already set).
+    pub fn at<P: Into<String>>(self, pointer: P) -> Self {
+        match self {
+            PaperClipError::Validation(e) => PaperClipError::Validation(e.at(pointer)),
+            PaperClipError::Located(..) => self,
+            e => PaperClipError::Located(pointer.into(), Box::new(e)),
+        }
+    }
+
+    /// JSON pointer to the node where this error occurred (if known).
+    pub fn pointer(&self) -> Option<&str> {
+        match self {
+            PaperClipError::Validation(e) => e.pointer(),
+            PaperClipError::Located(p, _) => Some(p),
+            _ => None,
+        }
+    }
+}
+
 impl_err_from!(PaperClipError::std::io::Error > Io);
 impl_err_from!(PaperClipError::serde_json::Error > Json);
 impl_err_from!(PaperClipError::serde_yaml::Error > Yaml);
 impl_err_from!(PaperClipError::paperclip_core::ValidationError > Validation);
+#[cfg(feature = "remote")]
+impl_err_from!(PaperClipError::reqwest::Error > Http);
 #[cfg(feature = "codegen-fmt")]
 impl_err_from!(PaperClipError::rustfmt_nightly::ErrorKind > RustFmt);
 #[cfg(feature = "codegen")]
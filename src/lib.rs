@@ -8,9 +8,19 @@
 #[cfg(feature = "codegen")]
 extern crate log;
 
+#[cfg(feature = "v2")]
+pub mod bundle;
+#[cfg(feature = "v2")]
+pub mod diagnostics;
 mod error;
 #[cfg(feature = "v2")]
+pub mod overlay;
+#[cfg(feature = "remote")]
+pub mod remote;
+#[cfg(feature = "v2")]
 pub mod v2;
+#[cfg(feature = "v3")]
+pub mod v3;
 
 pub use error::{PaperClipError, PaperClipResult};
 pub use paperclip_core::util;
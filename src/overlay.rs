@@ -0,0 +1,187 @@
+//! Overlays for changing specs without editing them.
+//!
+//! An overlay is a list of actions, each of which updates (or removes) the
+//! nodes matching a target. Targets are JSON pointers, where `*` in a segment
+//! matches any sequence of characters in keys (or array indices), so that
+//! `/paths/~1internal*` matches all paths beginning with `/internal` and
+//! `/paths/*/*/x-internal` matches that extension in all operations.
+//!
+//! Updates are merged into the matching nodes. Objects are merged recursively,
+//! arrays are appended and other values are replaced.
+//!
+//! ```rust
+//! use paperclip::overlay::Overlay;
+//!
+//! let mut spec = serde_yaml::from_str("
+//! swagger: '2.0'
+//! info: {title: Pets, version: '1.0'}
+//! paths:
+//!   /pets:
+//!     get:
+//!       responses:
+//!         '200': {description: OK}
+//!   /internal/metrics:
+//!     get:
+//!       responses:
+//!         '200': {description: OK}
+//! ").unwrap();
+//!
+//! let overlay: Overlay = serde_yaml::from_str("
+//! actions:
+//!   - target: /info
+//!     update:
+//!       description: Pet store
+//!       x-audience: public
+//!   - target: /paths/~1internal*
+//!     remove: true
+//! ").unwrap();
+//! overlay.apply(&mut spec).unwrap();
+//!
+//! assert_eq!(spec["info"]["description"], "Pet store");
+//! assert!(spec["paths"].get("/internal/metrics").is_none());
+//! ```
+
+use crate::error::PaperClipError;
+use crate::util::{glob_match, join_pointer};
+use serde_derive::{Deserialize, Serialize};
+use serde_json::Value;
+
+use std::fs::File;
+use std::path::Path;
+
+/// Overlay document.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Overlay {
+    /// Actions applied (in order) to the spec.
+    #[serde(default)]
+    pub actions: Vec<Action>,
+}
+
+/// Action in an overlay.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Action {
+    /// JSON pointer (with `*` wildcards) to the nodes changed by this action.
+    pub target: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Value merged into the matching nodes.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub update: Option<Value>,
+    /// Whether the matching nodes should be removed.
+    #[serde(default)]
+    pub remove: bool,
+}
+
+impl Overlay {
+    /// Loads the overlay from the given file (JSON or YAML).
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Self, PaperClipError> {
+        Ok(serde_yaml::from_reader(File::open(path)?)?)
+    }
+
+    /// Applies the actions in this overlay to the given spec. Targets
+    /// which don't match any node (or aren't JSON pointers) are errors.
+    pub fn apply(&self, spec: &mut Value) -> Result<(), PaperClipError> {
+        for action in &self.actions {
+            let mut pointers = vec![];
+            let tokens = match action.target.as_str() {
+                "" => vec![],
+                t if t.starts_with('/') => t.split('/').skip(1).collect(),
+                _ => return Err(PaperClipError::OverlayTarget(action.target.clone())),
+            };
+
+            matches(spec, String::new(), &tokens, &mut pointers);
+            if pointers.is_empty() {
+                return Err(PaperClipError::OverlayTarget(action.target.clone()));
+            }
+
+            if action.remove {
+                // Later array items are removed first, so that the indices
+                // of earlier items don't change.
+                for pointer in pointers.iter().rev() {
+                    remove(spec, pointer);
+                }
+            } else if let Some(update) = action.update.as_ref() {
+                for pointer in &pointers {
+                    if let Some(node) = spec.pointer_mut(pointer) {
+                        merge(node, update.clone());
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Collects the pointers to the nodes matching the given (target) tokens.
+fn matches(value: &Value, pointer: String, tokens: &[&str], pointers: &mut Vec<String>) {
+    let (token, rest) = match tokens.split_first() {
+        Some(t) => t,
+        None => {
+            pointers.push(pointer);
+            return;
+        }
+    };
+
+    let token = token.replace("~1", "/").replace("~0", "~");
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map {
+                if glob_match(&token, key) {
+                    matches(value, join_pointer(&pointer, key), rest, pointers);
+                }
+            }
+        }
+        Value::Array(items) => {
+            for (i, value) in items.iter().enumerate() {
+                let key = i.to_string();
+                if glob_match(&token, &key) {
+                    matches(value, join_pointer(&pointer, &key), rest, pointers);
+                }
+            }
+        }
+        _ => (),
+    }
+}
+
+/// Removes the node at the given pointer (if it exists).
+fn remove(spec: &mut Value, pointer: &str) {
+    let idx = match pointer.rfind('/') {
+        Some(i) => i,
+        // Root cannot be removed.
+        None => return,
+    };
+
+    let key = pointer[idx + 1..].replace("~1", "/").replace("~0", "~");
+    match spec.pointer_mut(&pointer[..idx]) {
+        Some(Value::Object(map)) => {
+            map.remove(&key);
+        }
+        Some(Value::Array(items)) => {
+            if let Ok(i) = key.parse::<usize>() {
+                if i < items.len() {
+                    items.remove(i);
+                }
+            }
+        }
+        _ => (),
+    }
+}
+
+/// Merges the update into the node.
+fn merge(node: &mut Value, update: Value) {
+    match (node, update) {
+        (Value::Object(map), Value::Object(update)) => {
+            for (key, value) in update {
+                match map.get_mut(&key) {
+                    Some(existing) => merge(existing, value),
+                    None => {
+                        map.insert(key, value);
+                    }
+                }
+            }
+        }
+        (Value::Array(items), Value::Array(update)) => items.extend(update),
+        (node, update) => *node = update,
+    }
+}
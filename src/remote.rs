@@ -0,0 +1,273 @@
+//! Loading specs from remote (HTTP/HTTPS) locations.
+//!
+//! ```rust,no_run
+//! use paperclip::remote::SpecLoader;
+//! use paperclip::v2::{DefaultSchema, ResolvableApi};
+//!
+//! let loader = SpecLoader::default()
+//!     .auth_header("Authorization", "Bearer foobar")
+//!     .cache_dir("/tmp/paperclip-cache");
+//! let spec = loader.fetch("https://example.com/api/openapi.yaml").unwrap();
+//! let api: ResolvableApi<DefaultSchema> = spec.deserialize().unwrap();
+//! ```
+//!
+//! `SpecLoader` is also a [`RefLoader`](../bundle/trait.RefLoader.html), so it
+//! can be used for loading specs with references to other documents (files or URLs).
+//! The auth header is only sent to the origin of the root spec, which should be
+//! set with [`auth_origin`](struct.SpecLoader.html#method.auth_origin) when bundling.
+
+use crate::bundle::{FileLoader, RefLoader};
+use crate::error::PaperClipError;
+use paperclip_core::v2::models::SpecFormat;
+use reqwest::{
+    blocking::Client,
+    header::{CONTENT_TYPE, ETAG, IF_NONE_MATCH},
+    StatusCode,
+};
+use serde::de::DeserializeOwned;
+use serde_derive::{Deserialize, Serialize};
+use serde_json::Value;
+use url::{Origin, Url};
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Returns the URL if the given location refers to a remote (HTTP/HTTPS) spec.
+pub fn remote_url(location: &Path) -> Option<&str> {
+    location
+        .to_str()
+        .filter(|s| s.starts_with("http://") || s.starts_with("https://"))
+}
+
+/// Loader for specs hosted on HTTP(S) servers.
+#[derive(Debug, Clone, Default)]
+pub struct SpecLoader {
+    auth: Option<(String, String)>,
+    auth_origin: Option<Origin>,
+    cache_dir: Option<PathBuf>,
+    timeout: Option<Duration>,
+}
+
+/// Spec fetched by the [`SpecLoader`](struct.SpecLoader.html).
+#[derive(Debug, Clone)]
+pub struct RemoteSpec {
+    /// Raw contents of the spec.
+    pub body: Vec<u8>,
+    /// Format of the spec (based on the content type, the extension in the
+    /// URL or the contents, in that order).
+    pub format: SpecFormat,
+    /// Whether this spec was loaded from the local cache.
+    pub from_cache: bool,
+}
+
+/// Metadata stored along with a cached spec.
+#[derive(Debug, Serialize, Deserialize)]
+struct CacheMeta {
+    url: String,
+    etag: Option<String>,
+    json: bool,
+}
+
+impl SpecLoader {
+    /// Adds the given header (usually `Authorization`) to the requests for
+    /// the auth origin (if any), or to the specs fetched directly with `fetch`.
+    pub fn auth_header(mut self, name: &str, value: &str) -> Self {
+        self.auth = Some((name.into(), value.into()));
+        self
+    }
+
+    /// Sends the auth header only to the origin (scheme, host and port) of
+    /// the given URL (usually the root spec). The documents it references
+    /// are loaded without the header unless they have the same origin.
+    pub fn auth_origin(mut self, url: &Url) -> Self {
+        self.auth_origin = Some(url.origin());
+        self
+    }
+
+    /// Caches the fetched specs in the given directory.
+    ///
+    /// Cached specs are revalidated using their `ETag` (if any) and
+    /// they're used as fallback when the server cannot be reached.
+    pub fn cache_dir<P: Into<PathBuf>>(mut self, path: P) -> Self {
+        self.cache_dir = Some(path.into());
+        self
+    }
+
+    /// Sets the timeout for requests (default: 30 seconds).
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Fetches the spec from the given URL.
+    pub fn fetch(&self, url: &str) -> Result<RemoteSpec, PaperClipError> {
+        let auth = match self.auth_origin.as_ref() {
+            Some(origin) => Url::parse(url).ok().map(|u| u.origin()).as_ref() == Some(origin),
+            None => true,
+        };
+
+        self.fetch_spec(url, auth)
+    }
+
+    fn fetch_spec(&self, url: &str, auth: bool) -> Result<RemoteSpec, PaperClipError> {
+        let cached = self.cached(url);
+        let client = Client::builder()
+            .timeout(self.timeout.unwrap_or_else(|| Duration::from_secs(30)))
+            .build()?;
+        let mut req = client.get(url);
+        if let Some((name, value)) = self.auth.as_ref().filter(|_| auth) {
+            req = req.header(name.as_str(), value.as_str());
+        }
+
+        if let Some(etag) = cached.as_ref().and_then(|(m, _)| m.etag.as_ref()) {
+            req = req.header(IF_NONE_MATCH, etag.as_str());
+        }
+
+        let resp = match req.send() {
+            Ok(r) => r,
+            // Use the cached spec if the server is unreachable.
+            Err(e) if e.is_connect() || e.is_timeout() => {
+                return cached.map(|(m, body)| m.into_spec(body)).ok_or(e.into())
+            }
+            Err(e) => return Err(e.into()),
+        };
+
+        let status = resp.status();
+        if status == StatusCode::NOT_MODIFIED {
+            if let Some((meta, body)) = cached {
+                return Ok(meta.into_spec(body));
+            }
+        }
+
+        if !status.is_success() {
+            return Err(PaperClipError::RemoteSpec(url.into(), status.as_u16()));
+        }
+
+        let etag = header_value(&resp, ETAG);
+        let content_type = header_value(&resp, CONTENT_TYPE);
+        let body = resp.bytes()?.to_vec();
+        let format = detect_format(url, content_type.as_deref(), &body);
+        let spec = RemoteSpec {
+            body,
+            format,
+            from_cache: false,
+        };
+
+        self.store(
+            url,
+            CacheMeta {
+                url: url.into(),
+                etag,
+                json: format == SpecFormat::Json,
+            },
+            &spec.body,
+        )?;
+
+        Ok(spec)
+    }
+
+    /// Returns the cached metadata and body (if any) for the given URL.
+    fn cached(&self, url: &str) -> Option<(CacheMeta, Vec<u8>)> {
+        let path = self.cache_path(url)?;
+        let meta: CacheMeta =
+            serde_json::from_slice(&fs::read(path.with_extension("json")).ok()?).ok()?;
+        if meta.url != url {
+            return None;
+        }
+
+        Some((meta, fs::read(path).ok()?))
+    }
+
+    fn store(&self, url: &str, meta: CacheMeta, body: &[u8]) -> Result<(), PaperClipError> {
+        let path = match self.cache_path(url) {
+            Some(p) => p,
+            None => return Ok(()),
+        };
+
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+
+        fs::write(&path, body)?;
+        fs::write(path.with_extension("json"), serde_json::to_vec(&meta)?)?;
+        Ok(())
+    }
+
+    fn cache_path(&self, url: &str) -> Option<PathBuf> {
+        // FNV-1a, so that the cache names don't change across builds.
+        let hash = url.bytes().fold(0xcbf2_9ce4_8422_2325_u64, |h, b| {
+            (h ^ u64::from(b)).wrapping_mul(0x0100_0000_01b3)
+        });
+
+        self.cache_dir
+            .as_ref()
+            .map(|d| d.join(format!("{:016x}.spec", hash)))
+    }
+}
+
+impl RefLoader for SpecLoader {
+    fn load(&self, url: &Url) -> Result<(Value, SpecFormat), PaperClipError> {
+        match url.scheme() {
+            "http" | "https" => {
+                // Referenced documents could be anywhere, so the header is
+                // only sent if they have the same origin as the root spec.
+                let auth = self.auth_origin.as_ref() == Some(&url.origin());
+                let spec = self.fetch_spec(url.as_str(), auth)?;
+                Ok((spec.deserialize()?, spec.format))
+            }
+            _ => FileLoader.load(url),
+        }
+    }
+}
+
+impl RemoteSpec {
+    /// Deserializes the spec based on its format.
+    pub fn deserialize<T: DeserializeOwned>(&self) -> Result<T, PaperClipError> {
+        Ok(crate::v2::decode(&*self.body, Some(self.format))?.0)
+    }
+}
+
+impl CacheMeta {
+    fn into_spec(self, body: Vec<u8>) -> RemoteSpec {
+        RemoteSpec {
+            body,
+            format: if self.json {
+                SpecFormat::Json
+            } else {
+                SpecFormat::Yaml
+            },
+            from_cache: true,
+        }
+    }
+}
+
+fn header_value(
+    resp: &reqwest::blocking::Response,
+    name: reqwest::header::HeaderName,
+) -> Option<String> {
+    resp.headers()
+        .get(name)
+        .and_then(|v| v.to_str().ok())
+        .map(String::from)
+}
+
+/// Detects the format of a spec using its content type, the extension
+/// in its URL or its first non-whitespace character (in that order).
+fn detect_format(url: &str, content_type: Option<&str>, body: &[u8]) -> SpecFormat {
+    let mime = content_type
+        .and_then(|c| c.split(';').next())
+        .map(|c| c.trim().to_ascii_lowercase())
+        .unwrap_or_default();
+    if mime.ends_with("json") {
+        return SpecFormat::Json;
+    } else if mime.ends_with("yaml") || mime.ends_with("yml") {
+        return SpecFormat::Yaml;
+    }
+
+    let path = url.split(&['?', '#'][..]).next().unwrap_or(url);
+    let name = path.rsplit('/').next().unwrap_or_default();
+    let ext = name.rfind('.').map(|i| &name[i + 1..]);
+    ext.and_then(SpecFormat::from_extension)
+        .unwrap_or_else(|| SpecFormat::detect(body))
+}
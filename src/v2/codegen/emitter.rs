@@ -1,21 +1,27 @@
 use super::object::{
-    ApiObject, ObjectContainer, ObjectField, ObjectVariant, OpRequirement, Parameter, Response,
+    ApiObject, ObjectContainer, ObjectField, ObjectVariant, OpRequirement, OpSecurity, Parameter,
+    Response,
 };
 use super::state::{ChildModule, EmitterState};
 use super::CrateMeta;
 use crate::error::PaperClipError;
+use crate::util::join_pointer;
 use crate::v2::{
     models::{
-        Coder, CollectionFormat, DataType, DataTypeFormat, Either, HttpMethod, Items, MediaRange,
-        ParameterIn, Reference, ResolvableApi, ResolvableOperation, ResolvableParameter,
-        ResolvablePathItem, ResolvableResponse, JSON_CODER, JSON_MIME, YAML_CODER, YAML_MIME,
+        self, Coder, CollectionFormat, DataType, DataTypeFormat, Either, HttpMethod, Items,
+        MediaRange, ParameterIn, Reference, ResolvableApi, ResolvableOperation,
+        ResolvableParameter, ResolvablePathItem, ResolvableResponse, JSON_CODER, JSON_MIME,
+        YAML_CODER, YAML_MIME,
     },
     Schema,
 };
+#[cfg(feature = "v3")]
+use crate::v3;
 use anyhow::Error;
 use heck::{CamelCase, SnekCase};
 use http::{header::HeaderName, HeaderMap};
 use itertools::Itertools;
+use paperclip_core::ValidationError;
 use parking_lot::RwLock;
 use std::collections::{BTreeMap, BTreeSet, HashSet};
 use std::fmt::Debug;
@@ -177,6 +183,7 @@ pub trait Emitter: Sized {
         Some(ObjectVariant {
             name,
             value: value.clone(),
+            ty_path: None,
         })
     }
 
@@ -237,12 +244,22 @@ pub trait Emitter: Sized {
 
     /// Entrypoint for emitter. Given an API spec, generate code
     /// inside Rust modules in the configured working directory.
+    /// Only the operations (and definitions) kept by `EmitterState::filter`
+    /// are generated.
     ///
     /// **NOTE:** Not meant to be overridden.
     fn generate(&self, api: &ResolvableApi<Self::Definition>) -> Result<(), Error> {
         let state = self.state();
         state.reset_internal_fields();
 
+        let filtered;
+        let api = if state.filter.is_empty() {
+            api
+        } else {
+            filtered = state.filter.apply(api);
+            &filtered
+        };
+
         let m = state.get_meta();
         if m.borrow().is_none() {
             let mut meta = CrateMeta::default();
@@ -267,20 +284,22 @@ pub trait Emitter: Sized {
         }
 
         state.set_media_info(api.spec_format, &coders);
+        state.set_security_info(&api.security_definitions);
 
         // Set host and base path.
         if let Some(h) = api.host.as_ref() {
             let mut parts = h.split(':');
             let mut u = state.base_url.borrow_mut();
             if let Some(host) = parts.next() {
-                Host::parse(host).map_err(|e| PaperClipError::InvalidHost(h.into(), e))?;
+                Host::parse(host)
+                    .map_err(|e| PaperClipError::InvalidHost(h.into(), e).at("/host"))?;
                 u.set_host(Some(&host))
                     .expect("expected valid host in URL?");
             }
 
             if let Some(port) = parts.next() {
                 let p = port.parse::<u16>().map_err(|_| {
-                    PaperClipError::InvalidHost(h.into(), url::ParseError::InvalidPort)
+                    PaperClipError::InvalidHost(h.into(), url::ParseError::InvalidPort).at("/host")
                 })?;
                 u.set_port(Some(p)).expect("expected valid port in URL?");
             }
@@ -291,11 +310,14 @@ pub trait Emitter: Sized {
         }
 
         let gen = CodegenEmitter(self);
+        gen.collect_tagged_enums(api)?;
+
         // Generate file contents by accumulating definitions.
         for (name, schema) in &api.definitions {
             debug!("Creating definition {}", name);
             let schema = schema.read();
-            gen.generate_from_definition(&schema)?;
+            gen.generate_from_definition(&schema)
+                .map_err(|e| locate(e, &join_pointer("/definitions", name)))?;
         }
 
         for (path, map) in &api.paths {
@@ -318,6 +340,27 @@ pub trait Emitter: Sized {
         Ok(())
     }
 
+    /// Entrypoint for emitting code from an OpenAPI v3 spec. The resolved spec
+    /// is lowered into its v2 equivalent (request bodies become body or form data
+    /// parameters, the first server becomes the base URL) and the code is generated
+    /// in the same way as `generate`.
+    ///
+    /// **NOTE:** Not meant to be overridden.
+    #[cfg(feature = "v3")]
+    fn generate_v3(&self, api: &v3::ResolvableApi<Self::Definition>) -> Result<(), Error>
+    where
+        Self::Definition: Default,
+    {
+        let api = v3::codegen::lower(api)?;
+        // `generate` doesn't use `schemes` for the base URL (it defaults to HTTPS),
+        // but here, we know that this has come from the server URL.
+        if api.schemes.contains(&models::OperationProtocol::Http) {
+            let _ = self.state().base_url.borrow_mut().set_scheme("http");
+        }
+
+        self.generate(&api).map_err(v3::codegen::relocate)
+    }
+
     /// Builds a schema. This resolves type aliases to known types
     /// and defines/reuses types based on the given context.
     ///
@@ -340,7 +383,7 @@ pub trait Emitter: Sized {
             return Ok(EmittedUnit::Known(ty.to_owned()));
         }
 
-        match def.data_type() {
+        match data_type(def) {
             Some(DataType::Array) => CodegenEmitter(self).emit_array(def, ctx),
             Some(DataType::Object) => CodegenEmitter(self).emit_object(def, ctx),
             Some(DataType::File) => Ok(EmittedUnit::Known(FILE_MARKER.into())),
@@ -389,6 +432,65 @@ where
         self.add_objects_to_path(objects, self.def_mod_path(def)?)
     }
 
+    /// Collects the subtypes (definitions composing them through `allOf`) of
+    /// definitions with discriminators, so that those definitions are emitted
+    /// as tagged enums over their subtypes.
+    fn collect_tagged_enums(&self, api: &ResolvableApi<E::Definition>) -> Result<(), Error> {
+        let mut enums = BTreeMap::new();
+        for (name, schema) in &api.definitions {
+            let schema = schema.read();
+            let discriminator = match schema.discriminator() {
+                Some(d) => d,
+                None => continue,
+            };
+
+            let mut variants = vec![];
+            let mut needs_any = schema.contains_any();
+            for (sub_name, sub) in &api.definitions {
+                let sub = sub.read();
+                let is_subtype = sub
+                    .all_of()
+                    .unwrap_or_default()
+                    .iter()
+                    .any(|s| s.read().name() == Some(name));
+                if !is_subtype {
+                    continue;
+                }
+
+                needs_any |= sub.contains_any();
+                variants.push(ObjectVariant {
+                    name: self.def_name(&sub)?,
+                    value: discriminator.value_for(sub_name).into(),
+                    ty_path: Some(
+                        self.build_def(&sub, DefinitionContext::default())?
+                            .known_type(),
+                    ),
+                });
+            }
+
+            if variants.is_empty() {
+                continue;
+            } else if needs_any {
+                warn!(
+                    "Skipping discriminator of {:?} because it (or its subtypes) has `Any` types.",
+                    name
+                );
+                continue;
+            }
+
+            enums.insert(
+                name.clone(),
+                ObjectContainer::Tagged {
+                    tag: discriminator.property_name().into(),
+                    variants,
+                },
+            );
+        }
+
+        *self.state().tagged_enums.borrow_mut() = enums;
+        Ok(())
+    }
+
     /// Given a bunch of API objects and their module path, add them to the internal state.
     ///
     /// **NOTE:** Should we need to add any `ApiObject` to `EmitterState.def_mods`, this
@@ -487,7 +589,24 @@ where
         def: &E::Definition,
         ctx: DefinitionContext<'_>,
     ) -> Result<Option<EmittedUnit>, Error> {
-        // FIXME: Research on how we can support complex enums.
+        let tagged = def
+            .name()
+            .and_then(|n| self.state().tagged_enums.borrow().get(n).cloned());
+        if let Some(inner) = tagged {
+            if !ctx.define {
+                return CodegenEmitter(self)
+                    .emit_known_object_path(def, ctx)
+                    .map(Some);
+            }
+
+            let mut obj = ApiObject::with_name(self.def_name(def)?);
+            obj.description = def.description().map(String::from);
+            obj.inner = inner;
+            return Ok(Some(EmittedUnit::Objects(vec![obj])));
+        }
+
+        // FIXME: Research on how we can support complex enums (other
+        // than the ones with discriminators).
         if def.data_type().is_some() && matching_unit_type(def.format(), def.data_type()).is_none()
         {
             return Ok(None);
@@ -626,6 +745,7 @@ where
         })?;
         let mut obj = ApiObject::with_name(&name);
         obj.description = def.description().map(String::from);
+        obj.example = def.example().cloned();
 
         // If we don't have any parents and there's a name for this object,
         // then it's the root object - add the name to parents before checking
@@ -637,9 +757,11 @@ where
 
         // Anonymous objects that we've collected along the way.
         let mut objects = vec![];
+        let composed = Composed::from_schema(def, &self.state().tagged_enums.borrow());
 
-        if let Some(props) = def.properties() {
-            props
+        if !composed.properties.is_empty() {
+            composed
+                .properties
                 .iter()
                 .try_for_each(|(name, prop)| -> Result<(), Error> {
                     let schema = prop.read();
@@ -650,11 +772,9 @@ where
                     obj.fields_mut().push(ObjectField {
                         name: name.clone(),
                         description: prop.get_description(),
+                        example: prop.get_example(),
                         ty_path,
-                        is_required: def
-                            .required_properties()
-                            .map(|s| s.contains(name))
-                            .unwrap_or(false),
+                        is_required: composed.required.contains(name),
                         needs_any: schema.contains_any(),
                         boxed: schema.is_cyclic(),
                         child_req_fields: self.children_requirements(&schema),
@@ -669,11 +789,12 @@ where
 
             // If additional properties are enabled, then collect them into
             // a separate field for flattening.
-            if let Some(Either::Left(true)) = def.additional_properties() {
+            if composed.extra_props {
                 obj.fields_mut().push(ObjectField {
                     name: EXTRA_PROPS_FIELD.into(),
                     ty_path: "std::collections::BTreeMap<String, Any>".into(),
                     description: None,
+                    example: None,
                     is_required: false,
                     needs_any: true,
                     boxed: false,
@@ -690,13 +811,23 @@ where
     ///
     /// See `ObjectField.children_req` field for what it means.
     fn children_requirements(&self, schema: &E::Definition) -> Vec<String> {
-        match schema.data_type() {
+        let tagged = self.state().tagged_enums.borrow();
+        let is_tagged = schema
+            .name()
+            .map(|n| tagged.contains_key(n))
+            .unwrap_or(false);
+        match data_type(schema) {
+            // Tagged enums don't have builders.
+            Some(DataType::Object) if is_tagged => (),
             Some(DataType::Object) => {
                 if let Some(Either::Right(s)) = schema.additional_properties() {
                     return self.children_requirements(&s.read());
-                } else if let Some(s) = schema.required_properties() {
-                    return s.iter().cloned().collect();
                 }
+
+                return Composed::from_schema(schema, &tagged)
+                    .required
+                    .into_iter()
+                    .collect();
             }
             Some(DataType::Array) => {
                 if let Some(s) = schema.items() {
@@ -727,17 +858,21 @@ where
     /// Given a path and an operation map, collect the stuff required
     /// for generating builders later.
     fn collect(mut self) -> Result<(), Error> {
-        self.validate_path_and_add_params()?;
+        let pointer = join_pointer("/paths", self.path);
+        self.validate_path_and_add_params()
+            .map_err(|e| e.at(&pointer))?;
         debug!("Collecting builder requirement for {:?}", self.path);
 
         // Collect all the parameters local to some API call.
-        let (unused_params, _) = self.collect_parameters(&self.map.parameters)?;
+        let (unused_params, _) =
+            self.collect_parameters(&self.map.parameters, &join_pointer(&pointer, "parameters"))?;
         // FIXME: What if a body is "required" globally (for all operations)?
         // This means, operations can override the body with some other schema
         // and we may need to map it to the appropriate builders.
 
         for (&meth, op) in &self.map.methods {
-            self.collect_from_operation(meth, op, &unused_params)?;
+            let op_pointer = join_pointer(&pointer, &meth.to_string().to_lowercase());
+            self.collect_from_operation(meth, op, &unused_params, &op_pointer)?;
         }
 
         // FIXME: If none of the parameters (local to operation or global) specify
@@ -760,6 +895,7 @@ where
                 self.path.into(),
                 self.template_params,
             )
+            .at(pointer)
             .into());
         }
 
@@ -793,8 +929,10 @@ where
         meth: HttpMethod,
         op: &ResolvableOperation<E::Definition>,
         unused_params: &[Parameter],
+        pointer: &str,
     ) -> Result<(), Error> {
-        let (mut params, schema_path) = self.collect_parameters(&op.parameters)?;
+        let (mut params, schema_path) =
+            self.collect_parameters(&op.parameters, &join_pointer(pointer, "parameters"))?;
         // If we have unused params which don't exist in the method-specific
         // params (which take higher precedence), then we can copy those inside.
         for global_param in unused_params {
@@ -823,11 +961,12 @@ where
             })
             .collect();
 
+        let security = self.collect_security(op, pointer)?;
         // If there's a matching object, add the params to its operation.
         if let Some(pat) = schema_path.as_ref() {
-            self.bind_schema_to_operation(pat, meth, op, params)?;
+            self.bind_schema_to_operation(pat, meth, op, params, security)?;
         } else {
-            self.bind_operation_blindly(meth, op, params)?;
+            self.bind_operation_blindly(meth, op, params, security)?;
         }
 
         Ok(())
@@ -893,13 +1032,16 @@ where
     fn collect_parameters(
         &mut self,
         obj_params: &[Either<Reference, ResolvableParameter<E::Definition>>],
+        pointer: &str,
     ) -> Result<(Vec<Parameter>, Option<PathBuf>), Error> {
         let def_mods = self.emitter.state().def_mods.borrow();
         let mut schema_path = None;
         let mut params = vec![];
-        for param in obj_params {
+        for (i, param) in obj_params.iter().enumerate() {
             let p = param.read();
-            p.check(self.path)?; // validate the parameter
+            // validate the parameter
+            p.check(self.path)
+                .map_err(|e| e.at(format!("{}/{}", pointer, i)))?;
 
             if let Some(def) = p.schema.as_ref() {
                 // If a schema exists, then get its path for later use.
@@ -964,6 +1106,7 @@ where
         meth: HttpMethod,
         op: &ResolvableOperation<E::Definition>,
         params: Vec<Parameter>,
+        security: Vec<Vec<OpSecurity>>,
     ) -> Result<(), Error> {
         trace!(
             "Binding {:?} operation in path {:?} to module {:?}",
@@ -1009,6 +1152,7 @@ where
                 body_required: true,
                 encoding: self.get_coder(op.consumes.as_ref(), &self.api.consumes),
                 decoding: self.get_coder(op.produces.as_ref(), &self.api.produces),
+                security,
             },
         );
 
@@ -1022,6 +1166,7 @@ where
         meth: HttpMethod,
         op: &ResolvableOperation<E::Definition>,
         params: Vec<Parameter>,
+        security: Vec<Vec<OpSecurity>>,
     ) -> Result<(), Error> {
         // Let's try from the response maybe...
         let s = match Self::get_2xx_response_schema(&op) {
@@ -1037,12 +1182,24 @@ where
 
         let schema = &*s.read();
         let state = self.emitter.state();
-        let listable = schema.items().and_then(|s| s.read().data_type()) == Some(DataType::Object);
+        // Tagged enums don't have builders, so they can't be bound to operations.
+        let is_tagged = |s: &E::Definition| {
+            s.name()
+                .map(|n| state.tagged_enums.borrow().contains_key(n))
+                .unwrap_or(false)
+        };
+        let listable = schema
+            .items()
+            .map(|s| {
+                let s = s.read();
+                data_type(&*s) == Some(DataType::Object) && !is_tagged(&s)
+            })
+            .unwrap_or(false);
 
         let mut unknown_schema_context = None;
-        let s = match schema.data_type() {
+        let s = match data_type(schema) {
             // We can deal with object responses.
-            Some(DataType::Object) => s.clone(),
+            Some(DataType::Object) if !is_tagged(schema) => s.clone(),
             // We can also deal with array of objects by mapping
             // the operation to that object.
             _ if listable => Clone::clone(&**schema.items().unwrap()),
@@ -1115,12 +1272,49 @@ where
                 },
                 encoding: self.get_coder(op.consumes.as_ref(), &self.api.consumes),
                 decoding: self.get_coder(op.produces.as_ref(), &self.api.produces),
+                security,
             },
         );
 
         Ok(())
     }
 
+    /// Collects the security requirements (along with their schemes) for some
+    /// operation. Operation-level requirements override the global ones.
+    ///
+    /// Requirements with unknown or invalid schemes are errors, because
+    /// skipping them would leave the operation unauthenticated.
+    fn collect_security(
+        &self,
+        op: &ResolvableOperation<E::Definition>,
+        pointer: &str,
+    ) -> Result<Vec<Vec<OpSecurity>>, ValidationError> {
+        let (requirements, pointer) = match op.security.as_ref() {
+            Some(r) => (r, join_pointer(pointer, "security")),
+            None => (&self.api.security, pointer.to_owned()),
+        };
+
+        requirements
+            .iter()
+            .map(|req| {
+                req.iter()
+                    .map(|(name, scopes)| {
+                        let scheme = self.api.security_definitions.get(name).ok_or_else(|| {
+                            ValidationError::UndefinedSecurityScheme(name.clone())
+                        })?;
+                        scheme.check(name)?;
+                        Ok(OpSecurity {
+                            name: name.clone(),
+                            scheme: scheme.clone(),
+                            scopes: scopes.clone(),
+                        })
+                    })
+                    .collect()
+            })
+            .collect::<Result<_, _>>()
+            .map_err(|e: ValidationError| e.at(pointer))
+    }
+
     /// Returns the first 2xx response schema in this operation.
     ///
     /// **NOTE:** This assumes that 2xx response schemas are the same for an operation.
@@ -1169,6 +1363,18 @@ where
 
 /// Ensures that a parameter type is either a simple type or an array
 /// and returns the resolved Rust type.
+/// Sets the JSON pointer to the node (in the spec) for the given error
+/// (if it's a validation or codegen error without a location).
+fn locate(err: Error, pointer: &str) -> Error {
+    match err.downcast::<PaperClipError>() {
+        Ok(e) => e.at(pointer).into(),
+        Err(err) => match err.downcast::<ValidationError>() {
+            Ok(e) => e.at(pointer).into(),
+            Err(err) => err,
+        },
+    }
+}
+
 fn resolve_parameter_type(
     dt: Option<DataType>,
     dt_fmt: Option<&DataTypeFormat>,
@@ -1272,3 +1478,78 @@ fn validate_collection_format(
         }
     }
 }
+
+/// Data type of the given schema. Composed schemas (`allOf`) are
+/// objects, even if they don't specify the type.
+fn data_type<S: Schema>(schema: &S) -> Option<DataType> {
+    schema
+        .data_type()
+        .or_else(|| schema.all_of().map(|_| DataType::Object))
+}
+
+/// Properties of a schema, flattened along with the properties of
+/// the schemas composed into it (`allOf`). Discriminator properties
+/// of the composed schemas emitted as tagged enums are skipped, because
+/// they're handled by those enums.
+struct Composed<S> {
+    properties: BTreeMap<String, models::Resolvable<S>>,
+    required: BTreeSet<String>,
+    /// Whether additional properties are allowed.
+    extra_props: bool,
+}
+
+impl<S: Schema> Composed<S> {
+    fn from_schema(schema: &S, tagged: &BTreeMap<String, ObjectContainer>) -> Self {
+        let mut composed = Composed {
+            properties: BTreeMap::new(),
+            required: BTreeSet::new(),
+            extra_props: false,
+        };
+
+        composed.add(schema, tagged, &mut vec![]);
+        composed
+    }
+
+    /// Adds the properties of the given schema. Names of the (resolved)
+    /// schemas we've passed through are tracked for breaking cycles.
+    fn add(
+        &mut self,
+        schema: &S,
+        tagged: &BTreeMap<String, ObjectContainer>,
+        names: &mut Vec<String>,
+    ) {
+        if let Some(name) = schema.name() {
+            if names.iter().any(|n| n == name) {
+                return;
+            }
+
+            names.push(name.into());
+        }
+
+        // Composed schemas go first, so that the schema's own
+        // properties override them.
+        for s in schema.all_of().into_iter().flatten() {
+            let s = s.read();
+            self.add(&*s, tagged, names);
+            if let Some(ObjectContainer::Tagged { tag, .. }) = s.name().and_then(|n| tagged.get(n))
+            {
+                self.properties.remove(tag);
+                self.required.remove(tag);
+            }
+        }
+
+        if let Some(props) = schema.properties() {
+            for (name, prop) in props {
+                self.properties.insert(name.clone(), prop.clone());
+            }
+        }
+
+        if let Some(req) = schema.required_properties() {
+            self.required.extend(req.iter().cloned());
+        }
+
+        if let Some(Either::Left(true)) = schema.additional_properties() {
+            self.extra_props = true;
+        }
+    }
+}
@@ -1,7 +1,9 @@
 use super::emitter::ANY_GENERIC_PARAMETER;
 use super::object::{ApiObject, ApiObjectBuilder, Response, StructField, TypeParameters};
 use super::RUST_KEYWORDS;
-use crate::v2::models::{CollectionFormat, ParameterIn, JSON_CODER, JSON_MIME};
+use crate::v2::models::{
+    ApiKeyIn, CollectionFormat, ParameterIn, SecuritySchemeType, JSON_CODER, JSON_MIME,
+};
 use heck::{CamelCase, KebabCase, SnekCase};
 
 use std::fmt::{self, Display, Write};
@@ -74,6 +76,7 @@ impl ApiObject {
                         global_params: &path_ops.params,
                         local_params: &req.params,
                         needs_any: needs_any && req.body_required,
+                        security: &req.security,
                         response: Response {
                             ty_path: req.response.ty_path.as_deref(),
                             contains_any: req.response.contains_any,
@@ -701,6 +704,7 @@ impl<'a, 'b> SendableCodegen<'a, 'b> {
 
         f.write_str(";\n\n    const METHOD: http::Method = http::Method::")?;
         f.write_str(&method.to_string().to_uppercase())?;
+        self.write_security(f)?;
         f.write_str(";\n\n    fn rel_path(&self) -> std::borrow::Cow<'static, str> {\n        ")?;
 
         self.builder
@@ -992,6 +996,59 @@ impl<'a, 'b> SendableCodegen<'a, 'b> {
         );
     }
 
+    /// Writes the security requirements for this operation (if any).
+    fn write_security<F>(&self, f: &mut F) -> fmt::Result
+    where
+        F: Write,
+    {
+        if self.builder.security.is_empty() {
+            return Ok(());
+        }
+
+        let prefix = self.builder.helper_module_prefix;
+        write!(
+            f,
+            ";\n\n    const SECURITY: &'static [{}client::SecurityRequirement] = &[",
+            prefix
+        )?;
+
+        for req in self.builder.security {
+            f.write_str("\n        &[")?;
+            for (i, s) in req.iter().enumerate() {
+                if i > 0 {
+                    f.write_str(", ")?;
+                }
+
+                let scheme = match (s.scheme.type_, s.scheme.in_) {
+                    (SecuritySchemeType::Basic, _) => "Basic".into(),
+                    (SecuritySchemeType::ApiKey, Some(ApiKeyIn::Header)) => {
+                        format!(
+                            "ApiKeyHeader({:?})",
+                            s.scheme.name.as_deref().unwrap_or_default()
+                        )
+                    }
+                    (SecuritySchemeType::ApiKey, _) => {
+                        format!(
+                            "ApiKeyQuery({:?})",
+                            s.scheme.name.as_deref().unwrap_or_default()
+                        )
+                    }
+                    (SecuritySchemeType::OAuth2, _) => "OAuth2".into(),
+                };
+
+                write!(
+                    f,
+                    "({:?}, {}client::SecurityScheme::{}, &{:?})",
+                    s.name, prefix, scheme, s.scopes
+                )?;
+            }
+
+            f.write_str("],")?;
+        }
+
+        f.write_str("\n    ]")
+    }
+
     /// We have determined that we have to override the default `modify` method.
     fn write_modify_method<F>(&mut self, f: &mut F, accepted_range: Option<String>) -> fmt::Result
     where
@@ -7,7 +7,7 @@ pub use super::impls::{ApiObjectBuilderImpl, ApiObjectImpl};
 
 use super::emitter::{ANY_GENERIC_PARAMETER, EXTRA_PROPS_FIELD, FILE_MARKER};
 use super::RUST_KEYWORDS;
-use crate::v2::models::{Coder, CollectionFormat, HttpMethod, ParameterIn};
+use crate::v2::models::{Coder, CollectionFormat, HttpMethod, ParameterIn, SecurityScheme};
 use heck::{CamelCase, SnekCase};
 use once_cell::sync::Lazy;
 use regex::{Captures, Regex};
@@ -17,6 +17,9 @@ use std::fmt::{self, Display, Write};
 use std::iter;
 use std::sync::Arc;
 
+/// Variant for unknown discriminator values in tagged enums.
+pub(super) const TAGGED_ENUM_FALLBACK: &str = "Unknown";
+
 /// Regex for appropriate escaping in docs.
 static DOC_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"\[|\]").expect("invalid doc regex?"));
 
@@ -27,6 +30,8 @@ pub struct ApiObject {
     pub name: String,
     /// Description for this object (if any), to be used for docs.
     pub description: Option<String>,
+    /// Example for this object (if any), to be used for docs.
+    pub example: Option<serde_json::Value>,
     /// Path to this object from (generated) root module.
     pub path: String,
     /// Fields/variants based on whether this is a struct/enum.
@@ -58,7 +63,9 @@ impl ApiObject {
     /// is not an enum.
     pub fn variants_mut(&mut self) -> &mut Vec<ObjectVariant> {
         match &mut self.inner {
-            ObjectContainer::Enum { variants, .. } => variants,
+            ObjectContainer::Enum { variants, .. } | ObjectContainer::Tagged { variants, .. } => {
+                variants
+            }
             _ => panic!("cannot obtain fields for enum type"),
         }
     }
@@ -67,7 +74,9 @@ impl ApiObject {
     /// is not an enum.
     pub fn variants(&self) -> &[ObjectVariant] {
         match &self.inner {
-            ObjectContainer::Enum { variants, .. } => variants,
+            ObjectContainer::Enum { variants, .. } | ObjectContainer::Tagged { variants, .. } => {
+                variants
+            }
             _ => panic!("cannot obtain fields for enum type"),
         }
     }
@@ -86,6 +95,13 @@ pub enum ObjectContainer {
         /// serde completely.
         is_string: bool,
     },
+    /// Enum over the subtypes of a definition with a discriminator.
+    Tagged {
+        /// Name of the property holding the discriminator value.
+        tag: String,
+        /// Variants for the subtypes (with the discriminator values).
+        variants: Vec<ObjectVariant>,
+    },
     Struct {
         /// Fields if it's a struct.
         fields: Vec<ObjectField>,
@@ -96,7 +112,7 @@ impl ObjectContainer {
     /// Returns whether this object is an enum.
     pub fn is_enum(&self) -> bool {
         match self {
-            ObjectContainer::Enum { .. } => true,
+            ObjectContainer::Enum { .. } | ObjectContainer::Tagged { .. } => true,
             _ => false,
         }
     }
@@ -147,6 +163,20 @@ pub struct OpRequirement {
     /// when objects make use of `Any` type. If there's no coder, then JSON
     /// encoding is assumed.
     pub decoding: Option<(String, Arc<Coder>)>,
+    /// Security requirements for this operation (any one of which should
+    /// be satisfied by the client).
+    pub security: Vec<Vec<OpSecurity>>,
+}
+
+/// Security scheme (and the scopes) needed by an operation.
+#[derive(Debug, Clone)]
+pub struct OpSecurity {
+    /// Name of the scheme in security definitions.
+    pub name: String,
+    /// The actual scheme.
+    pub scheme: SecurityScheme,
+    /// Scopes needed from this scheme (for OAuth2).
+    pub scopes: Vec<String>,
 }
 
 #[derive(Default, Debug, Clone)]
@@ -199,6 +229,8 @@ pub struct ObjectVariant {
     /// Value of this variant (if any). Note that this is only applicable
     /// for simple enums, and it's ignored entirely for string enums.
     pub value: serde_json::Value,
+    /// Type of the value held by this variant (for tagged enums).
+    pub ty_path: Option<String>,
 }
 
 /// Represents a struct field.
@@ -210,6 +242,8 @@ pub struct ObjectField {
     pub ty_path: String,
     /// Description of this operation (if any), to be used for docs.
     pub description: Option<String>,
+    /// Example for this field (if any), to be used for docs.
+    pub example: Option<serde_json::Value>,
     /// Whether this field is required (i.e., not optional).
     pub is_required: bool,
     /// Whether this field's type "is" or "has" an `Any` type.
@@ -286,6 +320,47 @@ impl ApiObject {
         Ok(())
     }
 
+    /// Writes the given example (if any) as a JSON block in Rust documentation
+    /// into the given formatter. This is expected to follow the docs written
+    /// by `write_docs` (if there were any).
+    pub(super) fn write_example<F>(
+        example: Option<&serde_json::Value>,
+        has_docs: bool,
+        f: &mut F,
+        levels: usize,
+    ) -> fmt::Result
+    where
+        F: Write,
+    {
+        let example = match example.and_then(|e| serde_json::to_string_pretty(e).ok()) {
+            Some(e) => e,
+            None => return Ok(()),
+        };
+
+        let indent = " ".repeat(levels * 4);
+        if has_docs {
+            f.write_str(&indent)?;
+            f.write_str("///")?;
+        }
+
+        let lines = ["Example:", "", "```json"].iter().copied();
+        lines
+            .chain(example.lines())
+            .chain(iter::once("```"))
+            .try_for_each(|line| -> fmt::Result {
+                f.write_str("\n")?;
+                f.write_str(&indent)?;
+                f.write_str("///")?;
+                if !line.is_empty() {
+                    f.write_str(" ")?;
+                    f.write_str(line)?;
+                }
+
+                Ok(())
+            })?;
+        f.write_str("\n")
+    }
+
     /// Returns whether this type is simple (i.e., not an object defined by us).
     #[inline]
     pub(super) fn is_simple_type(ty: &str) -> bool {
@@ -365,6 +440,8 @@ pub(super) struct ApiObjectBuilder<'a> {
     pub local_params: &'a [Parameter],
     /// Whether this builder is generic over `Any` type.
     pub needs_any: bool,
+    /// Security requirements for the operation.
+    pub security: &'a [Vec<OpSecurity>],
 }
 
 /// The property we're dealing with.
@@ -896,6 +973,9 @@ impl Display for ApiObject {
             return self.write_enum(f);
         }
 
+        let has_docs = self.description.is_some();
+        ApiObject::write_example(self.example.as_ref(), has_docs, f, 0)?;
+
         f.write_str("#[derive(Debug, Default, Clone, Serialize, Deserialize)]\npub struct ")?;
         f.write_str(&self.name)?;
         if !self.inner.is_enum() && self.fields().iter().any(|f| f.needs_any) {
@@ -914,7 +994,9 @@ impl Display for ApiObject {
                 }
 
                 ApiObject::write_docs(field.description.as_ref(), f, 1)?;
-                if field.description.is_none() {
+                let has_docs = field.description.is_some();
+                ApiObject::write_example(field.example.as_ref(), has_docs, f, 1)?;
+                if !has_docs && field.example.is_none() {
                     f.write_str("\n")?;
                 }
 
@@ -970,6 +1052,10 @@ impl ApiObject {
     where
         F: fmt::Write,
     {
+        if let ObjectContainer::Tagged { tag, .. } = &self.inner {
+            return self.write_tagged_enum(tag, f);
+        }
+
         let is_string = self.inner.is_string_enum();
         f.write_str("#[derive(Debug, Clone")?;
         if is_string {
@@ -1022,6 +1108,52 @@ impl ApiObject {
     }
 }
 
+impl ApiObject {
+    /// Writes an (internally) tagged enum over the subtypes of some object.
+    /// Unknown discriminator values are deserialized into a fallback variant.
+    fn write_tagged_enum<F>(&self, tag: &str, f: &mut F) -> fmt::Result
+    where
+        F: fmt::Write,
+    {
+        write!(
+            f,
+            "#[derive(Debug, Clone, Serialize, Deserialize)]\n#[serde(tag = {:?})]\npub enum {} {{",
+            tag, &self.name
+        )?;
+
+        self.variants().iter().try_for_each(|var| {
+            if let serde_json::Value::String(s) = &var.value {
+                if s != &var.name {
+                    write!(f, "\n    #[serde(rename = {:?})]", s)?;
+                }
+            }
+
+            f.write_str("\n    ")?;
+            f.write_str(&var.name)?;
+            f.write_str("(")?;
+            f.write_str(var.ty_path.as_deref().unwrap_or_default())?;
+            f.write_str("),")
+        })?;
+
+        write!(
+            f,
+            "
+    /// Unknown discriminator value.
+    #[serde(other)]
+    {fallback},
+}}
+impl Default for {name} {{
+    fn default() -> Self {{
+        {name}::{fallback}
+    }}
+}}
+",
+            name = &self.name,
+            fallback = TAGGED_ENUM_FALLBACK
+        )
+    }
+}
+
 /// Abstraction for implementing Serialize/Deserialize mechanism
 /// for non-string enums.
 struct EnumSerdeImpl<'a> {
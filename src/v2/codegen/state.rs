@@ -1,10 +1,11 @@
+use super::object::{ApiObject, ObjectContainer};
 use super::template::{self, TEMPLATE};
-use super::{object::ApiObject, CrateMeta, EmitMode};
+use super::{CrateMeta, EmitMode};
 use crate::error::PaperClipError;
-use crate::v2::models::{Coders, SpecFormat};
+use crate::v2::filter::Filter;
+use crate::v2::models::{Coders, OAuth2Flow, SecurityScheme, SpecFormat};
 use anyhow::Error;
 use heck::CamelCase;
-#[cfg(feature = "cli")]
 use heck::SnekCase;
 use itertools::Itertools;
 use url::Url;
@@ -29,6 +30,9 @@ pub struct EmitterState {
     pub ns_sep: &'static str,
     /// Module prefix for using in generated code.
     pub mod_prefix: &'static str,
+    /// Filter applied to the spec before generating code. Definitions which
+    /// aren't used by the kept operations are skipped.
+    pub filter: Filter,
 
     /* MARK: Private fields. */
     /// Base URL for the API.
@@ -47,8 +51,12 @@ pub struct EmitterState {
     pub(super) def_mods: RefCell<BTreeMap<PathBuf, Vec<ApiObject>>>,
     /// Relative paths
     pub(super) rel_paths: RefCell<HashSet<String>>,
+    /// Tagged enums for definitions with discriminators (by definition names).
+    pub(super) tagged_enums: RefCell<BTreeMap<String, ObjectContainer>>,
     /// Media ranges and the corresponding decoders we've registered.
     media_coders: RefCell<Vec<MediaCoder>>,
+    /// OAuth2 schemes for which we generate token providers.
+    oauth2_providers: RefCell<Vec<OAuth2Provider>>,
     /// Unit types used by builders.
     unit_types: RefCell<BTreeSet<String>>,
     /// Generated CLI YAML for clap.
@@ -90,6 +98,7 @@ impl EmitterState {
         *self.mod_children.borrow_mut() = Default::default();
         *self.def_mods.borrow_mut() = Default::default();
         *self.rel_paths.borrow_mut() = Default::default();
+        *self.tagged_enums.borrow_mut() = Default::default();
         *self.unit_types.borrow_mut() = Default::default();
         *self.cli_yaml.borrow_mut() = Default::default();
         *self.cli_match_arms.borrow_mut() = Default::default();
@@ -112,6 +121,46 @@ impl EmitterState {
             .collect();
     }
 
+    /// Sets the `oauth2` schemes (with token URLs) for which token
+    /// providers should be generated in the client module.
+    ///
+    /// Constructors are named after the schemes. If some schemes end up with
+    /// the same name (say, `petstoreApp` and `petstore_app`), then the later
+    /// ones get a numeric suffix (`for_petstore_app_2`).
+    pub(crate) fn set_security_info(&self, schemes: &BTreeMap<String, SecurityScheme>) {
+        let mut fn_names = HashSet::new();
+        *self.oauth2_providers.borrow_mut() = schemes
+            .iter()
+            .filter_map(|(name, scheme)| {
+                let token_url = scheme.token_url.as_ref()?;
+                if scheme.check(name).is_err() {
+                    return None;
+                }
+
+                let is_password = match scheme.flow? {
+                    OAuth2Flow::Application => false,
+                    OAuth2Flow::Password => true,
+                    _ => return None,
+                };
+
+                let base = format!("for_{}", name.to_snek_case());
+                let mut fn_name = base.clone();
+                let mut suffix = 1;
+                while !fn_names.insert(fn_name.clone()) {
+                    suffix += 1;
+                    fn_name = format!("{}_{}", base, suffix);
+                }
+
+                Some(OAuth2Provider {
+                    name: format!("{:?}", name),
+                    fn_name,
+                    token_url: format!("{:?}", token_url),
+                    is_password,
+                })
+            })
+            .collect();
+    }
+
     /// Once the emitter has generated the struct definitions,
     /// we can call this method to generate the module declarations
     /// from root.
@@ -290,6 +339,7 @@ pub mod util {
             &ClientModContext {
                 mod_prefix: &self.normalized_mod_prefix(),
                 media_coders: &*self.media_coders.borrow(),
+                oauth2_providers: &self.oauth2_providers.borrow(),
                 base_url: self.base_url.borrow().as_str(),
             },
         )?;
@@ -529,16 +579,19 @@ impl Default for EmitterState {
             working_dir: PathBuf::from("."),
             mod_prefix: "crate::",
             ns_sep: ".",
+            filter: Filter::default(),
             #[cfg(feature = "cli")]
             crate_meta: Rc::new(RefCell::new(None)),
             base_url: RefCell::new("https://example.com".parse().expect("invalid URL?")),
             def_mods: RefCell::new(BTreeMap::new()),
             rel_paths: RefCell::new(HashSet::new()),
+            tagged_enums: RefCell::new(BTreeMap::new()),
             mod_children: RefCell::new(HashMap::new()),
             unit_types: RefCell::new(BTreeSet::new()),
             cli_yaml: RefCell::new(String::new()),
             cli_match_arms: RefCell::new(String::new()),
             media_coders: RefCell::new(vec![]),
+            oauth2_providers: RefCell::new(vec![]),
             default_encoding: RefCell::new(SpecFormat::Json),
         }
     }
@@ -578,6 +631,7 @@ struct ClientModContext<'a> {
     base_url: &'a str,
     mod_prefix: &'a str,
     media_coders: &'a [MediaCoder],
+    oauth2_providers: &'a [OAuth2Provider],
 }
 
 #[derive(Debug, serde::Serialize)]
@@ -588,6 +642,16 @@ struct MediaCoder {
     error_ty_path: String,
 }
 
+#[derive(Debug, serde::Serialize)]
+struct OAuth2Provider {
+    /// Name of the scheme (quoted, for docs).
+    name: String,
+    fn_name: String,
+    /// Token URL as a Rust string literal.
+    token_url: String,
+    is_password: bool,
+}
+
 #[derive(serde::Serialize)]
 struct ClapYamlContext<'a> {
     name: &'a str,
@@ -0,0 +1,739 @@
+//! Changes between two versions of a v2 spec.
+//!
+//! [`diff`](fn.diff.html) compares two resolved specs and classifies the
+//! changes as breaking (existing clients may fail) or non-breaking, from
+//! which the semver bump for the new version can be suggested.
+//!
+//! ```rust
+//! use paperclip::v2::{self, diff, DefaultSchema, ResolvableApi};
+//!
+//! let old: ResolvableApi<DefaultSchema> = v2::from_str("
+//! swagger: '2.0'
+//! info: {title: Pets, version: 1.2.0}
+//! paths:
+//!   /pets:
+//!     get:
+//!       responses:
+//!         '200': {description: OK}
+//!   /stores:
+//!     get:
+//!       responses:
+//!         '200': {description: OK}
+//! ").unwrap();
+//! let new: ResolvableApi<DefaultSchema> = v2::from_str("
+//! swagger: '2.0'
+//! info: {title: Pets, version: 1.3.0}
+//! paths:
+//!   /pets:
+//!     get:
+//!       responses:
+//!         '200': {description: OK}
+//! ").unwrap();
+//!
+//! let diff = diff::diff(&old.resolve().unwrap(), &new.resolve().unwrap());
+//! assert!(diff.is_breaking());
+//! assert_eq!(diff.changes[0].message, "Path \"/stores\" was removed");
+//! assert_eq!(diff.next_version(&"1.2.0".parse().unwrap()).to_string(), "2.0.0");
+//! ```
+
+use super::models::{
+    DataType, DataTypeFormat, Either, HttpMethod, Items, ParameterIn, Resolvable, ResolvableApi,
+    ResolvableOperation, ResolvableParameter, ResolvablePathItem,
+};
+use super::Schema;
+use crate::util::join_pointer;
+use semver::Version;
+use serde_derive::Serialize;
+use serde_json::Value;
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::{self, Display};
+
+/// Whether a change breaks existing clients.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Compatibility {
+    NonBreaking,
+    Breaking,
+}
+
+/// Change between two versions of a spec.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Change {
+    pub compatibility: Compatibility,
+    /// JSON pointer to the changed node. This is in the old spec for
+    /// removals, and in the new spec otherwise. Pointers go through
+    /// references (say, `/paths/~1pets/get/responses/200/schema/properties/id`),
+    /// so that it's clear which operations are affected.
+    pub pointer: String,
+    /// Description of the change.
+    pub message: String,
+}
+
+/// Semver bump for a new version of the spec.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Bump {
+    Minor,
+    Major,
+}
+
+impl Display for Bump {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Bump::Minor => "minor",
+            Bump::Major => "major",
+        })
+    }
+}
+
+/// Changes between two versions of a spec.
+#[derive(Debug, Clone, Default)]
+pub struct Diff {
+    pub changes: Vec<Change>,
+}
+
+impl Diff {
+    /// Whether any of the changes are breaking.
+    pub fn is_breaking(&self) -> bool {
+        self.breaking().next().is_some()
+    }
+
+    /// Changes which break existing clients.
+    pub fn breaking(&self) -> impl Iterator<Item = &Change> {
+        self.with_compatibility(Compatibility::Breaking)
+    }
+
+    /// Changes which don't break existing clients.
+    pub fn non_breaking(&self) -> impl Iterator<Item = &Change> {
+        self.with_compatibility(Compatibility::NonBreaking)
+    }
+
+    /// Suggested bump: major for breaking changes, minor for other
+    /// changes and none if nothing has changed.
+    pub fn bump(&self) -> Option<Bump> {
+        if self.is_breaking() {
+            Some(Bump::Major)
+        } else if !self.changes.is_empty() {
+            Some(Bump::Minor)
+        } else {
+            None
+        }
+    }
+
+    /// Suggested version after the given (old) version. For `0.x` versions,
+    /// the bumps are shifted (breaking changes bump the minor version and
+    /// other changes bump the patch version), like Cargo. The version is
+    /// returned as it is if nothing has changed.
+    pub fn next_version(&self, version: &Version) -> Version {
+        let mut version = version.clone();
+        match (self.bump(), version.major) {
+            (Some(Bump::Major), 0) => version.increment_minor(),
+            (Some(Bump::Major), _) => version.increment_major(),
+            (Some(Bump::Minor), 0) => version.increment_patch(),
+            (Some(Bump::Minor), _) => version.increment_minor(),
+            (None, _) => (),
+        }
+
+        version
+    }
+
+    fn with_compatibility(&self, compatibility: Compatibility) -> impl Iterator<Item = &Change> {
+        self.changes
+            .iter()
+            .filter(move |c| c.compatibility == compatibility)
+    }
+}
+
+/// Compares the given (resolved) specs.
+pub fn diff<S: Schema>(old: &ResolvableApi<S>, new: &ResolvableApi<S>) -> Diff {
+    let mut differ = Differ {
+        diff: Diff::default(),
+    };
+
+    differ.paths(old, new);
+    differ.diff
+}
+
+/// Effective parameters of an operation (keyed by their location and
+/// name), along with their pointers.
+type Parameters<S> = BTreeMap<(ParameterIn, String), (String, ResolvableParameter<S>)>;
+
+/// Which way the data flows (which decides whether some changes are breaking).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Direction {
+    Request,
+    Response,
+}
+
+struct Differ {
+    diff: Diff,
+}
+
+impl Differ {
+    fn change<M: Into<String>>(&mut self, compatibility: Compatibility, pointer: &str, message: M) {
+        self.diff.changes.push(Change {
+            compatibility,
+            pointer: pointer.into(),
+            message: message.into(),
+        });
+    }
+
+    fn breaking<M: Into<String>>(&mut self, pointer: &str, message: M) {
+        self.change(Compatibility::Breaking, pointer, message)
+    }
+
+    fn non_breaking<M: Into<String>>(&mut self, pointer: &str, message: M) {
+        self.change(Compatibility::NonBreaking, pointer, message)
+    }
+
+    /// Compares the paths. Paths are matched regardless of the names
+    /// of the parameters in their templates.
+    fn paths<S: Schema>(&mut self, old: &ResolvableApi<S>, new: &ResolvableApi<S>) {
+        let (old_paths, new_paths) = (templates(old), templates(new));
+        for (template, old_path) in &old_paths {
+            match new_paths.get(template) {
+                Some(new_path) => self.operations(
+                    (old_path, &old.paths[*old_path]),
+                    (new_path, &new.paths[*new_path]),
+                ),
+                None => self.breaking(
+                    &join_pointer("/paths", old_path),
+                    format!("Path {:?} was removed", old_path),
+                ),
+            }
+        }
+
+        for (template, new_path) in &new_paths {
+            if !old_paths.contains_key(template) {
+                self.non_breaking(
+                    &join_pointer("/paths", new_path),
+                    format!("Path {:?} was added", new_path),
+                );
+            }
+        }
+    }
+
+    /// Compares the operations in matching paths.
+    fn operations<S: Schema>(
+        &mut self,
+        (old_path, old): (&str, &ResolvablePathItem<S>),
+        (new_path, new): (&str, &ResolvablePathItem<S>),
+    ) {
+        let pointer = |path: &str, method: &HttpMethod| {
+            join_pointer(
+                &join_pointer("/paths", path),
+                &method.to_string().to_lowercase(),
+            )
+        };
+        let name = |path: &str, method: &HttpMethod| {
+            format!("{} {}", method.to_string().to_uppercase(), path)
+        };
+
+        for (method, old_op) in &old.methods {
+            let old_pointer = pointer(old_path, method);
+            let new_op = match new.methods.get(method) {
+                Some(op) => op,
+                None => {
+                    self.breaking(
+                        &old_pointer,
+                        format!("Operation {:?} was removed", name(old_path, method)),
+                    );
+                    continue;
+                }
+            };
+
+            let new_pointer = pointer(new_path, method);
+            self.parameters(
+                &parameters(old_path, old, old_op, &old_pointer),
+                &parameters(new_path, new, new_op, &new_pointer),
+            );
+            self.responses((old_op, &old_pointer), (new_op, &new_pointer));
+        }
+
+        for method in new.methods.keys() {
+            if !old.methods.contains_key(method) {
+                self.non_breaking(
+                    &pointer(new_path, method),
+                    format!("Operation {:?} was added", name(new_path, method)),
+                );
+            }
+        }
+    }
+
+    /// Compares the parameters of an operation.
+    fn parameters<S: Schema>(&mut self, old: &Parameters<S>, new: &Parameters<S>) {
+        for (key, (old_pointer, old_param)) in old {
+            let old_param = old_param.read();
+            let (new_pointer, new_param) = match new.get(key) {
+                Some((p, param)) => (p, param.read()),
+                None => {
+                    self.breaking(
+                        old_pointer,
+                        format!("Parameter {:?} was removed", old_param.name),
+                    );
+                    continue;
+                }
+            };
+
+            let name = &new_param.name;
+            if !old_param.required && new_param.required {
+                self.breaking(new_pointer, format!("Parameter {:?} is now required", name));
+            } else if old_param.required && !new_param.required {
+                self.non_breaking(new_pointer, format!("Parameter {:?} is now optional", name));
+            }
+
+            if let (Some(old_schema), Some(new_schema)) = (&old_param.schema, &new_param.schema) {
+                self.schema(
+                    old_schema,
+                    new_schema,
+                    Direction::Request,
+                    &join_pointer(new_pointer, "schema"),
+                    &mut vec![],
+                );
+                continue;
+            }
+
+            self.types(
+                (old_param.data_type, old_param.format.as_ref()),
+                (new_param.data_type, new_param.format.as_ref()),
+                new_pointer,
+            );
+            self.enums(
+                &old_param.enum_,
+                &new_param.enum_,
+                Direction::Request,
+                new_pointer,
+            );
+            if old_param.data_type == new_param.data_type {
+                self.items(
+                    old_param.items.as_ref(),
+                    new_param.items.as_ref(),
+                    &join_pointer(new_pointer, "items"),
+                );
+            }
+        }
+
+        for (key, (new_pointer, new_param)) in new {
+            if old.contains_key(key) {
+                continue;
+            }
+
+            let new_param = new_param.read();
+            if new_param.required {
+                self.breaking(
+                    new_pointer,
+                    format!("Required parameter {:?} was added", new_param.name),
+                );
+            } else {
+                self.non_breaking(
+                    new_pointer,
+                    format!("Optional parameter {:?} was added", new_param.name),
+                );
+            }
+        }
+    }
+
+    /// Compares the items of array parameters.
+    fn items(&mut self, old: Option<&Items>, new: Option<&Items>, pointer: &str) {
+        let (old, new) = match (old, new) {
+            (Some(o), Some(n)) => (o, n),
+            _ => return,
+        };
+
+        self.types(
+            (old.data_type, old.format.as_ref()),
+            (new.data_type, new.format.as_ref()),
+            pointer,
+        );
+        self.enums(&old.enum_, &new.enum_, Direction::Request, pointer);
+        if old.data_type == new.data_type {
+            self.items(
+                old.items.as_deref(),
+                new.items.as_deref(),
+                &join_pointer(pointer, "items"),
+            );
+        }
+    }
+
+    /// Compares the responses of matching operations.
+    fn responses<S: Schema>(
+        &mut self,
+        (old, old_pointer): (&ResolvableOperation<S>, &str),
+        (new, new_pointer): (&ResolvableOperation<S>, &str),
+    ) {
+        let (old_pointer, new_pointer) = (
+            join_pointer(old_pointer, "responses"),
+            join_pointer(new_pointer, "responses"),
+        );
+
+        for (code, old_resp) in &old.responses {
+            let new_resp = match new.responses.get(code) {
+                Some(r) => r,
+                None => {
+                    self.breaking(
+                        &join_pointer(&old_pointer, code),
+                        format!("Response {:?} was removed", code),
+                    );
+                    continue;
+                }
+            };
+
+            // Responses are resolved.
+            let (old_resp, new_resp) = match (old_resp, new_resp) {
+                (Either::Right(o), Either::Right(n)) => (o.read(), n.read()),
+                _ => continue,
+            };
+
+            let pointer = join_pointer(&join_pointer(&new_pointer, code), "schema");
+            match (&old_resp.schema, &new_resp.schema) {
+                (Some(o), Some(n)) => self.schema(o, n, Direction::Response, &pointer, &mut vec![]),
+                (Some(_), None) => self.breaking(
+                    &pointer,
+                    format!("Schema of response {:?} was removed", code),
+                ),
+                (None, Some(_)) => {
+                    self.non_breaking(&pointer, format!("Schema of response {:?} was added", code))
+                }
+                (None, None) => (),
+            }
+        }
+
+        for code in new.responses.keys() {
+            if !old.responses.contains_key(code) {
+                self.non_breaking(
+                    &join_pointer(&new_pointer, code),
+                    format!("Response {:?} was added", code),
+                );
+            }
+        }
+    }
+
+    /// Compares the schemas. Names of the definitions being compared are
+    /// kept in `stack` (for cyclic definitions).
+    fn schema<S: Schema>(
+        &mut self,
+        old: &Resolvable<S>,
+        new: &Resolvable<S>,
+        direction: Direction,
+        pointer: &str,
+        stack: &mut Vec<String>,
+    ) {
+        let (old, new) = (old.read(), new.read());
+        let name = new.name().map(String::from);
+        if let Some(n) = name.as_ref() {
+            if stack.contains(n) {
+                return;
+            }
+
+            stack.push(n.clone());
+        }
+
+        let types_changed = self.types(
+            (old.data_type(), old.format()),
+            (new.data_type(), new.format()),
+            pointer,
+        );
+        if !types_changed {
+            self.enums(
+                old.enum_variants().unwrap_or_default(),
+                new.enum_variants().unwrap_or_default(),
+                direction,
+                pointer,
+            );
+            self.properties(&*old, &*new, direction, pointer, stack);
+
+            if let (Some(o), Some(n)) = (old.items(), new.items()) {
+                self.schema(o, n, direction, &join_pointer(pointer, "items"), stack);
+            }
+
+            if let (Some(Either::Right(o)), Some(Either::Right(n))) =
+                (old.additional_properties(), new.additional_properties())
+            {
+                let pointer = join_pointer(pointer, "additionalProperties");
+                self.schema(o, n, direction, &pointer, stack);
+            }
+
+            // Composed schemas are compared by their positions.
+            let all_of_pointer = join_pointer(pointer, "allOf");
+            let (old_all_of, new_all_of) = (
+                old.all_of().unwrap_or_default(),
+                new.all_of().unwrap_or_default(),
+            );
+            for (i, (o, n)) in old_all_of.iter().zip(new_all_of).enumerate() {
+                let pointer = join_pointer(&all_of_pointer, &i.to_string());
+                self.schema(o, n, direction, &pointer, stack);
+            }
+
+            if old_all_of.len() != new_all_of.len() {
+                self.breaking(
+                    &all_of_pointer,
+                    format!(
+                        "Number of composed schemas changed from {} to {}",
+                        old_all_of.len(),
+                        new_all_of.len()
+                    ),
+                );
+            }
+        }
+
+        if name.is_some() {
+            stack.pop();
+        }
+    }
+
+    /// Compares the properties of object schemas.
+    fn properties<S: Schema>(
+        &mut self,
+        old: &S,
+        new: &S,
+        direction: Direction,
+        pointer: &str,
+        stack: &mut Vec<String>,
+    ) {
+        let empty = BTreeMap::new();
+        let (old_props, new_props) = (
+            old.properties().unwrap_or(&empty),
+            new.properties().unwrap_or(&empty),
+        );
+        let no_fields = BTreeSet::new();
+        let (old_required, new_required) = (
+            old.required_properties().unwrap_or(&no_fields),
+            new.required_properties().unwrap_or(&no_fields),
+        );
+
+        let props_pointer = join_pointer(pointer, "properties");
+        for (name, old_prop) in old_props {
+            let prop_pointer = join_pointer(&props_pointer, name);
+            let new_prop = match new_props.get(name) {
+                Some(p) => p,
+                // Clients may rely on fields in responses, but servers
+                // usually ignore unknown fields in requests.
+                None => {
+                    let message = format!("Property {:?} was removed", name);
+                    match direction {
+                        Direction::Response => self.breaking(&prop_pointer, message),
+                        Direction::Request => self.non_breaking(&prop_pointer, message),
+                    }
+
+                    continue;
+                }
+            };
+
+            match (
+                direction,
+                old_required.contains(name),
+                new_required.contains(name),
+            ) {
+                (Direction::Request, false, true) => self.breaking(
+                    &prop_pointer,
+                    format!("Property {:?} is now required", name),
+                ),
+                (Direction::Response, true, false) => self.breaking(
+                    &prop_pointer,
+                    format!("Property {:?} is now optional", name),
+                ),
+                (_, false, true) => self.non_breaking(
+                    &prop_pointer,
+                    format!("Property {:?} is now required", name),
+                ),
+                (_, true, false) => self.non_breaking(
+                    &prop_pointer,
+                    format!("Property {:?} is now optional", name),
+                ),
+                _ => (),
+            }
+
+            self.schema(old_prop, new_prop, direction, &prop_pointer, stack);
+        }
+
+        for name in new_props.keys() {
+            if old_props.contains_key(name) {
+                continue;
+            }
+
+            let prop_pointer = join_pointer(&props_pointer, name);
+            if direction == Direction::Request && new_required.contains(name) {
+                self.breaking(
+                    &prop_pointer,
+                    format!("Required property {:?} was added", name),
+                );
+            } else {
+                self.non_breaking(&prop_pointer, format!("Property {:?} was added", name));
+            }
+        }
+    }
+
+    /// Compares the types (and formats). Returns whether they've changed.
+    fn types(
+        &mut self,
+        old: (Option<DataType>, Option<&DataTypeFormat>),
+        new: (Option<DataType>, Option<&DataTypeFormat>),
+        pointer: &str,
+    ) -> bool {
+        if old.0 != new.0 {
+            self.breaking(
+                pointer,
+                format!(
+                    "Type changed from {} to {}",
+                    TypeName(old.0),
+                    TypeName(new.0)
+                ),
+            );
+            return true;
+        }
+
+        if old.1 != new.1 {
+            self.breaking(
+                pointer,
+                format!(
+                    "Format changed from {} to {}",
+                    FormatName(old.1),
+                    FormatName(new.1)
+                ),
+            );
+            return true;
+        }
+
+        false
+    }
+
+    /// Compares the enum variants. Narrowing breaks requests (the removed
+    /// values are rejected) and widening breaks responses (clients don't
+    /// know the new values).
+    fn enums(&mut self, old: &[Value], new: &[Value], direction: Direction, pointer: &str) {
+        if old == new {
+            return;
+        }
+
+        // No variants means that any value is allowed.
+        let removed = if new.is_empty() {
+            vec![]
+        } else if old.is_empty() {
+            vec![Value::String("any value".into())]
+        } else {
+            old.iter().filter(|v| !new.contains(v)).cloned().collect()
+        };
+        let added = if old.is_empty() {
+            vec![]
+        } else if new.is_empty() {
+            vec![Value::String("any value".into())]
+        } else {
+            new.iter().filter(|v| !old.contains(v)).cloned().collect()
+        };
+
+        let list = |values: &[Value]| {
+            values
+                .iter()
+                .map(|v| v.to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+
+        if !removed.is_empty() {
+            let message = format!("Enum was narrowed (removed {})", list(&removed));
+            match direction {
+                Direction::Request => self.breaking(pointer, message),
+                Direction::Response => self.non_breaking(pointer, message),
+            }
+        }
+
+        if !added.is_empty() {
+            let message = format!("Enum was widened (added {})", list(&added));
+            match direction {
+                Direction::Request => self.non_breaking(pointer, message),
+                Direction::Response => self.breaking(pointer, message),
+            }
+        }
+    }
+}
+
+/// Returns the parameters for an operation (along with their pointers).
+/// Operation parameters override path parameters, and parameters in
+/// paths are identified by their position in the template (because
+/// their names don't matter to clients).
+fn parameters<S>(
+    path: &str,
+    item: &ResolvablePathItem<S>,
+    op: &ResolvableOperation<S>,
+    pointer: &str,
+) -> Parameters<S> {
+    let mut template = vec![];
+    ResolvableApi::<()>::path_parameters_map(path, |p| {
+        template.push(p.to_owned());
+        ":".into()
+    });
+
+    let mut params = BTreeMap::new();
+    let sources = [
+        (&op.parameters, join_pointer(pointer, "parameters")),
+        (
+            &item.parameters,
+            join_pointer(parent(pointer), "parameters"),
+        ),
+    ];
+    for (list, list_pointer) in &sources {
+        for (i, p) in list.iter().enumerate() {
+            // Parameters are resolved.
+            let param = match p {
+                Either::Right(p) => p,
+                Either::Left(_) => continue,
+            };
+
+            let (in_, name) = {
+                let p = param.read();
+                (p.in_, p.name.clone())
+            };
+            let key = match template.iter().position(|n| *n == name) {
+                Some(idx) if in_ == ParameterIn::Path => idx.to_string(),
+                _ => name,
+            };
+            params
+                .entry((in_, key))
+                .or_insert_with(|| (join_pointer(list_pointer, &i.to_string()), param.clone()));
+        }
+    }
+
+    params
+}
+
+/// Returns the paths in the spec by their templates (with parameters
+/// replaced by `:`).
+fn templates<S>(api: &ResolvableApi<S>) -> BTreeMap<String, &str> {
+    api.paths
+        .keys()
+        .map(|p| {
+            let fmt = ResolvableApi::<()>::path_parameters_map(p, |_| ":".into());
+            (fmt.into_owned(), p.as_str())
+        })
+        .collect()
+}
+
+/// Returns the parent of the node at the given pointer.
+fn parent(pointer: &str) -> &str {
+    pointer.rfind('/').map(|i| &pointer[..i]).unwrap_or("")
+}
+
+/// Name of the (JSON) type for messages.
+struct TypeName(Option<DataType>);
+
+/// Name of the format for messages.
+struct FormatName<'a>(Option<&'a DataTypeFormat>);
+
+impl Display for TypeName {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.0.and_then(|t| serde_json::to_value(t).ok()) {
+            Some(Value::String(s)) => f.write_str(&s),
+            _ => f.write_str("any"),
+        }
+    }
+}
+
+impl Display for FormatName<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.0.and_then(|t| serde_json::to_value(t).ok()) {
+            Some(Value::String(s)) => f.write_str(&s),
+            _ => f.write_str("none"),
+        }
+    }
+}
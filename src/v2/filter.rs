@@ -0,0 +1,325 @@
+//! Filtering operations in v2 specs.
+//!
+//! [`Filter`](struct.Filter.html) keeps the operations matching its criteria
+//! (tags, path globs, operation IDs and HTTP methods), and prunes the definitions
+//! (along with global parameters and responses) which are no longer reachable
+//! from the kept operations. This works for both raw and resolved specs.
+//!
+//! Criteria of the same kind are alternatives (say, any of the included tags),
+//! whereas different kinds of criteria should all match. Operations matching
+//! any of the excluded criteria are removed.
+//!
+//! ```rust
+//! use paperclip::v2::{self, filter::Filter, models::HttpMethod, DefaultSchema, ResolvableApi};
+//!
+//! let api: ResolvableApi<DefaultSchema> = v2::from_str("
+//! swagger: '2.0'
+//! info: {title: Pets, version: '1.0'}
+//! paths:
+//!   /pets:
+//!     get:
+//!       tags: [pets]
+//!       responses:
+//!         '200':
+//!           description: OK
+//!           schema:
+//!             $ref: '#/definitions/Pet'
+//!     delete:
+//!       tags: [pets]
+//!       responses:
+//!         '204': {description: Deleted}
+//!   /stores:
+//!     get:
+//!       tags: [stores]
+//!       responses:
+//!         '200':
+//!           description: OK
+//!           schema:
+//!             $ref: '#/definitions/Store'
+//! definitions:
+//!   Pet: {type: object}
+//!   Store: {type: object}
+//! ").unwrap();
+//!
+//! let filter = Filter::default()
+//!     .include_tag("pets")
+//!     .exclude_method(HttpMethod::Delete);
+//! let api = filter.apply(&api.resolve().unwrap());
+//! assert_eq!(api.paths.keys().collect::<Vec<_>>(), vec!["/pets"]);
+//! assert_eq!(api.paths["/pets"].methods.len(), 1);
+//! assert_eq!(api.definitions.keys().collect::<Vec<_>>(), vec!["Pet"]);
+//! ```
+
+use super::models::{Either, HttpMethod, Resolvable, ResolvableApi, ResolvableOperation};
+use super::Schema;
+use crate::util::glob_match;
+
+use std::collections::{BTreeMap, HashSet};
+use std::sync::Arc;
+
+const DEF_REF_PREFIX: &str = "#/definitions/";
+const PARAM_REF_PREFIX: &str = "#/parameters/";
+const RESP_REF_PREFIX: &str = "#/responses/";
+
+/// Criteria for keeping (or removing) operations.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Filter {
+    pub include_tags: Vec<String>,
+    pub exclude_tags: Vec<String>,
+    /// Path globs (where `*` matches any sequence of characters).
+    pub include_paths: Vec<String>,
+    /// Path globs (where `*` matches any sequence of characters).
+    pub exclude_paths: Vec<String>,
+    pub include_operations: Vec<String>,
+    pub exclude_operations: Vec<String>,
+    pub include_methods: Vec<HttpMethod>,
+    pub exclude_methods: Vec<HttpMethod>,
+}
+
+impl Filter {
+    /// Keeps the operations having this tag.
+    pub fn include_tag<T: Into<String>>(mut self, tag: T) -> Self {
+        self.include_tags.push(tag.into());
+        self
+    }
+
+    /// Removes the operations having this tag.
+    pub fn exclude_tag<T: Into<String>>(mut self, tag: T) -> Self {
+        self.exclude_tags.push(tag.into());
+        self
+    }
+
+    /// Keeps the operations in paths matching this glob (say, `/pets*`).
+    pub fn include_path<P: Into<String>>(mut self, glob: P) -> Self {
+        self.include_paths.push(glob.into());
+        self
+    }
+
+    /// Removes the operations in paths matching this glob.
+    pub fn exclude_path<P: Into<String>>(mut self, glob: P) -> Self {
+        self.exclude_paths.push(glob.into());
+        self
+    }
+
+    /// Keeps the operation with this ID.
+    pub fn include_operation<O: Into<String>>(mut self, id: O) -> Self {
+        self.include_operations.push(id.into());
+        self
+    }
+
+    /// Removes the operation with this ID.
+    pub fn exclude_operation<O: Into<String>>(mut self, id: O) -> Self {
+        self.exclude_operations.push(id.into());
+        self
+    }
+
+    /// Keeps the operations with this method.
+    pub fn include_method(mut self, method: HttpMethod) -> Self {
+        self.include_methods.push(method);
+        self
+    }
+
+    /// Removes the operations with this method.
+    pub fn exclude_method(mut self, method: HttpMethod) -> Self {
+        self.exclude_methods.push(method);
+        self
+    }
+
+    /// Whether this filter keeps everything.
+    pub fn is_empty(&self) -> bool {
+        *self == Filter::default()
+    }
+
+    /// Checks whether the given operation should be kept.
+    pub fn keeps<S>(&self, path: &str, method: HttpMethod, op: &ResolvableOperation<S>) -> bool {
+        let id = op.operation_id.as_deref();
+        let has_tag = |tags: &[String]| op.tags.iter().any(|t| tags.contains(t));
+        let has_id = |ids: &[String]| id.map(|i| ids.iter().any(|s| s == i)).unwrap_or(false);
+        let in_paths = |globs: &[String]| globs.iter().any(|g| glob_match(g, path));
+
+        let included = (self.include_tags.is_empty() || has_tag(&self.include_tags))
+            && (self.include_paths.is_empty() || in_paths(&self.include_paths))
+            && (self.include_operations.is_empty() || has_id(&self.include_operations))
+            && (self.include_methods.is_empty() || self.include_methods.contains(&method));
+        let excluded = has_tag(&self.exclude_tags)
+            || in_paths(&self.exclude_paths)
+            || has_id(&self.exclude_operations)
+            || self.exclude_methods.contains(&method);
+
+        included && !excluded
+    }
+
+    /// Returns the spec with only the operations kept by this filter. Paths
+    /// without operations are removed, and so are the definitions (and global
+    /// parameters and responses) which aren't reachable from the kept operations.
+    pub fn apply<S: Schema>(&self, api: &ResolvableApi<S>) -> ResolvableApi<S> {
+        let mut api = api.clone();
+        let mut paths = BTreeMap::new();
+        for (path, mut item) in std::mem::take(&mut api.paths) {
+            item.methods = std::mem::take(&mut item.methods)
+                .into_iter()
+                .filter(|(m, op)| self.keeps(&path, *m, op))
+                .collect();
+            if !item.methods.is_empty() {
+                paths.insert(path, item);
+            }
+        }
+
+        api.paths = paths;
+        let mut reachable = Reachable {
+            api: &api,
+            definitions: HashSet::new(),
+            parameters: HashSet::new(),
+            responses: HashSet::new(),
+        };
+        reachable.walk_paths();
+
+        let (definitions, parameters, responses) = (
+            reachable.definitions,
+            reachable.parameters,
+            reachable.responses,
+        );
+        api.definitions.retain(|n, _| definitions.contains(n));
+        api.parameters.retain(|n, _| parameters.contains(n));
+        api.responses.retain(|n, _| responses.contains(n));
+        api
+    }
+}
+
+/// Names of the objects reachable from the operations in a spec.
+struct Reachable<'a, S> {
+    api: &'a ResolvableApi<S>,
+    definitions: HashSet<String>,
+    parameters: HashSet<String>,
+    responses: HashSet<String>,
+}
+
+impl<'a, S: Schema> Reachable<'a, S> {
+    fn walk_paths(&mut self) {
+        let api = self.api;
+        for item in api.paths.values() {
+            let params = item
+                .parameters
+                .iter()
+                .chain(item.methods.values().flat_map(|op| &op.parameters));
+            for param in params {
+                // Resolved parameters are the global ones (if they were referenced).
+                let (name, param) = match param {
+                    Either::Left(r) => match r
+                        .reference
+                        .strip_prefix(PARAM_REF_PREFIX)
+                        .and_then(|n| api.parameters.get_key_value(n))
+                    {
+                        Some((n, p)) => (Some(n), p),
+                        None => continue,
+                    },
+                    Either::Right(p) => (
+                        api.parameters
+                            .iter()
+                            .find(|(_, g)| Arc::ptr_eq(g, p))
+                            .map(|(n, _)| n),
+                        p,
+                    ),
+                };
+
+                self.parameters.extend(name.cloned());
+                if let Some(s) = param.read().schema.as_ref() {
+                    self.walk_schema(s);
+                }
+            }
+
+            for resp in item.methods.values().flat_map(|op| op.responses.values()) {
+                let (name, resp) = match resp {
+                    Either::Left(r) => match r
+                        .reference
+                        .strip_prefix(RESP_REF_PREFIX)
+                        .and_then(|n| api.responses.get_key_value(n))
+                    {
+                        Some((n, r)) => (Some(n), r),
+                        None => continue,
+                    },
+                    Either::Right(r) => (
+                        api.responses
+                            .iter()
+                            .find(|(_, g)| Arc::ptr_eq(g, r))
+                            .map(|(n, _)| n),
+                        r,
+                    ),
+                };
+
+                self.responses.extend(name.cloned());
+                if let Some(s) = resp.read().schema.as_ref() {
+                    self.walk_schema(s);
+                }
+            }
+        }
+    }
+
+    fn walk_schema(&mut self, schema: &Resolvable<S>) {
+        let schema = schema.read();
+        // Resolved schemas have the names of their definitions, and raw
+        // schemas have references.
+        let name = schema.name().map(String::from).or_else(|| {
+            schema
+                .reference()
+                .and_then(|r| r.strip_prefix(DEF_REF_PREFIX).map(String::from))
+        });
+        if let Some(name) = name {
+            if self.definitions.contains(&name) {
+                return;
+            }
+
+            if let Some(def) = self.api.definitions.get(&name) {
+                self.definitions.insert(name.clone());
+                // Subtypes are needed for definitions with discriminators.
+                if def.read().discriminator().is_some() {
+                    self.walk_subtypes(&name);
+                }
+
+                // Raw references should be followed to the definition.
+                if schema.reference().is_some() {
+                    return self.walk_schema(def);
+                }
+            }
+        }
+
+        if let Some(s) = schema.items() {
+            self.walk_schema(s);
+        }
+
+        if let Some(props) = schema.properties() {
+            for s in props.values() {
+                self.walk_schema(s);
+            }
+        }
+
+        if let Some(Either::Right(s)) = schema.additional_properties() {
+            self.walk_schema(s);
+        }
+
+        for s in schema.all_of().unwrap_or_default() {
+            self.walk_schema(s);
+        }
+    }
+
+    /// Walks the definitions composing the given definition (through `allOf`).
+    fn walk_subtypes(&mut self, name: &str) {
+        let reference = format!("{}{}", DEF_REF_PREFIX, name);
+        for (sub_name, sub) in &self.api.definitions {
+            let is_subtype = sub.read().all_of().unwrap_or_default().iter().any(|s| {
+                let s = s.read();
+                s.name() == Some(name) || s.reference() == Some(&reference)
+            });
+            if !is_subtype || self.definitions.contains(sub_name) {
+                continue;
+            }
+
+            // Resolved definitions are marked when they're walked.
+            if sub.read().name().is_none() {
+                self.definitions.insert(sub_name.clone());
+            }
+
+            self.walk_schema(sub);
+        }
+    }
+}
@@ -0,0 +1,249 @@
+//! Lints for style problems in v2 specs.
+//!
+//! Unlike [`validate`](../fn.validate.html), lints don't make the spec invalid.
+//! They're conventions (say, operation IDs in camelCase) which are checked
+//! by [rules](trait.Rule.html). Built-in rules are listed in the
+//! [`rules`](rules/index.html) module, and the severity of each rule can be
+//! changed (or the rule can be turned off) through [`LintConfig`](struct.LintConfig.html).
+//!
+//! Lints for some node (and its children) can be suppressed by adding
+//! `x-paperclip-lint-ignore` to the node in the spec. Its value is either
+//! the list of rules to be ignored or `true` (for ignoring all rules).
+//!
+//! ```rust
+//! use paperclip::v2::lint::{LintConfig, Linter};
+//!
+//! let spec = serde_yaml::from_str("
+//! swagger: '2.0'
+//! info: {title: Pets, version: '1.0'}
+//! paths:
+//!   /pets:
+//!     get:
+//!       operationId: list_pets
+//!       x-paperclip-lint-ignore: [operation-tags, operation-description]
+//!       responses:
+//!         '200': {description: OK}
+//! ").unwrap();
+//!
+//! let config: LintConfig = serde_yaml::from_str("
+//! rules:
+//!   operation-id-camel-case: error
+//! ").unwrap();
+//! let linter: Linter = Linter::default().configure(&config).unwrap();
+//! let report = linter.lint(&spec).unwrap();
+//! assert_eq!(
+//!     report.errors().map(ToString::to_string).collect::<Vec<_>>(),
+//!     vec![
+//!         "Operation ID \"list_pets\" is not in camelCase [operation-id-camel-case] \
+//!          (at /paths/~1pets/get/operationId)"
+//!     ]
+//! );
+//! ```
+
+pub mod rules;
+
+use super::models::{DefaultSchema, ResolvableApi};
+use super::Schema;
+use crate::diagnostics::{Report, Severity};
+use crate::error::PaperClipError;
+use serde::de::DeserializeOwned;
+use serde_derive::{Deserialize, Serialize};
+use serde_json::Value;
+
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::path::Path;
+
+/// Extension for suppressing lints in a node (and its children).
+pub const IGNORE_EXTENSION: &str = "x-paperclip-lint-ignore";
+
+/// Style problem found by a rule.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Lint {
+    /// JSON pointer to the offending node.
+    pub pointer: String,
+    /// Description of the problem.
+    pub message: String,
+}
+
+impl Lint {
+    /// Creates a lint for the node at the given pointer.
+    pub fn new<P, M>(pointer: P, message: M) -> Self
+    where
+        P: Into<String>,
+        M: Into<String>,
+    {
+        Lint {
+            pointer: pointer.into(),
+            message: message.into(),
+        }
+    }
+}
+
+/// Rule checked by the linter.
+pub trait Rule<S> {
+    /// Name of this rule (used in configuration and `x-paperclip-lint-ignore`).
+    fn name(&self) -> &str;
+
+    /// Severity of lints from this rule (unless it's configured otherwise).
+    fn severity(&self) -> Severity {
+        Severity::Warning
+    }
+
+    /// Checks the given spec and returns the problems found.
+    fn check(&self, api: &ResolvableApi<S>) -> Vec<Lint>;
+}
+
+/// Level of a rule in the configuration.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Level {
+    /// The rule isn't checked.
+    Off,
+    Warning,
+    Error,
+}
+
+/// Configuration for the linter.
+///
+/// ```yaml
+/// rules:
+///   operation-id-camel-case: error
+///   plural-collection-paths: off
+/// ```
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LintConfig {
+    /// Levels of rules (by their names).
+    #[serde(default)]
+    pub rules: BTreeMap<String, Level>,
+}
+
+impl LintConfig {
+    /// Loads the configuration from the given file (JSON or YAML).
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Self, PaperClipError> {
+        Ok(serde_yaml::from_reader(File::open(path)?)?)
+    }
+}
+
+/// Linter which checks specs against a set of rules.
+pub struct Linter<S = DefaultSchema> {
+    rules: Vec<(Box<dyn Rule<S>>, Level)>,
+}
+
+impl<S> Default for Linter<S>
+where
+    S: Schema,
+{
+    /// Linter with the built-in rules.
+    fn default() -> Self {
+        Linter {
+            rules: rules::builtin()
+                .into_iter()
+                .map(|r| {
+                    let level = r.severity().into();
+                    (r, level)
+                })
+                .collect(),
+        }
+    }
+}
+
+impl<S> Linter<S>
+where
+    S: Schema,
+{
+    /// Linter without any rules.
+    pub fn empty() -> Self {
+        Linter { rules: vec![] }
+    }
+
+    /// Adds the given rule to this linter.
+    pub fn rule<R>(mut self, rule: R) -> Self
+    where
+        R: Rule<S> + 'static,
+    {
+        let level = rule.severity().into();
+        self.rules.push((Box::new(rule), level));
+        self
+    }
+
+    /// Sets the levels of rules from the given configuration.
+    pub fn configure(mut self, config: &LintConfig) -> Result<Self, PaperClipError> {
+        for (name, &level) in &config.rules {
+            match self.rules.iter_mut().find(|(r, _)| r.name() == name) {
+                Some((_, l)) => *l = level,
+                None => return Err(PaperClipError::UnknownLintRule(name.clone())),
+            }
+        }
+
+        Ok(self)
+    }
+
+    /// Names of the rules in this linter.
+    pub fn rule_names(&self) -> impl Iterator<Item = &str> {
+        self.rules.iter().map(|(r, _)| r.name())
+    }
+
+    /// Lints the given spec (JSON value of a v2 spec). The value is needed
+    /// (instead of the parsed spec) for honoring `x-paperclip-lint-ignore`.
+    pub fn lint(&self, spec: &Value) -> Result<Report, PaperClipError>
+    where
+        S: DeserializeOwned,
+    {
+        let api: ResolvableApi<S> = serde_json::from_value(spec.clone())?;
+        let mut report = Report::default();
+        for (rule, level) in &self.rules {
+            let severity = match level {
+                Level::Off => continue,
+                Level::Warning => Severity::Warning,
+                Level::Error => Severity::Error,
+            };
+
+            for lint in rule.check(&api) {
+                if is_ignored(spec, &lint.pointer, rule.name()) {
+                    continue;
+                }
+
+                let err = PaperClipError::Lint(rule.name().into(), lint.message);
+                report.add(severity, err.at(lint.pointer));
+            }
+        }
+
+        Ok(report)
+    }
+}
+
+impl From<Severity> for Level {
+    fn from(severity: Severity) -> Self {
+        match severity {
+            Severity::Warning => Level::Warning,
+            Severity::Error => Level::Error,
+        }
+    }
+}
+
+/// Checks whether the given rule is ignored for the node at the given
+/// pointer (using `x-paperclip-lint-ignore` in the node or its ancestors).
+fn is_ignored(spec: &Value, pointer: &str, rule: &str) -> bool {
+    let mut node = Some(spec);
+    let mut tokens = pointer.split('/').skip(1);
+    while let Some(value) = node {
+        match value.get(IGNORE_EXTENSION) {
+            Some(Value::Bool(true)) => return true,
+            Some(Value::String(s)) if s == rule => return true,
+            Some(Value::Array(rules)) if rules.iter().any(|r| r == rule) => return true,
+            _ => (),
+        }
+
+        node = tokens.next().and_then(|t| {
+            let token = t.replace("~1", "/").replace("~0", "~");
+            match value {
+                Value::Object(map) => map.get(&token),
+                Value::Array(items) => token.parse::<usize>().ok().and_then(|i| items.get(i)),
+                _ => None,
+            }
+        });
+    }
+
+    false
+}
@@ -0,0 +1,306 @@
+//! Built-in lint rules.
+
+use super::{Lint, Rule};
+use crate::util::join_pointer;
+use crate::v2::models::{
+    DataType, Either, HttpMethod, ParameterIn, Resolvable, ResolvableApi, ResolvableOperation,
+};
+use crate::v2::Schema;
+
+/// Names normalized by `pagination_name` which are taken as pagination parameters.
+const PAGINATION_PARAMS: &[&str] = &[
+    "limit",
+    "offset",
+    "page",
+    "pagesize",
+    "perpage",
+    "cursor",
+    "pagetoken",
+];
+
+/// Returns the built-in rules.
+pub fn builtin<S: Schema>() -> Vec<Box<dyn Rule<S>>> {
+    vec![
+        Box::new(OperationIdCamelCase),
+        Box::new(OperationTags),
+        Box::new(OperationDescription),
+        Box::new(NoInlineResponseObjects),
+        Box::new(PluralCollectionPaths),
+        Box::new(ListPagination),
+    ]
+}
+
+/// Operation IDs should be in camelCase (say, `listPets`).
+pub struct OperationIdCamelCase;
+
+impl<S: Schema> Rule<S> for OperationIdCamelCase {
+    fn name(&self) -> &str {
+        "operation-id-camel-case"
+    }
+
+    fn check(&self, api: &ResolvableApi<S>) -> Vec<Lint> {
+        operations(api)
+            .filter_map(|(pointer, op)| {
+                let id = op.operation_id.as_ref()?;
+                let mut chars = id.chars();
+                let is_camel_case = matches!(chars.next(), Some(c) if c.is_ascii_lowercase())
+                    && chars.all(|c| c.is_ascii_alphanumeric());
+                if is_camel_case {
+                    return None;
+                }
+
+                Some(Lint::new(
+                    join_pointer(&pointer, "operationId"),
+                    format!("Operation ID {:?} is not in camelCase", id),
+                ))
+            })
+            .collect()
+    }
+}
+
+/// Operations should have at least one tag.
+pub struct OperationTags;
+
+impl<S: Schema> Rule<S> for OperationTags {
+    fn name(&self) -> &str {
+        "operation-tags"
+    }
+
+    fn check(&self, api: &ResolvableApi<S>) -> Vec<Lint> {
+        operations(api)
+            .filter(|(_, op)| op.tags.is_empty())
+            .map(|(pointer, _)| Lint::new(pointer, "Operation doesn't have any tags"))
+            .collect()
+    }
+}
+
+/// Operations should have a summary or a description.
+pub struct OperationDescription;
+
+impl<S: Schema> Rule<S> for OperationDescription {
+    fn name(&self) -> &str {
+        "operation-description"
+    }
+
+    fn check(&self, api: &ResolvableApi<S>) -> Vec<Lint> {
+        let is_empty = |s: &Option<String>| s.as_deref().unwrap_or_default().trim().is_empty();
+        operations(api)
+            .filter(|(_, op)| is_empty(&op.summary) && is_empty(&op.description))
+            .map(|(pointer, _)| {
+                Lint::new(pointer, "Operation doesn't have a summary or description")
+            })
+            .collect()
+    }
+}
+
+/// Response schemas shouldn't be anonymous objects (or arrays of them),
+/// so that they get proper names in definitions (and generated code).
+pub struct NoInlineResponseObjects;
+
+impl<S: Schema> Rule<S> for NoInlineResponseObjects {
+    fn name(&self) -> &str {
+        "no-inline-response-objects"
+    }
+
+    fn check(&self, api: &ResolvableApi<S>) -> Vec<Lint> {
+        let mut lints = vec![];
+        let mut check = |schema: Option<&Resolvable<S>>, pointer: String| {
+            let mut pointer = join_pointer(&pointer, "schema");
+            let mut schema = match schema {
+                Some(Resolvable::Raw(s)) => s.clone(),
+                _ => return,
+            };
+
+            loop {
+                let next = {
+                    let s = schema.read();
+                    if s.reference().is_some() {
+                        return;
+                    }
+
+                    if s.properties().map(|p| !p.is_empty()).unwrap_or(false) {
+                        lints.push(Lint::new(
+                            pointer,
+                            "Response schema is an anonymous object (it should be in definitions)",
+                        ));
+                        return;
+                    }
+
+                    match (s.data_type(), s.items()) {
+                        (Some(DataType::Array), Some(Resolvable::Raw(s))) => s.clone(),
+                        _ => return,
+                    }
+                };
+
+                pointer = join_pointer(&pointer, "items");
+                schema = next;
+            }
+        };
+
+        for (name, resp) in &api.responses {
+            check(
+                resp.read().schema.as_ref(),
+                join_pointer("/responses", name),
+            );
+        }
+
+        for (pointer, op) in operations(api) {
+            for (code, resp) in &op.responses {
+                if let Either::Right(r) = resp {
+                    let pointer = join_pointer(&join_pointer(&pointer, "responses"), code);
+                    check(r.read().schema.as_ref(), pointer);
+                }
+            }
+        }
+
+        lints
+    }
+}
+
+/// Collections in paths should be plural (say, `/pets/{id}` instead of
+/// `/pet/{id}`). A segment is taken as a collection if it's followed by
+/// a templated segment, and it's plural if it ends with "s".
+pub struct PluralCollectionPaths;
+
+impl<S: Schema> Rule<S> for PluralCollectionPaths {
+    fn name(&self) -> &str {
+        "plural-collection-paths"
+    }
+
+    fn check(&self, api: &ResolvableApi<S>) -> Vec<Lint> {
+        let mut lints = vec![];
+        for path in api.paths.keys() {
+            let segments = path.split('/').collect::<Vec<_>>();
+            for pair in segments.windows(2) {
+                let (collection, next) = (pair[0], pair[1]);
+                if collection.is_empty()
+                    || collection.contains('{')
+                    || !next.starts_with('{')
+                    || collection.ends_with('s')
+                {
+                    continue;
+                }
+
+                lints.push(Lint::new(
+                    join_pointer("/paths", path),
+                    format!(
+                        "Collection {:?} in path {:?} should be plural",
+                        collection, path
+                    ),
+                ));
+            }
+        }
+
+        lints
+    }
+}
+
+/// List operations (`GET` operations responding with arrays) should have
+/// pagination parameters (say, `limit` and `offset`, `page` or `cursor`).
+pub struct ListPagination;
+
+impl<S: Schema> Rule<S> for ListPagination {
+    fn name(&self) -> &str {
+        "list-pagination"
+    }
+
+    fn check(&self, api: &ResolvableApi<S>) -> Vec<Lint> {
+        let mut lints = vec![];
+        for (path, item) in &api.paths {
+            let op = match item.methods.get(&HttpMethod::Get) {
+                Some(op) => op,
+                None => continue,
+            };
+
+            let returns_array = op
+                .responses
+                .iter()
+                .filter(|(code, _)| code.starts_with('2'))
+                .any(|(_, resp)| {
+                    let resp = match resp {
+                        Either::Left(r) => r
+                            .reference
+                            .strip_prefix("#/responses/")
+                            .and_then(|n| api.responses.get(n)),
+                        Either::Right(r) => Some(r),
+                    };
+
+                    resp.and_then(|r| r.read().schema.as_ref().map(|s| is_array(api, s)))
+                        .unwrap_or(false)
+                });
+            if !returns_array {
+                continue;
+            }
+
+            let has_pagination = item
+                .parameters
+                .iter()
+                .chain(&op.parameters)
+                .filter_map(|p| match p {
+                    Either::Left(r) => r
+                        .reference
+                        .strip_prefix("#/parameters/")
+                        .and_then(|n| api.parameters.get(n)),
+                    Either::Right(p) => Some(p),
+                })
+                .any(|p| {
+                    let p = p.read();
+                    p.in_ == ParameterIn::Query
+                        && PAGINATION_PARAMS.contains(&pagination_name(&p.name).as_str())
+                });
+            if !has_pagination {
+                lints.push(Lint::new(
+                    join_pointer(&join_pointer("/paths", path), "get"),
+                    "List operation doesn't have pagination parameters (limit, offset, page or cursor)",
+                ));
+            }
+        }
+
+        lints
+    }
+}
+
+/// Returns the operations in the spec (along with their pointers).
+fn operations<S>(
+    api: &ResolvableApi<S>,
+) -> impl Iterator<Item = (String, &ResolvableOperation<S>)> {
+    api.paths.iter().flat_map(|(path, item)| {
+        let pointer = join_pointer("/paths", path);
+        item.methods.iter().map(move |(method, op)| {
+            (
+                join_pointer(&pointer, &method.to_string().to_lowercase()),
+                op,
+            )
+        })
+    })
+}
+
+/// Checks whether the given schema (or the definition it refers to) is an array.
+fn is_array<S: Schema>(api: &ResolvableApi<S>, schema: &Resolvable<S>) -> bool {
+    let schema = match schema {
+        Resolvable::Raw(s) => s.read(),
+        Resolvable::Resolved { new, .. } => new.read(),
+    };
+
+    if let Some(def) = schema
+        .reference()
+        .and_then(|r| r.strip_prefix("#/definitions/"))
+        .and_then(|n| api.definitions.get(n))
+    {
+        return match def {
+            Resolvable::Raw(s) => s.read().data_type() == Some(DataType::Array),
+            Resolvable::Resolved { new, .. } => new.read().data_type() == Some(DataType::Array),
+        };
+    }
+
+    schema.data_type() == Some(DataType::Array)
+}
+
+/// Lowercases the name and removes separators (so that `page_size`,
+/// `pageSize` and `page-size` are the same).
+fn pagination_name(name: &str) -> String {
+    name.chars()
+        .filter(|c| *c != '_' && *c != '-')
+        .flat_map(char::to_lowercase)
+        .collect()
+}
@@ -0,0 +1,331 @@
+//! Merging multiple v2 specs into one.
+//!
+//! This is useful for combining the specs of several services into a single
+//! (gateway) spec. Objects with the same name (or path) in both specs are
+//! fine as long as they're identical. Otherwise, the [`Policy`](enum.Policy.html)
+//! for that kind of object decides what happens.
+//!
+//! ```rust
+//! use paperclip::v2::merge::{Merger, Policy};
+//! use paperclip::v2::models::DefaultApiRaw;
+//!
+//! let mut pets: DefaultApiRaw = serde_yaml::from_str("
+//! swagger: '2.0'
+//! info: {title: Gateway, version: '1.0'}
+//! basePath: /api
+//! paths:
+//!   /pets:
+//!     get:
+//!       responses:
+//!         '200': {description: OK}
+//! definitions:
+//!   Error: {type: object}
+//! ").unwrap();
+//! let stores: DefaultApiRaw = serde_yaml::from_str("
+//! swagger: '2.0'
+//! info: {title: Stores, version: '2.3'}
+//! basePath: /api/stores
+//! paths:
+//!   /{id}:
+//!     get:
+//!       responses:
+//!         '200': {description: OK}
+//! definitions:
+//!   Error: {type: string}
+//! ").unwrap();
+//!
+//! // Definitions differ, and they're conflicts by default.
+//! assert!(Merger::default().merge(&mut pets.clone(), stores.clone()).is_err());
+//!
+//! Merger::default()
+//!     .definitions(Policy::Keep)
+//!     .merge(&mut pets, stores)
+//!     .unwrap();
+//! assert_eq!(pets.paths.keys().collect::<Vec<_>>(), vec!["/pets", "/stores/{id}"]);
+//! assert_eq!(pets.info.title, "Gateway");
+//! ```
+//!
+//! Only the fields known to the models are merged. Use [overlays](../../overlay/index.html)
+//! for adding (or removing) other fields afterwards.
+
+use super::models::{Api, PathItem, SecurityScheme};
+use crate::error::PaperClipError;
+use crate::util::join_pointer;
+use serde::Serialize;
+
+use std::collections::BTreeMap;
+
+/// What to do when objects in the merged specs are different.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Policy {
+    /// Fail with `PaperClipError::MergeConflict`.
+    Error,
+    /// Keep the object in the spec being merged into.
+    Keep,
+    /// Replace the object with the one from the spec being merged.
+    Replace,
+    /// Combine the objects. Operations of paths are combined (conflicting
+    /// operations are errors), and security schemes are combined like
+    /// `SecurityScheme::append_map` (existing fields are kept and scopes
+    /// are added). This is the same as `Error` for other objects.
+    Merge,
+}
+
+/// Merges specs using the configured policies.
+#[derive(Debug, Clone)]
+pub struct Merger {
+    definitions: Policy,
+    paths: Policy,
+    security_definitions: Policy,
+}
+
+impl Default for Merger {
+    /// Merger which combines paths and security definitions, but fails
+    /// on conflicting definitions (and global parameters and responses).
+    fn default() -> Self {
+        Merger {
+            definitions: Policy::Error,
+            paths: Policy::Merge,
+            security_definitions: Policy::Merge,
+        }
+    }
+}
+
+impl Merger {
+    /// Sets the policy for definitions (and global parameters and responses).
+    pub fn definitions(mut self, policy: Policy) -> Self {
+        self.definitions = policy;
+        self
+    }
+
+    /// Sets the policy for paths.
+    pub fn paths(mut self, policy: Policy) -> Self {
+        self.paths = policy;
+        self
+    }
+
+    /// Sets the policy for security definitions.
+    pub fn security_definitions(mut self, policy: Policy) -> Self {
+        self.security_definitions = policy;
+        self
+    }
+
+    /// Merges the other spec into the given spec.
+    ///
+    /// Paths of the other spec are moved under the base path of the given spec
+    /// (if the base paths are different). Info, host, base path and external
+    /// docs of the given spec are kept, whereas media types, schemes, security
+    /// requirements, tags and codegen extensions are combined.
+    pub fn merge<P, R, S>(
+        &self,
+        api: &mut Api<P, R, S>,
+        other: Api<P, R, S>,
+    ) -> Result<(), PaperClipError>
+    where
+        P: Serialize,
+        R: Serialize,
+        S: Serialize,
+    {
+        let mut paths = BTreeMap::new();
+        for (path, item) in other.paths {
+            let path = rebase(&path, other.base_path.as_deref(), api.base_path.as_deref())
+                .ok_or_else(|| {
+                    PaperClipError::MergeBasePath(
+                        path.clone(),
+                        api.base_path.clone().unwrap_or_default(),
+                    )
+                })?;
+            paths.insert(path, item);
+        }
+
+        self.merge_paths(&mut api.paths, paths)?;
+        merge_map(
+            "Definition",
+            "/definitions",
+            self.definitions,
+            &mut api.definitions,
+            other.definitions,
+        )?;
+        merge_map(
+            "Parameter",
+            "/parameters",
+            self.definitions,
+            &mut api.parameters,
+            other.parameters,
+        )?;
+        merge_map(
+            "Response",
+            "/responses",
+            self.definitions,
+            &mut api.responses,
+            other.responses,
+        )?;
+
+        if self.security_definitions == Policy::Merge {
+            SecurityScheme::append_map(other.security_definitions, &mut api.security_definitions);
+        } else {
+            merge_map(
+                "Security scheme",
+                "/securityDefinitions",
+                self.security_definitions,
+                &mut api.security_definitions,
+                other.security_definitions,
+            )?;
+        }
+
+        api.consumes.extend(other.consumes);
+        api.produces.extend(other.produces);
+        api.schemes.extend(other.schemes);
+        for requirement in other.security {
+            if !api.security.contains(&requirement) {
+                api.security.push(requirement);
+            }
+        }
+
+        for tag in other.tags {
+            if api.tags.iter().all(|t| t.name != tag.name) {
+                api.tags.push(tag);
+            }
+        }
+
+        for (media, coder) in other.coders.iter() {
+            if !api.coders.contains_key(media) {
+                api.coders.insert(media.clone(), coder.clone());
+            }
+        }
+
+        for (name, requirement) in other.support_crates {
+            api.support_crates.entry(name).or_insert(requirement);
+        }
+
+        Ok(())
+    }
+
+    /// Merges all the given specs into the first one (if any).
+    pub fn merge_all<P, R, S, I>(&self, specs: I) -> Result<Option<Api<P, R, S>>, PaperClipError>
+    where
+        I: IntoIterator<Item = Api<P, R, S>>,
+        P: Serialize,
+        R: Serialize,
+        S: Serialize,
+    {
+        let mut specs = specs.into_iter();
+        let mut api = match specs.next() {
+            Some(api) => api,
+            None => return Ok(None),
+        };
+
+        for other in specs {
+            self.merge(&mut api, other)?;
+        }
+
+        Ok(Some(api))
+    }
+
+    fn merge_paths<P, R>(
+        &self,
+        paths: &mut BTreeMap<String, PathItem<P, R>>,
+        other: BTreeMap<String, PathItem<P, R>>,
+    ) -> Result<(), PaperClipError>
+    where
+        P: Serialize,
+        R: Serialize,
+    {
+        if self.paths != Policy::Merge {
+            return merge_map("Path", "/paths", self.paths, paths, other);
+        }
+
+        for (path, item) in other {
+            let existing = match paths.get_mut(&path) {
+                Some(i) => i,
+                None => {
+                    paths.insert(path, item);
+                    continue;
+                }
+            };
+
+            let pointer = join_pointer("/paths", &path);
+            for (method, op) in item.methods {
+                match existing.methods.get(&method) {
+                    Some(o) if !is_same(o, &op) => {
+                        let method = method.to_string();
+                        return Err(PaperClipError::MergeConflict(
+                            "Operation".into(),
+                            format!("{} {}", method.to_uppercase(), path),
+                        )
+                        .at(join_pointer(&pointer, &method.to_lowercase())));
+                    }
+                    Some(_) => (),
+                    None => {
+                        existing.methods.insert(method, op);
+                    }
+                }
+            }
+
+            for param in item.parameters {
+                if !existing.parameters.iter().any(|p| is_same(p, &param)) {
+                    existing.parameters.push(param);
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Merges the objects in the given maps (identified by their names).
+fn merge_map<T: Serialize>(
+    kind: &str,
+    prefix: &str,
+    policy: Policy,
+    map: &mut BTreeMap<String, T>,
+    other: BTreeMap<String, T>,
+) -> Result<(), PaperClipError> {
+    for (name, value) in other {
+        match map.get(&name) {
+            Some(v) if is_same(v, &value) => (),
+            Some(_) => match policy {
+                Policy::Keep => (),
+                Policy::Replace => {
+                    map.insert(name, value);
+                }
+                Policy::Error | Policy::Merge => {
+                    let pointer = join_pointer(prefix, &name);
+                    return Err(PaperClipError::MergeConflict(kind.into(), name).at(pointer));
+                }
+            },
+            None => {
+                map.insert(name, value);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Checks whether the objects are the same (by comparing their JSON values).
+fn is_same<T: Serialize>(a: &T, b: &T) -> bool {
+    match (serde_json::to_value(a), serde_json::to_value(b)) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => false,
+    }
+}
+
+/// Moves the path from one base path to the other. Returns `None` if the
+/// path doesn't fall under the new base path.
+fn rebase(path: &str, from: Option<&str>, to: Option<&str>) -> Option<String> {
+    let (from, to) = (
+        from.unwrap_or_default().trim_end_matches('/'),
+        to.unwrap_or_default().trim_end_matches('/'),
+    );
+    if from == to {
+        return Some(path.into());
+    }
+
+    let full = format!("{}{}", from, path);
+    match full.strip_prefix(to) {
+        Some("") => Some("/".into()),
+        Some(p) if p.starts_with('/') => Some(p.into()),
+        _ => None,
+    }
+}
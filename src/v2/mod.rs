@@ -85,43 +85,188 @@
 
 #[cfg(feature = "codegen")]
 pub mod codegen;
+pub mod diff;
+pub mod filter;
+pub mod lint;
+pub mod merge;
+mod validation;
 
+use crate::bundle::{self, FileLoader, RefLoader};
 use crate::error::PaperClipError;
-use paperclip_core::v2::models::SpecFormat;
-use serde::Deserialize;
+use paperclip_core::v2::models::{SpecFormat, UTF8_BOM};
+use serde::de::DeserializeOwned;
 
-use std::io::{Read, Seek, SeekFrom};
+use std::fs::File;
+use std::io::{BufReader, Cursor, Read};
+use std::path::Path;
 
 #[cfg(feature = "codegen")]
 pub use self::codegen::{DefaultEmitter, Emitter, EmitterState};
+pub use self::validation::validate;
 pub use paperclip_core::im;
 pub use paperclip_core::v2::models::{self, DefaultSchema, ResolvableApi};
 pub use paperclip_core::v2::schema::{self, Schema};
 
 /// Deserialize the schema from the given reader. Currently, this only supports
 /// JSON and YAML formats.
-pub fn from_reader<R, S>(mut reader: R) -> Result<ResolvableApi<S>, PaperClipError>
+///
+/// The format is detected from the contents (ignoring the byte order mark
+/// and leading whitespaces). Use `from_reader_with_format` to override this.
+///
+/// References to other documents are not loaded. Use `from_path` (or
+/// `from_path_with`) for specs split across multiple documents.
+pub fn from_reader<R, S>(reader: R) -> Result<ResolvableApi<S>, PaperClipError>
 where
-    R: Read + Seek,
-    for<'de> S: Deserialize<'de> + Schema,
+    R: Read,
+    S: DeserializeOwned + Schema,
 {
-    let mut buf = [0; 1];
-    reader.read_exact(&mut buf)?;
-    reader.seek(SeekFrom::Start(0))?;
-
-    // FIXME: Support whitespaces
-    let (mut api, fmt) = if buf[0] == b'{' {
-        (
-            serde_json::from_reader::<_, ResolvableApi<S>>(reader)?,
-            SpecFormat::Json,
-        )
+    read_spec(reader, None)
+}
+
+/// Deserialize the schema (in the given format) from the given reader.
+pub fn from_reader_with_format<R, S>(
+    reader: R,
+    format: SpecFormat,
+) -> Result<ResolvableApi<S>, PaperClipError>
+where
+    R: Read,
+    S: DeserializeOwned + Schema,
+{
+    read_spec(reader, Some(format))
+}
+
+/// Deserialize the schema from the given bytes (JSON or YAML).
+pub fn from_slice<S>(contents: &[u8]) -> Result<ResolvableApi<S>, PaperClipError>
+where
+    S: DeserializeOwned + Schema,
+{
+    from_reader(contents)
+}
+
+/// Deserialize the schema from the given string (JSON or YAML).
+pub fn from_str<S>(contents: &str) -> Result<ResolvableApi<S>, PaperClipError>
+where
+    S: DeserializeOwned + Schema,
+{
+    from_reader(contents.as_bytes())
+}
+
+/// Deserialize the schema from the file at the given path. The format is
+/// based on the extension of the file (`.json`, `.yaml` or `.yml`), or the
+/// contents if the extension is something else.
+///
+/// References to other files (say, `models.yaml#/User`) are loaded and
+/// bundled into the spec (see the [`bundle`](../bundle/index.html) module),
+/// so that the spec can be resolved as usual.
+pub fn from_path<P, S>(path: P) -> Result<ResolvableApi<S>, PaperClipError>
+where
+    P: AsRef<Path>,
+    S: DeserializeOwned + Schema,
+{
+    from_path_with(path, &FileLoader)
+}
+
+/// Same as `from_path`, but the spec (file path or URL) and the documents it
+/// references are loaded using the given loader (say, `remote::SpecLoader`
+/// for URLs).
+pub fn from_path_with<P, L, S>(path: P, loader: &L) -> Result<ResolvableApi<S>, PaperClipError>
+where
+    P: AsRef<Path>,
+    L: RefLoader,
+    S: DeserializeOwned + Schema,
+{
+    load_spec(path, loader)
+}
+
+/// An API spec which keeps track of the format it was deserialized from.
+pub(crate) trait FormattedSpec: DeserializeOwned {
+    /// Sets the format of the spec.
+    fn set_spec_format(&mut self, format: SpecFormat);
+}
+
+impl<S> FormattedSpec for ResolvableApi<S>
+where
+    S: DeserializeOwned + Schema,
+{
+    fn set_spec_format(&mut self, format: SpecFormat) {
+        self.spec_format = format;
+    }
+}
+
+/// Deserializes the spec in the given format (detected from the contents
+/// if it's not specified) from the given reader.
+pub(crate) fn read_spec<R, T>(reader: R, format: Option<SpecFormat>) -> Result<T, PaperClipError>
+where
+    R: Read,
+    T: FormattedSpec,
+{
+    let (mut spec, fmt) = decode::<_, T>(reader, format)?;
+    spec.set_spec_format(fmt);
+    Ok(spec)
+}
+
+/// Loads the spec from the given path (or URL) using the given loader, and
+/// bundles the documents it references.
+pub(crate) fn load_spec<P, L, T>(path: P, loader: &L) -> Result<T, PaperClipError>
+where
+    P: AsRef<Path>,
+    L: RefLoader,
+    T: FormattedSpec,
+{
+    let url = bundle::location_url(path)?;
+    let (value, fmt) = bundle::load(&url, loader)?;
+    let mut spec: T = serde_json::from_value(value)?;
+    spec.set_spec_format(fmt);
+    Ok(spec)
+}
+
+/// Deserializes the value in the given format (detected from the contents
+/// if it's not specified) from the given reader.
+pub(crate) fn decode<R, T>(
+    reader: R,
+    format: Option<SpecFormat>,
+) -> Result<(T, SpecFormat), PaperClipError>
+where
+    R: Read,
+    T: DeserializeOwned,
+{
+    // Read until the first significant byte for detecting the format.
+    let mut reader = BufReader::new(reader);
+    let mut prefix = vec![];
+    for byte in reader.by_ref().bytes() {
+        let byte = byte?;
+        prefix.push(byte);
+        if !byte.is_ascii_whitespace() && !UTF8_BOM.starts_with(&prefix) {
+            break;
+        }
+    }
+
+    let format = format.unwrap_or_else(|| SpecFormat::detect(&prefix));
+    let start = if prefix.starts_with(UTF8_BOM) {
+        UTF8_BOM.len()
     } else {
-        (
-            serde_yaml::from_reader::<_, ResolvableApi<S>>(reader)?,
-            SpecFormat::Yaml,
-        )
+        0
     };
 
-    api.spec_format = fmt;
-    Ok(api)
+    prefix.drain(..start);
+    let reader = Cursor::new(prefix).chain(reader);
+    let value = match format {
+        SpecFormat::Json => serde_json::from_reader(reader)?,
+        SpecFormat::Yaml => serde_yaml::from_reader(reader)?,
+    };
+
+    Ok((value, format))
+}
+
+/// Deserializes the value from the file at the given path (using the
+/// extension of the file as a hint for the format).
+pub(crate) fn decode_path<T>(path: &Path) -> Result<(T, SpecFormat), PaperClipError>
+where
+    T: DeserializeOwned,
+{
+    let format = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .and_then(SpecFormat::from_extension);
+    decode(File::open(path)?, format)
 }
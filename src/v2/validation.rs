@@ -0,0 +1,361 @@
+//! Validation pass which collects all the problems in a spec.
+
+use super::models::{
+    DataType, Either, MediaRange, ParameterIn, Reference, Resolvable, ResolvableApi,
+    ResolvableParameter, SecurityRequirement,
+};
+use super::Schema;
+use crate::diagnostics::{Report, Severity};
+use crate::error::PaperClipError;
+use crate::util::join_pointer;
+use paperclip_core::ValidationError;
+
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+
+const DEF_REF_PREFIX: &str = "#/definitions/";
+const PARAM_REF_PREFIX: &str = "#/parameters/";
+const RESP_REF_PREFIX: &str = "#/responses/";
+/// Media types allowed for operations with file parameters.
+const FORM_MEDIA_TYPES: &[&str] = &["multipart/form-data", "application/x-www-form-urlencoded"];
+
+/// Validates the given (unresolved) spec and returns the report of all
+/// the problems found in it.
+///
+/// Unlike `ResolvableApi::resolve` and `Emitter::generate` (which stop at
+/// the first error), this visits the entire spec. It checks references,
+/// parameters (see `Parameter::check`), path templates, status codes,
+/// `consumes` for file parameters, uniqueness of operation IDs, security
+/// schemes (see `SecurityScheme::check`) and security requirements. All
+/// problems are located using the JSON pointers to their nodes.
+pub fn validate<S: Schema>(api: &ResolvableApi<S>) -> Report {
+    let mut validator = Validator {
+        api,
+        report: Report::default(),
+    };
+
+    validator.definitions();
+    validator.security(&api.security, "/security");
+    validator.paths();
+    validator.report
+}
+
+struct Validator<'a, S> {
+    api: &'a ResolvableApi<S>,
+    report: Report,
+}
+
+impl<'a, S> Validator<'a, S>
+where
+    S: Schema,
+{
+    fn error<E: Into<PaperClipError>>(&mut self, err: E, pointer: &str) {
+        self.report.add(Severity::Error, err.into().at(pointer));
+    }
+
+    fn warn<E: Into<PaperClipError>>(&mut self, err: E, pointer: &str) {
+        self.report.add(Severity::Warning, err.into().at(pointer));
+    }
+
+    /// Checks the global definitions, parameters, responses and security schemes.
+    fn definitions(&mut self) {
+        let api = self.api;
+        for (name, schema) in &api.definitions {
+            self.schema(schema, &join_pointer("/definitions", name));
+        }
+
+        for (name, param) in &api.parameters {
+            if let Some(s) = param.read().schema.as_ref() {
+                let pointer = join_pointer(&join_pointer("/parameters", name), "schema");
+                self.schema(s, &pointer);
+            }
+        }
+
+        for (name, resp) in &api.responses {
+            if let Some(s) = resp.read().schema.as_ref() {
+                let pointer = join_pointer(&join_pointer("/responses", name), "schema");
+                self.schema(s, &pointer);
+            }
+        }
+
+        for (name, scheme) in &api.security_definitions {
+            if let Err(e) = scheme.check(name) {
+                self.error(e, &join_pointer("/securityDefinitions", name));
+            }
+        }
+    }
+
+    /// Checks the paths and their operations.
+    fn paths(&mut self) {
+        let api = self.api;
+        let mut rel_paths = HashSet::new();
+        let mut operation_ids = HashMap::<&str, String>::new();
+        for (path, item) in &api.paths {
+            let pointer = join_pointer("/paths", path);
+            let mut template_params = HashSet::new();
+            let path_fmt = ResolvableApi::<()>::path_parameters_map(path, |p| {
+                template_params.insert(p.to_owned());
+                ":".into()
+            });
+            let template = template_params.clone();
+            let mut params = Parameters {
+                path,
+                template: &template,
+                unused: &mut template_params,
+                consumes: &api.consumes,
+            };
+
+            if !rel_paths.insert(path_fmt.into_owned()) {
+                self.error(
+                    PaperClipError::RelativePathNotUnique(path.clone()),
+                    &pointer,
+                );
+            }
+
+            if item.methods.is_empty() {
+                self.warn(ValidationError::MissingOperations(path.clone()), &pointer);
+            }
+
+            let params_pointer = join_pointer(&pointer, "parameters");
+            self.parameters(&item.parameters, &mut params, &params_pointer);
+            for (method, op) in &item.methods {
+                let op_pointer = join_pointer(&pointer, &method.to_string().to_lowercase());
+                if let Some(id) = op.operation_id.as_ref() {
+                    let id_pointer = join_pointer(&op_pointer, "operationId");
+                    match operation_ids.get(id.as_str()) {
+                        Some(p) => self.error(
+                            ValidationError::DuplicateOperationId(id.clone(), p.clone()),
+                            &id_pointer,
+                        ),
+                        None => {
+                            operation_ids.insert(id.as_str(), id_pointer);
+                        }
+                    }
+                }
+
+                params.consumes = op.consumes.as_ref().unwrap_or(&api.consumes);
+                let params_pointer = join_pointer(&op_pointer, "parameters");
+                self.parameters(&op.parameters, &mut params, &params_pointer);
+                if let Some(security) = op.security.as_ref() {
+                    self.security(security, &join_pointer(&op_pointer, "security"));
+                }
+
+                let responses_pointer = join_pointer(&op_pointer, "responses");
+                for (code, resp) in &op.responses {
+                    let resp_pointer = join_pointer(&responses_pointer, code);
+                    if !is_status_code(code) {
+                        self.error(
+                            ValidationError::InvalidStatusCode(code.clone()),
+                            &resp_pointer,
+                        );
+                    }
+
+                    match resp {
+                        // Global responses have already been checked.
+                        Either::Left(r) => {
+                            self.reference(
+                                &r.reference,
+                                RESP_REF_PREFIX,
+                                &api.responses,
+                                &resp_pointer,
+                            );
+                        }
+                        Either::Right(r) => {
+                            if let Some(s) = r.read().schema.as_ref() {
+                                self.schema(s, &join_pointer(&resp_pointer, "schema"));
+                            }
+                        }
+                    }
+                }
+            }
+
+            if !template_params.is_empty() {
+                self.error(
+                    PaperClipError::MissingParametersInPath(path.clone(), template_params),
+                    &pointer,
+                );
+            }
+        }
+    }
+
+    /// Checks the given parameters (of a path or an operation).
+    fn parameters(
+        &mut self,
+        params: &[Either<Reference, ResolvableParameter<S>>],
+        state: &mut Parameters<'_>,
+        pointer: &str,
+    ) {
+        let api = self.api;
+        for (i, param) in params.iter().enumerate() {
+            let param_pointer = format!("{}/{}", pointer, i);
+            let param = match param {
+                // Global parameters have already been checked.
+                Either::Left(r) => {
+                    match self.reference(
+                        &r.reference,
+                        PARAM_REF_PREFIX,
+                        &api.parameters,
+                        &param_pointer,
+                    ) {
+                        Some(p) => p.read(),
+                        None => continue,
+                    }
+                }
+                Either::Right(p) => {
+                    let p = p.read();
+                    if let Some(s) = p.schema.as_ref() {
+                        self.schema(s, &join_pointer(&param_pointer, "schema"));
+                    }
+
+                    p
+                }
+            };
+
+            if let Err(e) = param.check(state.path) {
+                self.error(e, &param_pointer);
+            }
+
+            if param.in_ == ParameterIn::Path {
+                if !state.template.contains(&param.name) {
+                    self.error(
+                        ValidationError::UnknownPathParameter(
+                            param.name.clone(),
+                            state.path.into(),
+                        ),
+                        &param_pointer,
+                    );
+                }
+
+                state.unused.remove(&param.name);
+            }
+
+            if param.data_type == Some(DataType::File) {
+                let err = ValidationError::InvalidFileParameterConsumes(
+                    param.name.clone(),
+                    state.path.into(),
+                );
+                // Missing `consumes` is common in the wild (and clients default
+                // to multipart forms anyway), so it's only a warning.
+                if state.consumes.is_empty() {
+                    self.warn(err, &param_pointer);
+                } else if !state
+                    .consumes
+                    .iter()
+                    .any(|r| FORM_MEDIA_TYPES.contains(&r.0.essence_str()))
+                {
+                    self.error(err, &param_pointer);
+                }
+            }
+        }
+    }
+
+    /// Checks that the given security requirements refer to the
+    /// schemes (and scopes) in security definitions.
+    fn security(&mut self, requirements: &[SecurityRequirement], pointer: &str) {
+        let api = self.api;
+        for (i, requirement) in requirements.iter().enumerate() {
+            let req_pointer = format!("{}/{}", pointer, i);
+            for (name, scopes) in requirement {
+                let scheme_pointer = join_pointer(&req_pointer, name);
+                let scheme = match api.security_definitions.get(name) {
+                    Some(s) => s,
+                    None => {
+                        self.error(
+                            ValidationError::UndefinedSecurityScheme(name.clone()),
+                            &scheme_pointer,
+                        );
+                        continue;
+                    }
+                };
+
+                // Only OAuth2 schemes have scopes.
+                for (j, scope) in scopes.iter().enumerate() {
+                    if !scheme.scopes.contains_key(scope) {
+                        self.error(
+                            ValidationError::UndefinedSecurityScope(scope.clone(), name.clone()),
+                            &format!("{}/{}", scheme_pointer, j),
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    /// Checks the references in the given schema (and its children).
+    fn schema(&mut self, schema: &Resolvable<S>, pointer: &str) {
+        let schema = match schema {
+            Resolvable::Raw(s) => s.read(),
+            // Already resolved (and validated).
+            Resolvable::Resolved { .. } => return,
+        };
+
+        if let Some(r) = schema.reference() {
+            self.reference(r, DEF_REF_PREFIX, &self.api.definitions, pointer);
+            return;
+        }
+
+        if let Some(s) = schema.items() {
+            self.schema(s, &join_pointer(pointer, "items"));
+        }
+
+        if let Some(props) = schema.properties() {
+            let props_pointer = join_pointer(pointer, "properties");
+            for (name, s) in props {
+                self.schema(s, &join_pointer(&props_pointer, name));
+            }
+        }
+
+        if let Some(Either::Right(s)) = schema.additional_properties() {
+            self.schema(s, &join_pointer(pointer, "additionalProperties"));
+        }
+
+        let all_of_pointer = join_pointer(pointer, "allOf");
+        for (i, s) in schema.all_of().unwrap_or_default().iter().enumerate() {
+            self.schema(s, &join_pointer(&all_of_pointer, &i.to_string()));
+        }
+    }
+
+    /// Returns the object for the given reference (if it exists in the given map).
+    fn reference<T>(
+        &mut self,
+        reference: &str,
+        prefix: &str,
+        map: &'a BTreeMap<String, T>,
+        pointer: &str,
+    ) -> Option<&'a T> {
+        let name = match reference.strip_prefix(prefix) {
+            Some(n) => n,
+            None => {
+                self.error(ValidationError::invalid_ref(reference), pointer);
+                return None;
+            }
+        };
+
+        let value = map.get(name);
+        if value.is_none() {
+            self.error(ValidationError::MissingReference(name.into()), pointer);
+        }
+
+        value
+    }
+}
+
+/// State for checking the parameters of a path and its operations.
+struct Parameters<'a> {
+    path: &'a str,
+    /// Parameters in the path template.
+    template: &'a HashSet<String>,
+    /// Template parameters which haven't been defined yet.
+    unused: &'a mut HashSet<String>,
+    /// Media ranges consumed by the operation.
+    consumes: &'a BTreeSet<MediaRange>,
+}
+
+/// Checks whether the given key (in responses) is a valid status code (or `default`).
+fn is_status_code(code: &str) -> bool {
+    code == "default"
+        || (code.len() == 3
+            && code
+                .parse::<u16>()
+                .map(|c| (100..600).contains(&c))
+                .unwrap_or(false))
+}
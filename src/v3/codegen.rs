@@ -0,0 +1,511 @@
+//! Lowering of resolved OpenAPI v3 specs for code generation.
+//!
+//! The emitter only understands the (resolved) v2 models, so a v3 spec is
+//! converted into an equivalent v2 spec before generating code. Schemas are
+//! shared between both versions, which means that the definitions aren't copied.
+//! Stuff which doesn't affect the generated client (callbacks, links, examples)
+//! is dropped along the way.
+
+use super::models::{
+    self, Either, MediaRange, ParameterStyle, ResolvableApi, ResolvableOperation,
+    ResolvableParameter, ResolvablePathItem,
+};
+use crate::error::PaperClipError;
+use crate::v2::{
+    models::{
+        self as v2, ApiKeyIn, CollectionFormat, DataType, DataTypeFormat, HttpMethod, Items,
+        OAuth2Flow, OperationProtocol, ParameterIn, Resolvable, SecuritySchemeType, JSON_MIME,
+    },
+    Schema,
+};
+use anyhow::Error;
+use paperclip_core::ValidationError;
+use parking_lot::RwLock;
+use url::Url;
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::Arc;
+
+/// Media ranges whose schema map to form data parameters in v2.
+const FORM_MEDIA_RANGES: &[&str] = &["application/x-www-form-urlencoded", "multipart/form-data"];
+
+/// Converts the given resolved v3 spec into a resolved v2 spec, so that
+/// it can be fed to the emitter.
+pub fn lower<S: Schema + Default>(
+    api: &ResolvableApi<S>,
+) -> Result<v2::ResolvableApi<S>, PaperClipError> {
+    let mut lowered = v2::ResolvableApi {
+        swagger: v2::Version::V2,
+        definitions: api.components.schemas.clone(),
+        paths: BTreeMap::new(),
+        host: None,
+        base_path: None,
+        consumes: BTreeSet::new(),
+        produces: BTreeSet::new(),
+        schemes: BTreeSet::new(),
+        parameters: BTreeMap::new(),
+        responses: BTreeMap::new(),
+        security_definitions: BTreeMap::new(),
+        security: api.security.clone(),
+        tags: api.tags.clone(),
+        external_docs: api.external_docs.clone(),
+        coders: api.coders.clone(),
+        support_crates: api.support_crates.clone(),
+        spec_format: api.spec_format,
+        info: api.info.clone(),
+        extensions: api.extensions.clone(),
+    };
+
+    if api.servers.len() > 1 {
+        info!("Multiple servers found in spec. Using the first one for the base URL.");
+    }
+
+    if let Some(server) = api.servers.first() {
+        let url = server.default_url();
+        match Url::parse(&url) {
+            Ok(u) => {
+                lowered.host = u.host_str().map(|h| match u.port() {
+                    Some(p) => format!("{}:{}", h, p),
+                    None => h.into(),
+                });
+                if u.path() != "/" {
+                    lowered.base_path = Some(u.path().into());
+                }
+
+                match u.scheme() {
+                    "http" => lowered.schemes.insert(OperationProtocol::Http),
+                    "https" => lowered.schemes.insert(OperationProtocol::Https),
+                    "ws" => lowered.schemes.insert(OperationProtocol::Ws),
+                    "wss" => lowered.schemes.insert(OperationProtocol::Wss),
+                    _ => false,
+                };
+            }
+            // Server URLs are allowed to be relative to the spec's location.
+            Err(url::ParseError::RelativeUrlWithoutBase) => lowered.base_path = Some(url),
+            Err(e) => return Err(PaperClipError::InvalidBasePathURL(url, e).at("/servers/0/url")),
+        }
+    }
+
+    for (name, scheme) in &api.components.security_schemes {
+        if let Some(s) = lower_security_scheme(name, scheme) {
+            lowered.security_definitions.insert(name.clone(), s);
+        }
+    }
+
+    for (path, map) in &api.paths {
+        lowered
+            .paths
+            .insert(path.clone(), lower_path_item(path, map));
+    }
+
+    Ok(lowered)
+}
+
+/// Maps the JSON pointers in errors from the emitter (which refer to
+/// the lowered spec) to the corresponding nodes in the v3 spec.
+pub(crate) fn relocate(err: Error) -> Error {
+    fn map(pointer: &mut String) {
+        if let Some(name) = pointer.strip_prefix("/definitions/") {
+            *pointer = format!("/components/schemas/{}", name);
+        } else if pointer == "/host" {
+            *pointer = "/servers/0/url".into();
+        }
+    }
+
+    match err.downcast::<PaperClipError>() {
+        Ok(PaperClipError::Located(mut p, e)) => {
+            map(&mut p);
+            PaperClipError::Located(p, e).into()
+        }
+        Ok(PaperClipError::Validation(ValidationError::Located(mut p, e))) => {
+            map(&mut p);
+            ValidationError::Located(p, e).into()
+        }
+        Ok(e) => e.into(),
+        Err(err) => match err.downcast::<ValidationError>() {
+            Ok(ValidationError::Located(mut p, e)) => {
+                map(&mut p);
+                ValidationError::Located(p, e).into()
+            }
+            Ok(e) => e.into(),
+            Err(err) => err,
+        },
+    }
+}
+
+/// Converts a v3 security scheme into its v2 counterpart (if there's one).
+fn lower_security_scheme(
+    name: &str,
+    scheme: &models::SecurityScheme,
+) -> Option<v2::SecurityScheme> {
+    let mut lowered = match scheme.type_.as_str() {
+        "apiKey" => {
+            let in_ = match scheme.in_.as_deref() {
+                Some("query") => ApiKeyIn::Query,
+                Some("header") => ApiKeyIn::Header,
+                in_ => {
+                    warn!(
+                        "Skipping API key {:?} in unsupported location {:?}",
+                        name, in_
+                    );
+                    return None;
+                }
+            };
+
+            let mut lowered = v2::SecurityScheme::new(SecuritySchemeType::ApiKey);
+            lowered.name = scheme.name.clone();
+            lowered.in_ = Some(in_);
+            lowered
+        }
+        "http" if scheme.scheme.as_deref() == Some("basic") => {
+            v2::SecurityScheme::new(SecuritySchemeType::Basic)
+        }
+        // Bearer (and other) HTTP schemes go in the `Authorization` header.
+        "http" => {
+            let mut lowered = v2::SecurityScheme::new(SecuritySchemeType::ApiKey);
+            lowered.name = Some("Authorization".into());
+            lowered.in_ = Some(ApiKeyIn::Header);
+            lowered
+        }
+        "oauth2" => {
+            let flows = scheme.flows.as_ref()?;
+            let (flow_type, flow) = None
+                .or_else(|| flows.implicit.as_ref().map(|f| (OAuth2Flow::Implicit, f)))
+                .or_else(|| flows.password.as_ref().map(|f| (OAuth2Flow::Password, f)))
+                .or_else(|| {
+                    flows
+                        .client_credentials
+                        .as_ref()
+                        .map(|f| (OAuth2Flow::Application, f))
+                })
+                .or_else(|| {
+                    flows
+                        .authorization_code
+                        .as_ref()
+                        .map(|f| (OAuth2Flow::AccessCode, f))
+                })?;
+            let mut lowered = v2::SecurityScheme::new(SecuritySchemeType::OAuth2);
+            lowered.flow = Some(flow_type);
+            lowered.auth_url = flow.authorization_url.clone();
+            lowered.token_url = flow.token_url.clone();
+            lowered.scopes = flow.scopes.clone();
+            lowered
+        }
+        _ => {
+            warn!(
+                "Skipping security scheme {:?} of unsupported type {:?}",
+                name, scheme.type_
+            );
+            return None;
+        }
+    };
+
+    lowered.description = scheme.description.clone();
+    lowered.extensions = scheme.extensions.clone();
+    Some(lowered)
+}
+
+/// Converts the operations and parameters in the given path item.
+fn lower_path_item<S: Schema + Default>(
+    path: &str,
+    map: &ResolvablePathItem<S>,
+) -> v2::ResolvablePathItem<S> {
+    v2::PathItem {
+        methods: map
+            .methods
+            .iter()
+            .filter(|(&meth, _)| {
+                if meth == HttpMethod::Trace {
+                    warn!(
+                        "Skipping TRACE operation in {:?} (not supported in v2).",
+                        path
+                    );
+                }
+
+                meth != HttpMethod::Trace
+            })
+            .map(|(&meth, op)| (meth, lower_operation(path, op)))
+            .collect(),
+        parameters: lower_parameters(path, &map.parameters),
+        extensions: map.extensions.clone(),
+    }
+}
+
+/// Converts an operation. Request bodies become body (or form data)
+/// parameters and the media types become `consumes` and `produces`.
+fn lower_operation<S: Schema + Default>(
+    path: &str,
+    op: &ResolvableOperation<S>,
+) -> v2::ResolvableOperation<S> {
+    if !op.callbacks.is_empty() {
+        debug!("Ignoring callbacks for operation in path {:?}", path);
+    }
+
+    let mut parameters = lower_parameters(path, &op.parameters);
+    let mut consumes = None;
+    if let Some(body) = op.request_body.as_ref() {
+        let body = body.read();
+        let (params, ranges) = lower_request_body(&body);
+        parameters.extend(params.into_iter().map(Either::Right));
+        consumes = Some(ranges);
+    }
+
+    let mut produces = BTreeSet::new();
+    let responses = op
+        .responses
+        .iter()
+        .map(|(code, resp)| {
+            let resp = resp.read();
+            produces.extend(resp.content.keys().cloned());
+            (code.clone(), Either::Right(lower_response(&resp)))
+        })
+        .collect();
+
+    v2::Operation {
+        operation_id: op.operation_id.clone(),
+        summary: op.summary.clone(),
+        description: op.description.clone(),
+        consumes,
+        produces: Some(produces),
+        security: op.security.clone(),
+        schemes: BTreeSet::new(),
+        responses,
+        parameters,
+        deprecated: op.deprecated,
+        tags: op.tags.clone(),
+        external_docs: op.external_docs.clone(),
+        extensions: op.extensions.clone(),
+    }
+}
+
+/// Converts the given parameters, skipping those which can't be represented in v2.
+fn lower_parameters<S: Schema + Default>(
+    path: &str,
+    params: &[Either<models::Reference, ResolvableParameter<S>>],
+) -> Vec<Either<models::Reference, v2::ResolvableParameter<S>>> {
+    params
+        .iter()
+        .filter_map(|p| {
+            let p = p.read();
+            let in_ = match p.in_ {
+                models::ParameterIn::Query => ParameterIn::Query,
+                models::ParameterIn::Header => ParameterIn::Header,
+                models::ParameterIn::Path => ParameterIn::Path,
+                models::ParameterIn::Cookie => {
+                    warn!("Skipping cookie parameter {:?} in path {:?}", p.name, path);
+                    return None;
+                }
+            };
+
+            let schema = match p.schema.as_ref() {
+                Some(s) => s.read(),
+                None => {
+                    warn!(
+                        "Skipping parameter {:?} in path {:?} because it doesn't have a schema.",
+                        p.name, path
+                    );
+                    return None;
+                }
+            };
+
+            let dt = schema.data_type();
+            if !dt
+                .map(|t| t.is_primitive() || t == DataType::Array)
+                .unwrap_or(false)
+            {
+                warn!(
+                    "Skipping parameter {:?} in path {:?} with unsupported type {:?}",
+                    p.name, path, dt
+                );
+                return None;
+            }
+
+            let mut param = lower_simple_parameter(&p.name, in_, &*schema);
+            param.description = p.description.clone();
+            param.required = p.required;
+            param.allow_empty_value = p.allow_empty_value;
+            param.extensions = p.extensions.clone();
+            if dt == Some(DataType::Array) {
+                param.collection_format = Some(collection_format(p.in_, p.style, p.explode));
+            }
+
+            Some(Either::Right(Arc::new(RwLock::new(param))))
+        })
+        .collect()
+}
+
+/// Converts a request body into either a body parameter or a bunch of
+/// form data parameters. Also returns the media ranges for `consumes`.
+fn lower_request_body<S: Schema + Default>(
+    body: &models::RequestBody<Resolvable<S>>,
+) -> (Vec<v2::ResolvableParameter<S>>, BTreeSet<MediaRange>) {
+    let is_form = |r: &MediaRange| FORM_MEDIA_RANGES.iter().any(|f| r.0.essence_str() == *f);
+    let (form_ranges, ranges): (BTreeSet<_>, BTreeSet<_>) =
+        body.content.keys().cloned().partition(is_form);
+
+    // We prefer regular bodies over forms if there's a choice.
+    if ranges.is_empty() {
+        let is_multipart = form_ranges
+            .iter()
+            .any(|r| r.0.essence_str() == FORM_MEDIA_RANGES[1]);
+        let schema = form_ranges
+            .iter()
+            .find_map(|r| body.content[r].schema.as_ref());
+        let params = schema
+            .map(|s| lower_form_parameters(&*s.read(), is_multipart))
+            .unwrap_or_default();
+        return (params, form_ranges);
+    }
+
+    let schema = body
+        .content
+        .get(&*JSON_MIME)
+        .and_then(|m| m.schema.as_ref())
+        .or_else(|| ranges.iter().find_map(|r| body.content[r].schema.as_ref()));
+    let params = schema
+        .map(|s| {
+            vec![Arc::new(RwLock::new(v2::Parameter {
+                description: body.description.clone(),
+                in_: ParameterIn::Body,
+                name: "body".into(),
+                required: body.required,
+                schema: Some(s.clone()),
+                ..Default::default()
+            }))]
+        })
+        .unwrap_or_default();
+
+    (params, ranges)
+}
+
+/// Converts the properties of an object schema into form data parameters.
+fn lower_form_parameters<S: Schema + Default>(
+    schema: &S,
+    is_multipart: bool,
+) -> Vec<v2::ResolvableParameter<S>> {
+    let required = schema.required_properties();
+    schema
+        .properties()
+        .map(|props| {
+            props
+                .iter()
+                .map(|(name, prop)| {
+                    let prop = prop.read();
+                    let mut param = lower_simple_parameter(name, ParameterIn::FormData, &*prop);
+                    param.description = prop.description().map(String::from);
+                    param.required = required.map(|r| r.contains(name)).unwrap_or(false);
+                    // Binary strings are files in multipart forms.
+                    if is_multipart
+                        && param.data_type == Some(DataType::String)
+                        && param.format == Some(DataTypeFormat::Binary)
+                    {
+                        param.data_type = Some(DataType::File);
+                        param.format = None;
+                    }
+
+                    Arc::new(RwLock::new(param))
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Creates a non-body parameter using the type information from the given schema.
+fn lower_simple_parameter<S: Schema + Default>(
+    name: &str,
+    in_: ParameterIn,
+    schema: &S,
+) -> v2::Parameter<Resolvable<S>> {
+    let items = lower_items(schema);
+    v2::Parameter {
+        in_,
+        name: name.into(),
+        data_type: items.data_type,
+        format: items.format,
+        items: items.items.map(|i| *i),
+        enum_: items.enum_,
+        ..Default::default()
+    }
+}
+
+/// Converts a schema into the items object used by non-body parameters and headers.
+fn lower_items<S: Schema + Default>(schema: &S) -> Items {
+    Items {
+        data_type: schema.data_type(),
+        format: schema.format().cloned(),
+        items: schema.items().map(|s| Box::new(lower_items(&*s.read()))),
+        enum_: schema
+            .enum_variants()
+            .map(|v| v.to_vec())
+            .unwrap_or_default(),
+        ..Default::default()
+    }
+}
+
+/// Returns the v2 collection format for an array parameter
+/// based on its location, style and explode values.
+fn collection_format(
+    in_: models::ParameterIn,
+    style: Option<ParameterStyle>,
+    explode: Option<bool>,
+) -> CollectionFormat {
+    let style = style.unwrap_or(match in_ {
+        models::ParameterIn::Query | models::ParameterIn::Cookie => ParameterStyle::Form,
+        models::ParameterIn::Path | models::ParameterIn::Header => ParameterStyle::Simple,
+    });
+
+    match style {
+        ParameterStyle::Form if explode.unwrap_or(true) => CollectionFormat::Multi,
+        ParameterStyle::SpaceDelimited => CollectionFormat::Ssv,
+        ParameterStyle::PipeDelimited => CollectionFormat::Pipes,
+        ParameterStyle::Form | ParameterStyle::Simple => CollectionFormat::Csv,
+        s => {
+            warn!("Unsupported parameter style {:?}. Using CSV instead.", s);
+            CollectionFormat::Csv
+        }
+    }
+}
+
+/// Converts a response. JSON schema is preferred when there are multiple media types.
+fn lower_response<S: Schema + Default>(
+    resp: &models::Response<Resolvable<S>>,
+) -> v2::ResolvableResponse<S> {
+    let schema = resp
+        .content
+        .get(&*JSON_MIME)
+        .and_then(|m| m.schema.as_ref())
+        .or_else(|| resp.content.values().find_map(|m| m.schema.as_ref()))
+        .cloned();
+
+    let headers = resp
+        .headers
+        .iter()
+        .filter_map(|(name, header)| {
+            let schema = header.schema.as_ref()?.read();
+            let items = lower_items(&*schema);
+            Some((
+                name.clone(),
+                v2::Header {
+                    description: header.description.clone(),
+                    data_type: items.data_type,
+                    format: items.format,
+                    items: items.items.map(|i| *i),
+                    enum_: items.enum_,
+                    ..Default::default()
+                },
+            ))
+        })
+        .collect();
+
+    Arc::new(RwLock::new(v2::Response {
+        description: Some(resp.description.clone()),
+        schema,
+        headers,
+        examples: resp
+            .content
+            .iter()
+            .filter_map(|(m, media)| Some((m.clone(), media.example.clone()?)))
+            .collect(),
+        extensions: resp.extensions.clone(),
+    }))
+}
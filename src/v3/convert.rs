@@ -0,0 +1,27 @@
+//! Conversion of OpenAPI v2 specs into OpenAPI v3.
+//!
+//! The conversion itself is done by `ResolvableApi::from` (which also carries
+//! the `x-` extensions over to the corresponding objects). The functions here
+//! decode the documents and hand them over to it.
+
+use super::models::{ResolvableApi, SpecFormat};
+use crate::error::PaperClipError;
+use crate::v2::{self, decode, DefaultSchema};
+use serde_json::Value;
+use std::io::Read;
+
+/// Reads an OpenAPI v2 spec (JSON or YAML) from the given reader and
+/// returns the equivalent OpenAPI v3 spec along with the format of the input.
+pub fn from_v2_reader<R>(reader: R) -> Result<(Value, SpecFormat), PaperClipError>
+where
+    R: Read,
+{
+    let (raw, fmt) = decode(reader, None)?;
+    Ok((from_v2_value(raw)?, fmt))
+}
+
+/// Converts the given OpenAPI v2 spec into OpenAPI v3.
+pub fn from_v2_value(raw: Value) -> Result<Value, PaperClipError> {
+    let api: v2::ResolvableApi<DefaultSchema> = serde_json::from_value(raw)?;
+    Ok(serde_json::to_value(ResolvableApi::from(api))?)
+}
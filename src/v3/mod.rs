@@ -0,0 +1,134 @@
+//! Utilities related to the [OpenAPI v3 specification](https://github.com/OAI/OpenAPI-Specification/blob/master/versions/3.0.3.md).
+//!
+//! This works the same way as the v2 module. Schema objects are shared
+//! with v2, so you can use `DefaultSchema` or a struct annotated with
+//! `#[api_v2_schema]` for parsing `components/schemas`.
+//!
+//! ```rust,no_run
+//! use paperclip::v3::{self, ResolvableApi, DefaultSchema, models::Version};
+//!
+//! use std::fs::File;
+//!
+//! let mut fd = File::open("my_spec.yaml").unwrap(); // yaml or json
+//! let api: ResolvableApi<DefaultSchema> = v3::from_reader(&mut fd).unwrap();
+//! assert_eq!(api.openapi, Version::V3_0_3);
+//! ```
+//!
+//! If `codegen` feature is enabled, the spec can be resolved in the same way.
+//!
+//! ```rust,no_run
+//! # use paperclip::v3::{self, ResolvableApi, DefaultSchema};
+//! # let api: ResolvableApi<DefaultSchema> = v3::from_reader(&mut std::io::Cursor::new(vec![])).unwrap();
+//! let resolved = api.resolve().unwrap();
+//! ```
+//!
+//! Code is generated using the same emitter as v2, through `Emitter::generate_v3`.
+//!
+//! ```rust,no_run
+//! # use paperclip::v3::{self, ResolvableApi, DefaultSchema};
+//! # let api: ResolvableApi<DefaultSchema> = v3::from_reader(&mut std::io::Cursor::new(vec![])).unwrap();
+//! use paperclip::v3::{DefaultEmitter, EmitterState, Emitter};
+//!
+//! let mut state = EmitterState::default();
+//! state.working_dir = "/path/to/my/crate".into();
+//! let emitter = DefaultEmitter::from(state);
+//! emitter.generate_v3(&api.resolve().unwrap()).unwrap(); // generate code!
+//! ```
+
+#[cfg(feature = "codegen")]
+pub mod codegen;
+pub mod convert;
+
+use crate::bundle::{FileLoader, RefLoader};
+use crate::error::PaperClipError;
+use crate::v2::{load_spec, read_spec, FormattedSpec};
+use paperclip_core::v3::models::SpecFormat;
+use serde::de::DeserializeOwned;
+
+use std::io::Read;
+use std::path::Path;
+
+#[cfg(feature = "codegen")]
+pub use crate::v2::codegen::{DefaultEmitter, Emitter, EmitterState};
+pub use paperclip_core::v3::models::{self, DefaultSchema, ResolvableApi};
+pub use paperclip_core::v3::Schema;
+
+/// Deserialize the schema from the given reader. Currently, this only supports
+/// JSON and YAML formats.
+///
+/// The format is detected from the contents (ignoring the byte order mark
+/// and leading whitespaces). Use `from_reader_with_format` to override this.
+///
+/// References to other documents are not loaded. Use `from_path` (or
+/// `from_path_with`) for specs split across multiple documents.
+pub fn from_reader<R, S>(reader: R) -> Result<ResolvableApi<S>, PaperClipError>
+where
+    R: Read,
+    S: DeserializeOwned + Schema,
+{
+    read_spec(reader, None)
+}
+
+/// Deserialize the schema (in the given format) from the given reader.
+pub fn from_reader_with_format<R, S>(
+    reader: R,
+    format: SpecFormat,
+) -> Result<ResolvableApi<S>, PaperClipError>
+where
+    R: Read,
+    S: DeserializeOwned + Schema,
+{
+    read_spec(reader, Some(format))
+}
+
+/// Deserialize the schema from the given bytes (JSON or YAML).
+pub fn from_slice<S>(contents: &[u8]) -> Result<ResolvableApi<S>, PaperClipError>
+where
+    S: DeserializeOwned + Schema,
+{
+    from_reader(contents)
+}
+
+/// Deserialize the schema from the given string (JSON or YAML).
+pub fn from_str<S>(contents: &str) -> Result<ResolvableApi<S>, PaperClipError>
+where
+    S: DeserializeOwned + Schema,
+{
+    from_reader(contents.as_bytes())
+}
+
+/// Deserialize the schema from the file at the given path. The format is
+/// based on the extension of the file (`.json`, `.yaml` or `.yml`), or the
+/// contents if the extension is something else.
+///
+/// References to other files (say, `models.yaml#/User`) are loaded and
+/// bundled into the spec (see the [`bundle`](../bundle/index.html) module),
+/// so that the spec can be resolved as usual.
+pub fn from_path<P, S>(path: P) -> Result<ResolvableApi<S>, PaperClipError>
+where
+    P: AsRef<Path>,
+    S: DeserializeOwned + Schema,
+{
+    from_path_with(path, &FileLoader)
+}
+
+/// Same as `from_path`, but the spec (file path or URL) and the documents it
+/// references are loaded using the given loader (say, `remote::SpecLoader`
+/// for URLs).
+pub fn from_path_with<P, L, S>(path: P, loader: &L) -> Result<ResolvableApi<S>, PaperClipError>
+where
+    P: AsRef<Path>,
+    L: RefLoader,
+    S: DeserializeOwned + Schema,
+{
+    load_spec(path, loader)
+}
+
+impl<S> FormattedSpec for ResolvableApi<S>
+where
+    S: DeserializeOwned + Schema,
+{
+    fn set_spec_format(&mut self, format: SpecFormat) {
+        self.spec_format = format;
+    }
+}
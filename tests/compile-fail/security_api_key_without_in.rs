@@ -0,0 +1,7 @@
+use paperclip::actix::Apiv2Security;
+
+#[derive(Apiv2Security)]
+#[openapi(apiKey, name = "X-API-Key")]
+struct ApiKey;
+
+fn main() {}
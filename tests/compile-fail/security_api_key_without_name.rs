@@ -0,0 +1,7 @@
+use paperclip::actix::Apiv2Security;
+
+#[derive(Apiv2Security)]
+#[openapi(apiKey, in = "header")]
+struct ApiKey;
+
+fn main() {}
@@ -0,0 +1,7 @@
+use paperclip::actix::Apiv2Security;
+
+#[derive(Apiv2Security)]
+#[openapi(oauth2, token_url = "https://example.com/token")]
+struct OAuth2Access;
+
+fn main() {}
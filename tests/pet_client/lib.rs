@@ -0,0 +1,264 @@
+//! Runs the client generated for `tests/pet-v2.yaml` (in `tests/test_pet`)
+//! against stub servers, for testing the client module at runtime.
+//!
+//! The client is generated by `test_codegen`, so this crate is tested
+//! after that (see the `test` target in the Makefile).
+
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+use test_pet::client::{ApiClient, ApiError};
+
+/// Canned response of the stub server.
+#[derive(Debug, Clone)]
+pub struct Reply {
+    status: u16,
+    headers: Vec<(String, String)>,
+    body: String,
+    delay: Duration,
+}
+
+impl Reply {
+    /// Empty response with the given status code.
+    pub fn status(status: u16) -> Self {
+        Reply {
+            status,
+            headers: vec![],
+            body: String::new(),
+            delay: Duration::from_secs(0),
+        }
+    }
+
+    /// JSON response with the given status code.
+    pub fn json(status: u16, body: &str) -> Self {
+        Reply {
+            body: body.into(),
+            ..Reply::status(status)
+        }
+        .header("Content-Type", "application/json")
+    }
+
+    /// Adds the given header to this response.
+    pub fn header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// Sends this response only after the given duration.
+    pub fn delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+}
+
+/// Request received by the stub server.
+#[derive(Debug, Clone)]
+pub struct Received {
+    pub method: String,
+    pub path: String,
+    /// Headers (with lowercase names).
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+    /// When the request was received.
+    pub at: Instant,
+}
+
+impl Received {
+    /// Returns the value of the given header (if any).
+    pub fn header(&self, name: &str) -> Option<&str> {
+        let name = name.to_lowercase();
+        self.headers
+            .iter()
+            .find(|(n, _)| *n == name)
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+/// Server replying to the requests with the given responses (in order).
+/// Requests are served concurrently, and requests after the given responses
+/// are answered with 500.
+pub struct StubServer {
+    addr: SocketAddr,
+    stop: Arc<AtomicBool>,
+    received: Arc<Mutex<Vec<(usize, Received)>>>,
+    handle: JoinHandle<Vec<JoinHandle<()>>>,
+}
+
+impl StubServer {
+    /// Starts the server on some available port.
+    pub fn start(replies: Vec<Reply>) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("binding stub server");
+        let addr = listener.local_addr().expect("stub server address");
+        let stop = Arc::new(AtomicBool::new(false));
+        let received = Arc::new(Mutex::new(vec![]));
+
+        let (s, r) = (stop.clone(), received.clone());
+        let handle = thread::spawn(move || {
+            let mut handlers = vec![];
+            for (idx, stream) in listener.incoming().enumerate() {
+                if s.load(Ordering::SeqCst) {
+                    break;
+                }
+
+                let stream = match stream {
+                    Ok(s) => s,
+                    Err(_) => continue,
+                };
+
+                let reply = replies
+                    .get(idx)
+                    .cloned()
+                    .unwrap_or_else(|| Reply::status(500));
+                let received = r.clone();
+                handlers.push(thread::spawn(move || {
+                    let _ = serve(stream, idx, reply, &received);
+                }));
+            }
+
+            handlers
+        });
+
+        StubServer {
+            addr,
+            stop,
+            received,
+            handle,
+        }
+    }
+
+    /// URL for the given path in this server.
+    pub fn url(&self, path: &str) -> String {
+        format!("http://{}{}", self.addr, path)
+    }
+
+    /// Client sending requests to this server.
+    pub fn client(&self) -> StubClient {
+        StubClient {
+            base: self.url(""),
+            client: reqwest::Client::new(),
+        }
+    }
+
+    /// Stops the server and returns the requests it has received (in order).
+    pub fn stop(self) -> Vec<Received> {
+        self.stop.store(true, Ordering::SeqCst);
+        // Wake up the listener.
+        let _ = TcpStream::connect(self.addr);
+        for handler in self.handle.join().expect("joining stub server") {
+            let _ = handler.join();
+        }
+
+        let mut received = self.received.lock().expect("received requests").clone();
+        received.sort_by_key(|(idx, _)| *idx);
+        received.into_iter().map(|(_, r)| r).collect()
+    }
+}
+
+/// Reads a request from the stream and writes the reply.
+fn serve(
+    stream: TcpStream,
+    idx: usize,
+    reply: Reply,
+    received: &Mutex<Vec<(usize, Received)>>,
+) -> io::Result<()> {
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut line = String::new();
+    reader.read_line(&mut line)?;
+    let mut parts = line.split_whitespace();
+    let (method, path) = match (parts.next(), parts.next()) {
+        (Some(m), Some(p)) => (m.to_owned(), p.to_owned()),
+        _ => return Ok(()),
+    };
+
+    let mut headers = vec![];
+    loop {
+        line.clear();
+        reader.read_line(&mut line)?;
+        let header = line.trim_end();
+        if header.is_empty() {
+            break;
+        }
+
+        if let Some(idx) = header.find(':') {
+            let (name, value) = header.split_at(idx);
+            headers.push((name.to_lowercase(), value[1..].trim().to_owned()));
+        }
+    }
+
+    let mut request = Received {
+        method,
+        path,
+        headers,
+        body: String::new(),
+        at: Instant::now(),
+    };
+
+    let mut body = vec![];
+    if request.header("transfer-encoding") == Some("chunked") {
+        loop {
+            line.clear();
+            reader.read_line(&mut line)?;
+            let size = usize::from_str_radix(line.trim(), 16)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+            let mut chunk = vec![0; size + 2];
+            reader.read_exact(&mut chunk)?;
+            if size == 0 {
+                break;
+            }
+
+            body.extend_from_slice(&chunk[..size]);
+        }
+    } else if let Some(len) = request.header("content-length") {
+        body.resize(len.parse().unwrap_or(0), 0);
+        reader.read_exact(&mut body)?;
+    }
+
+    request.body = String::from_utf8_lossy(&body).into_owned();
+    received
+        .lock()
+        .expect("received requests")
+        .push((idx, request));
+
+    thread::sleep(reply.delay);
+    let mut stream = stream;
+    write!(stream, "HTTP/1.1 {} Stub\r\n", reply.status)?;
+    for (name, value) in &reply.headers {
+        write!(stream, "{}: {}\r\n", name, value)?;
+    }
+
+    write!(
+        stream,
+        "Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+        reply.body.len(),
+        reply.body
+    )?;
+    stream.flush()
+}
+
+/// Client sending the requests to a stub server (instead of the host in the spec).
+#[derive(Clone)]
+pub struct StubClient {
+    base: String,
+    client: reqwest::Client,
+}
+
+#[async_trait::async_trait]
+impl ApiClient for StubClient {
+    type Request = reqwest::RequestBuilder;
+    type Response = reqwest::Response;
+
+    fn request_builder(&self, method: http::Method, rel_path: &str) -> Self::Request {
+        self.client
+            .request(method, &format!("{}{}", self.base, rel_path))
+    }
+
+    async fn make_request(
+        &self,
+        req: Self::Request,
+    ) -> Result<Self::Response, ApiError<Self::Response>> {
+        Ok(req.send().await.map_err(ApiError::Reqwest)?)
+    }
+}
@@ -0,0 +1,156 @@
+use pet_client::{Reply, StubClient, StubServer};
+use test_pet::client::{
+    ApiError, InterceptedClient, Interceptor, Request, RequestInfo, RetryClient, RetryPolicy,
+    Sendable, WrappedRequest,
+};
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+type Pet = test_pet::pet::Pet<serde_yaml::Value>;
+type Log = Arc<Mutex<Vec<String>>>;
+
+const PET: &str = r#"{"id": 1, "name": "Milo"}"#;
+
+/// Records the calls to its hooks (and adds a header to the requests).
+struct Recorder {
+    name: &'static str,
+    log: Log,
+}
+
+#[async_trait::async_trait]
+impl Interceptor<StubClient> for Recorder {
+    async fn before_request(
+        &self,
+        req: WrappedRequest<reqwest::RequestBuilder>,
+    ) -> Result<WrappedRequest<reqwest::RequestBuilder>, ApiError<reqwest::Response>> {
+        self.log.lock().unwrap().push(format!(
+            "{}: before {} {}",
+            self.name,
+            req.method(),
+            req.rel_path()
+        ));
+        Ok(req.header("X-Interceptor", self.name))
+    }
+
+    async fn after_response(
+        &self,
+        info: &RequestInfo,
+        result: Result<reqwest::Response, ApiError<reqwest::Response>>,
+    ) -> Result<reqwest::Response, ApiError<reqwest::Response>> {
+        let status = match &result {
+            Ok(resp) => resp.status().as_u16(),
+            Err(ApiError::Failure(_, status, _)) => status.as_u16(),
+            Err(_) => 0,
+        };
+
+        self.log.lock().unwrap().push(format!(
+            "{}: after {} {} ({})",
+            self.name, info.method, info.rel_path, status
+        ));
+        result
+    }
+}
+
+/// Rejects all requests.
+struct Deny;
+
+#[async_trait::async_trait]
+impl Interceptor<StubClient> for Deny {
+    async fn before_request(
+        &self,
+        _: WrappedRequest<reqwest::RequestBuilder>,
+    ) -> Result<WrappedRequest<reqwest::RequestBuilder>, ApiError<reqwest::Response>> {
+        Err(ApiError::Auth("denied".into()))
+    }
+}
+
+fn recorder(name: &'static str, log: &Log) -> Recorder {
+    Recorder {
+        name,
+        log: log.clone(),
+    }
+}
+
+#[tokio::test(threaded_scheduler)]
+async fn test_hooks_order() {
+    let server = StubServer::start(vec![Reply::json(200, PET)]);
+    let log = Log::default();
+    let client = InterceptedClient::new(server.client())
+        .interceptor(recorder("First", &log))
+        .interceptor(recorder("Second", &log));
+
+    let pet = Pet::add_pet()
+        .x_auth("milo")
+        .id(1)
+        .name("Milo")
+        .send(&client)
+        .await
+        .expect("adding pet");
+    assert_eq!(pet.name, "Milo");
+
+    assert_eq!(
+        *log.lock().unwrap(),
+        vec![
+            "First: before POST /pets",
+            "Second: before POST /pets",
+            "Second: after POST /pets (200)",
+            "First: after POST /pets (200)",
+        ]
+    );
+
+    let requests = server.stop();
+    assert_eq!(requests.len(), 1);
+    let headers = requests[0]
+        .headers
+        .iter()
+        .filter(|(n, _)| n == "x-interceptor")
+        .map(|(_, v)| v.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(headers, ["First", "Second"]);
+}
+
+#[tokio::test(threaded_scheduler)]
+async fn test_error_stops_request() {
+    let server = StubServer::start(vec![Reply::json(200, "[]")]);
+    let log = Log::default();
+    let client = InterceptedClient::new(server.client())
+        .interceptor(Deny)
+        .interceptor(recorder("Recorder", &log));
+
+    match Pet::list_pets().send(&client).await {
+        Err(ApiError::Auth(e)) => assert_eq!(e.to_string(), "denied"),
+        r => panic!("unexpected result: {:?}", r.map(|_| ())),
+    }
+
+    // Neither the request nor the following hooks.
+    assert!(log.lock().unwrap().is_empty());
+    assert!(server.stop().is_empty());
+}
+
+#[tokio::test(threaded_scheduler)]
+async fn test_hooks_for_each_attempt() {
+    let server = StubServer::start(vec![Reply::status(503), Reply::json(200, "[]")]);
+    let log = Log::default();
+    let client = InterceptedClient::new(server.client()).interceptor(recorder("Recorder", &log));
+    let policy = RetryPolicy::default().backoff(Duration::from_millis(10), Duration::from_secs(1));
+    let client = RetryClient::new(client, policy);
+
+    Pet::list_pets().send(&client).await.expect("listing pets");
+
+    assert_eq!(
+        *log.lock().unwrap(),
+        vec![
+            "Recorder: before GET /pets",
+            "Recorder: after GET /pets (503)",
+            "Recorder: before GET /pets",
+            "Recorder: after GET /pets (200)",
+        ]
+    );
+
+    let requests = server.stop();
+    assert_eq!(requests.len(), 2);
+    assert!(requests
+        .iter()
+        .all(|r| r.header("x-interceptor") == Some("Recorder")));
+}
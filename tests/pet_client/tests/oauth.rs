@@ -0,0 +1,130 @@
+use pet_client::{Received, Reply, StubServer};
+use test_pet::client::{Auth, AuthClient, OAuth2TokenProvider, Sendable, TokenProvider};
+
+use std::time::Duration;
+
+type Pet = test_pet::pet::Pet<serde_yaml::Value>;
+
+/// Token responses for `check_tokens`.
+fn token_replies() -> Vec<Reply> {
+    vec![
+        Reply::json(
+            200,
+            r#"{"access_token": "t1", "token_type": "bearer", "expires_in": 1, "refresh_token": "r1"}"#,
+        ),
+        Reply::json(200, r#"{"access_token": "t2", "token_type": "bearer"}"#),
+        Reply::json(
+            200,
+            r#"{"access_token": "t3", "token_type": "bearer", "expires_in": 1}"#,
+        ),
+        Reply::json(400, r#"{"error": "invalid_grant"}"#),
+        Reply::json(200, r#"{"access_token": "t4", "token_type": "bearer"}"#),
+    ]
+}
+
+/// Gets tokens from the provider for the responses in `token_replies`.
+async fn check_tokens(provider: &OAuth2TokenProvider) {
+    // Fetched and cached for the scopes (in any order).
+    assert_eq!(provider.token(&["write", "read"]).await.unwrap(), "t1");
+    assert_eq!(provider.token(&["read", "write"]).await.unwrap(), "t1");
+    // Other scopes have their own tokens.
+    assert_eq!(provider.token(&["read"]).await.unwrap(), "t2");
+    assert_eq!(provider.token(&["read"]).await.unwrap(), "t2");
+
+    // Refreshed (halfway through its lifetime) using the refresh token.
+    tokio::time::delay_for(Duration::from_millis(600)).await;
+    assert_eq!(provider.token(&["read", "write"]).await.unwrap(), "t3");
+    assert_eq!(provider.token(&["read", "write"]).await.unwrap(), "t3");
+
+    // The refresh token is kept, but it's rejected, so the token is requested again.
+    tokio::time::delay_for(Duration::from_millis(600)).await;
+    assert_eq!(provider.token(&["read", "write"]).await.unwrap(), "t4");
+    assert_eq!(provider.token(&["read"]).await.unwrap(), "t2");
+}
+
+/// Checks the requests for `check_tokens` with the form for the grant.
+fn check_requests(requests: &[Received], grant: &str) {
+    let forms = requests
+        .iter()
+        .map(|r| {
+            assert_eq!((r.method.as_str(), r.path.as_str()), ("POST", "/token"));
+            assert_eq!(
+                r.header("content-type"),
+                Some("application/x-www-form-urlencoded")
+            );
+            r.body.as_str()
+        })
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        forms,
+        vec![
+            format!("{}&scope=read+write", grant),
+            format!("{}&scope=read", grant),
+            "grant_type=refresh_token&refresh_token=r1&scope=read+write".into(),
+            "grant_type=refresh_token&refresh_token=r1&scope=read+write".into(),
+            format!("{}&scope=read+write", grant),
+        ]
+    );
+}
+
+#[tokio::test(threaded_scheduler)]
+async fn test_client_credentials() {
+    let server = StubServer::start(token_replies());
+    let provider =
+        OAuth2TokenProvider::client_credentials(server.url("/token"), "client", "secret");
+
+    check_tokens(&provider).await;
+
+    let requests = server.stop();
+    check_requests(&requests, "grant_type=client_credentials");
+    // Client credentials are sent with basic auth.
+    assert!(requests
+        .iter()
+        .all(|r| r.header("authorization") == Some("Basic Y2xpZW50OnNlY3JldA==")));
+}
+
+#[tokio::test(threaded_scheduler)]
+async fn test_password() {
+    let server = StubServer::start(token_replies());
+    let provider = OAuth2TokenProvider::password(server.url("/token"), "milo", "pass word");
+
+    check_tokens(&provider).await;
+
+    let requests = server.stop();
+    check_requests(
+        &requests,
+        "grant_type=password&username=milo&password=pass+word",
+    );
+    assert!(requests.iter().all(|r| r.header("authorization").is_none()));
+}
+
+#[tokio::test(threaded_scheduler)]
+async fn test_token_for_operation() {
+    let server = StubServer::start(vec![
+        Reply::json(200, r#"{"access_token": "t1", "token_type": "bearer"}"#),
+        Reply::json(200, r#"{"id": 1, "name": "Milo"}"#),
+    ]);
+    let provider =
+        OAuth2TokenProvider::client_credentials(server.url("/token"), "client", "secret");
+    let auth = Auth::default().oauth2("petstore_auth", provider);
+    let client = AuthClient::new(server.client(), auth);
+
+    Pet::add_pet()
+        .x_auth("milo")
+        .id(1)
+        .name("Milo")
+        .send(&client)
+        .await
+        .expect("adding pet");
+
+    let requests = server.stop();
+    assert_eq!(requests.len(), 2);
+    // Token for the scopes required by the operation.
+    assert_eq!(
+        requests[0].body,
+        "grant_type=client_credentials&scope=write%3Apets"
+    );
+    assert_eq!(requests[1].path, "/pets");
+    assert_eq!(requests[1].header("authorization"), Some("Bearer t1"));
+}
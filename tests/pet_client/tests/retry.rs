@@ -0,0 +1,166 @@
+use pet_client::{Reply, StubServer};
+use test_pet::client::{ApiError, RetryClient, RetryPolicy, Sendable};
+use test_pet::status::Status;
+
+use std::time::Duration;
+
+type Pet = test_pet::pet::Pet<serde_yaml::Value>;
+
+const PET: &str = r#"{"id": 1, "name": "Milo"}"#;
+
+fn policy() -> RetryPolicy {
+    RetryPolicy::default()
+        .backoff(Duration::from_millis(20), Duration::from_secs(2))
+        .jitter(false)
+}
+
+#[tokio::test(threaded_scheduler)]
+async fn test_unavailable_is_retried() {
+    let server = StubServer::start(vec![Reply::status(503), Reply::json(200, "[]")]);
+    let client = RetryClient::new(server.client(), policy());
+
+    let pets = Pet::list_pets().send(&client).await.expect("listing pets");
+    assert!(pets.is_empty());
+
+    let requests = server.stop();
+    assert_eq!(requests.len(), 2);
+    for req in &requests {
+        assert_eq!((req.method.as_str(), req.path.as_str()), ("GET", "/pets"));
+    }
+
+    // Backoff between the attempts.
+    assert!(requests[1].at - requests[0].at >= Duration::from_millis(20));
+}
+
+#[tokio::test(threaded_scheduler)]
+async fn test_retry_after_is_honored() {
+    let server = StubServer::start(vec![
+        Reply::status(429).header("Retry-After", "1"),
+        Reply::json(200, "[]"),
+    ]);
+    let client = RetryClient::new(server.client(), policy());
+
+    Pet::list_pets().send(&client).await.expect("listing pets");
+
+    let requests = server.stop();
+    assert_eq!(requests.len(), 2);
+    assert!(requests[1].at - requests[0].at >= Duration::from_secs(1));
+}
+
+#[tokio::test(threaded_scheduler)]
+async fn test_long_retry_after_returns_response() {
+    // `Retry-After` is longer than the maximum backoff.
+    let server = StubServer::start(vec![Reply::status(503).header("Retry-After", "60")]);
+    let client = RetryClient::new(server.client(), policy());
+
+    match Pet::list_pets().send(&client).await {
+        Err(ApiError::Failure(_, status, _)) => assert_eq!(status, 503),
+        r => panic!("unexpected result: {:?}", r.map(|_| ())),
+    }
+
+    assert_eq!(server.stop().len(), 1);
+}
+
+#[tokio::test(threaded_scheduler)]
+async fn test_retries_are_limited() {
+    let server = StubServer::start(vec![Reply::status(500), Reply::status(502)]);
+    let client = RetryClient::new(server.client(), policy().max_retries(1));
+
+    match Pet::list_pets().send(&client).await {
+        Err(ApiError::Failure(_, status, _)) => assert_eq!(status, 502),
+        r => panic!("unexpected result: {:?}", r.map(|_| ())),
+    }
+
+    assert_eq!(server.stop().len(), 2);
+}
+
+#[tokio::test(threaded_scheduler)]
+async fn test_post_is_not_retried() {
+    let server = StubServer::start(vec![Reply::status(503), Reply::json(200, PET)]);
+    let client = RetryClient::new(server.client(), policy());
+
+    let result = Pet::add_pet()
+        .x_auth("milo")
+        .id(1)
+        .name("Milo")
+        .send(&client)
+        .await;
+    match result {
+        Err(ApiError::Failure(_, status, _)) => assert_eq!(status, 503),
+        r => panic!("unexpected result: {:?}", r.map(|_| ())),
+    }
+
+    let requests = server.stop();
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0].method, "POST");
+
+    // ... unless the policy allows it.
+    let server = StubServer::start(vec![Reply::status(503), Reply::json(200, PET)]);
+    let policy = policy().methods(vec![http::Method::GET, http::Method::POST]);
+    let client = RetryClient::new(server.client(), policy);
+
+    let pet = Pet::add_pet()
+        .x_auth("milo")
+        .id(1)
+        .name("Milo")
+        .send(&client)
+        .await
+        .expect("adding pet");
+    assert_eq!(pet.name, "Milo");
+
+    let requests = server.stop();
+    assert_eq!(requests.len(), 2);
+    assert_eq!(requests[0].body, requests[1].body);
+}
+
+#[tokio::test(threaded_scheduler)]
+async fn test_attempt_timeout() {
+    let timeout = Duration::from_millis(200);
+    let server = StubServer::start(vec![Reply::json(200, "[]").delay(Duration::from_secs(1))]);
+    let client = RetryClient::new(server.client(), policy().max_retries(0).timeout(timeout));
+
+    match Pet::list_pets().send(&client).await {
+        Err(ApiError::Timeout(t)) => assert_eq!(t, timeout),
+        r => panic!("unexpected result: {:?}", r.map(|_| ())),
+    }
+
+    assert_eq!(server.stop().len(), 1);
+
+    // Timed out attempts are retried.
+    let server = StubServer::start(vec![
+        Reply::json(200, "[]").delay(Duration::from_secs(1)),
+        Reply::json(200, "[]"),
+    ]);
+    let client = RetryClient::new(server.client(), policy().timeout(timeout));
+
+    Pet::list_pets().send(&client).await.expect("listing pets");
+    assert_eq!(server.stop().len(), 2);
+}
+
+#[tokio::test(threaded_scheduler)]
+async fn test_streaming_body_is_sent_once() {
+    let path = std::env::temp_dir().join("paperclip-pet-client-retry.txt");
+    std::fs::write(&path, "streamed file contents").expect("writing file");
+
+    // `PUT` can be retried, but the file is streamed, so the request can't be cloned.
+    let server = StubServer::start(vec![Reply::status(503), Reply::json(200, "{}")]);
+    let client = RetryClient::new(server.client(), policy());
+
+    let result = Status::put_1()
+        .some_data_file(&path)
+        .foobar("bar")
+        .send(&client)
+        .await;
+    match result {
+        Err(ApiError::Failure(_, status, _)) => assert_eq!(status, 503),
+        r => panic!("unexpected result: {:?}", r.map(|_| ())),
+    }
+
+    let requests = server.stop();
+    assert_eq!(requests.len(), 1);
+    assert_eq!(
+        (requests[0].method.as_str(), requests[0].path.as_str()),
+        ("PUT", "/test/file")
+    );
+    assert!(requests[0].body.contains("streamed file contents"));
+}
@@ -0,0 +1,134 @@
+use paperclip::v2::{
+    self,
+    codegen::{DefaultEmitter, Emitter, EmitterState},
+    models::{DefaultSchema, DefaultSchemaRaw, Resolvable, ResolvableApi},
+    Schema,
+};
+
+use std::fs;
+use std::sync::Arc;
+
+const SPEC: &str = "swagger: '2.0'
+info: {title: Pets, version: '1.0'}
+paths:
+  /dogs:
+    get:
+      responses:
+        '200':
+          description: OK
+          schema:
+            $ref: '#/definitions/Dog'
+definitions:
+  Pet:
+    type: object
+    required: [name]
+    properties:
+      name: {type: string}
+      tag: {type: string}
+  Dog:
+    allOf:
+      - $ref: '#/definitions/Pet'
+      - type: object
+        required: [bark]
+        properties:
+          bark: {type: boolean}
+          parent:
+            $ref: '#/definitions/Dog'
+";
+
+#[test]
+fn test_all_of_roundtrip() {
+    let schema: DefaultSchemaRaw = serde_yaml::from_str(
+        "
+allOf:
+  - $ref: '#/definitions/Pet'
+  - properties:
+      bark: {type: boolean}
+",
+    )
+    .expect("deserializing schema");
+
+    assert_eq!(schema.all_of.len(), 2);
+    assert_eq!(
+        schema.all_of[0].reference.as_deref(),
+        Some("#/definitions/Pet")
+    );
+    assert_eq!(
+        serde_json::to_value(&schema).expect("serializing schema"),
+        serde_json::json!({
+            "allOf": [
+                {"$ref": "#/definitions/Pet"},
+                {"properties": {"bark": {"type": "boolean"}}}
+            ]
+        })
+    );
+}
+
+#[test]
+fn test_all_of_resolution() {
+    let api: ResolvableApi<DefaultSchema> = v2::from_str(SPEC).expect("deserializing spec");
+    let api = api.resolve().expect("resolution");
+
+    let dog = api.definitions["Dog"].read();
+    let all_of = dog.all_of().expect("composed schemas");
+    match &all_of[0] {
+        Resolvable::Resolved { new, .. } => assert_eq!(new.read().name(), Some("Pet")),
+        Resolvable::Raw(_) => panic!("expected resolved schema"),
+    }
+
+    let parent = &all_of[1].read().properties["parent"];
+    match parent {
+        Resolvable::Resolved { new, .. } => assert!(Arc::ptr_eq(new, &api.definitions["Dog"])),
+        Resolvable::Raw(_) => panic!("expected resolved schema"),
+    }
+
+    assert!(dog.is_cyclic());
+    assert!(!api.definitions["Pet"].read().is_cyclic());
+    assert!(!dog.contains_any());
+}
+
+#[test]
+fn test_all_of_unresolved_ref() {
+    let api: ResolvableApi<DefaultSchema> = v2::from_str(
+        "swagger: '2.0'
+info: {title: Pets, version: '1.0'}
+paths: {}
+definitions:
+  Dog:
+    allOf:
+      - $ref: '#/definitions/Pet'
+",
+    )
+    .expect("deserializing spec");
+
+    let err = api.resolve().unwrap_err();
+    assert_eq!(err.pointer(), Some("/definitions/Dog/allOf/0"));
+}
+
+#[test]
+fn test_all_of_in_emitter() {
+    let api: ResolvableApi<DefaultSchema> = v2::from_str(SPEC).expect("deserializing spec");
+
+    let mut state = EmitterState::default();
+    state.working_dir = std::env::temp_dir().join("paperclip-test-all-of");
+    let _ = fs::remove_dir_all(&state.working_dir);
+    let dir = state.working_dir.clone();
+
+    let emitter = DefaultEmitter::from(state);
+    emitter
+        .generate(&api.resolve().expect("resolution"))
+        .expect("codegen");
+
+    let contents = fs::read_to_string(dir.join("dog.rs")).expect("reading generated code");
+    assert!(contents.starts_with(
+        "#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct Dog {
+    pub bark: bool,
+    pub name: String,
+    pub parent: Option<Box<crate::dog::Dog>>,
+    pub tag: Option<String>,
+}
+"
+    ));
+    let _ = fs::remove_dir_all(&dir);
+}
@@ -1321,17 +1321,17 @@ fn test_tags() {
                 Tag {
                     name: "Dogs".to_string(),
                     description: Some("Images of dogs".to_string()),
-                    external_docs: None,
+                    ..Default::default()
                 },
                 Tag {
                     name: "Cats".to_string(),
                     description: Some("Images of cats".to_string()),
-                    external_docs: None,
+                    ..Default::default()
                 },
                 Tag {
                     name: "Cars".to_string(),
                     description: Some("Images of nice cars".to_string()),
-                    external_docs: None,
+                    ..Default::default()
                 },
             ];
             spec.info = Info {
@@ -2515,6 +2515,458 @@ fn test_security_app() {
     );
 }
 
+#[test]
+fn test_v3_spec() {
+    #[derive(Apiv2Security, Deserialize)]
+    #[openapi(apiKey, alias = "JWT", in = "header", name = "Authorization")]
+    struct AccessToken;
+
+    impl FromRequest for AccessToken {
+        type Future = Ready<Result<Self, Self::Error>>;
+        type Error = Error;
+        type Config = ();
+
+        fn from_request(_: &HttpRequest, _payload: &mut actix_web::dev::Payload) -> Self::Future {
+            ready(Ok(Self {}))
+        }
+    }
+
+    #[derive(Deserialize, Apiv2Schema)]
+    struct Filter {
+        limit: Option<u16>,
+    }
+
+    #[api_v2_operation]
+    async fn echo_pet(_: AccessToken, body: web::Json<Pet>) -> web::Json<Pet> {
+        body
+    }
+
+    #[api_v2_operation]
+    async fn get_pet(_id: web::Path<u64>, _filter: web::Query<Filter>) -> web::Json<Pet> {
+        web::Json(Pet::default())
+    }
+
+    run_and_check_app(
+        || {
+            App::new()
+                .wrap_api()
+                .service(
+                    web::scope("/api")
+                        .service(web::resource("/echo").route(web::post().to(echo_pet)))
+                        .service(web::resource("/pets/{id}").route(web::get().to(get_pet))),
+                )
+                .with_json_spec_at("/spec")
+                .with_json_spec_v3_at("/spec/v3")
+                .build()
+        },
+        |addr| {
+            let resp = CLIENT
+                .get(&format!("http://{}/spec/v3", addr))
+                .send()
+                .expect("request failed?");
+
+            check_json(
+                resp,
+                json!({
+                  "openapi": "3.0.3",
+                  "info": {"title": "", "version": ""},
+                  "components": {
+                    "schemas": {
+                      "Pet": {
+                        "properties": {
+                          "class": {
+                            "enum": ["dog", "cat", "other"],
+                            "type": "string"
+                          },
+                          "id": {
+                            "format": "int64",
+                            "type": "integer"
+                          },
+                          "name": {
+                            "description": "Pick a good one.",
+                            "type": "string"
+                          },
+                          "birthday": {
+                            "format": "date",
+                            "type": "string"
+                          },
+                          "updatedOn": {
+                            "format": "date-time",
+                            "type": "string"
+                          },
+                          "uuid": {
+                            "format": "uuid",
+                            "type": "string"
+                          }
+                        },
+                        "required": ["birthday", "class", "name"],
+                        "type": "object"
+                      }
+                    },
+                    "securitySchemes": {
+                      "JWT": {
+                        "in": "header",
+                        "name": "Authorization",
+                        "type": "apiKey"
+                      }
+                    }
+                  },
+                  "paths": {
+                    "/api/echo": {
+                      "post": {
+                        "requestBody": {
+                          "content": {
+                            "application/json": {
+                              "schema": {
+                                "$ref": "#/components/schemas/Pet"
+                              }
+                            }
+                          },
+                          "required": true
+                        },
+                        "responses": {
+                          "200": {
+                            "content": {
+                              "application/json": {
+                                "schema": {
+                                  "$ref": "#/components/schemas/Pet"
+                                }
+                              }
+                            },
+                            "description": "OK"
+                          }
+                        },
+                        "security": [
+                          {
+                            "JWT": []
+                          }
+                        ]
+                      }
+                    },
+                    "/api/pets/{id}": {
+                      "get": {
+                        "parameters": [{
+                          "in": "path",
+                          "name": "id",
+                          "required": true,
+                          "schema": {
+                            "format": "int64",
+                            "type": "integer"
+                          }
+                        }, {
+                          "in": "query",
+                          "name": "limit",
+                          "schema": {
+                            "format": "int32",
+                            "type": "integer"
+                          }
+                        }],
+                        "responses": {
+                          "200": {
+                            "content": {
+                              "application/json": {
+                                "schema": {
+                                  "$ref": "#/components/schemas/Pet"
+                                }
+                              }
+                            },
+                            "description": "OK"
+                          }
+                        }
+                      }
+                    }
+                  }
+                }),
+            );
+
+            // The converted spec is kept for later requests.
+            let fetch = || {
+                CLIENT
+                    .get(&format!("http://{}/spec/v3", addr))
+                    .send()
+                    .expect("request failed?")
+                    .json::<serde_json::Value>()
+                    .expect("json error")
+            };
+            assert_eq!(fetch(), fetch());
+        },
+    );
+}
+
+#[test]
+fn test_field_constraints() {
+    #[derive(Deserialize, Serialize, Apiv2Schema)]
+    struct Pet {
+        #[openapi(min_length = 1, max_length = 64, pattern = "^[A-Za-z ]+$")]
+        name: String,
+        #[openapi(minimum = 0, maximum = 30.5, exclusive_maximum)]
+        age: f32,
+        #[openapi(max_items = 5, unique_items)]
+        tags: Vec<String>,
+        #[openapi(read_only)]
+        id: Option<u64>,
+    }
+
+    #[api_v2_operation]
+    async fn add_pet(body: web::Json<Pet>) -> Result<web::Json<Pet>, ()> {
+        Ok(body)
+    }
+
+    run_and_check_app(
+        || {
+            App::new()
+                .wrap_api()
+                .with_json_spec_at("/api/spec")
+                .service(web::resource("/pets").route(web::post().to(add_pet)))
+                .build()
+        },
+        |addr| {
+            let resp = CLIENT
+                .get(&format!("http://{}/api/spec", addr))
+                .send()
+                .expect("request failed?");
+
+            check_json(
+                resp,
+                json!({
+                  "info":{"title":"","version":""},
+                  "definitions": {
+                    "Pet": {
+                      "properties": {
+                        "age": {
+                          "exclusiveMaximum": true,
+                          "format": "float",
+                          "maximum": 30.5,
+                          "minimum": 0.0,
+                          "type": "number"
+                        },
+                        "id": {
+                          "format": "int64",
+                          "readOnly": true,
+                          "type": "integer"
+                        },
+                        "name": {
+                          "maxLength": 64,
+                          "minLength": 1,
+                          "pattern": "^[A-Za-z ]+$",
+                          "type": "string"
+                        },
+                        "tags": {
+                          "items": {
+                            "type": "string"
+                          },
+                          "maxItems": 5,
+                          "type": "array",
+                          "uniqueItems": true
+                        }
+                      },
+                      "required": ["age", "name", "tags"],
+                      "type": "object"
+                    }
+                  },
+                  "paths": {
+                    "/pets": {
+                      "post": {
+                        "parameters": [{
+                          "in": "body",
+                          "name": "body",
+                          "required": true,
+                          "schema": {
+                            "$ref": "#/definitions/Pet"
+                          }
+                        }],
+                        "responses": {
+                          "200": {
+                            "description": "OK",
+                            "schema": {
+                              "$ref": "#/definitions/Pet"
+                            }
+                          }
+                        }
+                      }
+                    }
+                  },
+                  "swagger": "2.0"
+                }),
+            );
+        },
+    );
+}
+
+#[test]
+fn test_extensions_app() {
+    #[derive(Apiv2Security, Deserialize)]
+    #[openapi(
+        apiKey,
+        alias = "ApiKey",
+        in = "header",
+        name = "X-API-Key",
+        extensions(x_scope = "pets")
+    )]
+    struct ApiKey;
+
+    impl FromRequest for ApiKey {
+        type Future = Ready<Result<Self, Self::Error>>;
+        type Error = Error;
+        type Config = ();
+
+        fn from_request(_: &HttpRequest, _payload: &mut actix_web::dev::Payload) -> Self::Future {
+            ready(Ok(Self {}))
+        }
+    }
+
+    #[api_v2_operation(extensions(x_internal = true, x_rate_limit = 10, x_owner = "pets"))]
+    async fn ping(_: ApiKey) -> &'static str {
+        ""
+    }
+
+    run_and_check_app(
+        || {
+            App::new()
+                .wrap_api()
+                .with_json_spec_at("/api/spec")
+                .service(web::resource("/ping").route(web::get().to(ping)))
+                .build()
+        },
+        |addr| {
+            let resp = CLIENT
+                .get(&format!("http://{}/api/spec", addr))
+                .send()
+                .expect("request failed?");
+
+            check_json(
+                resp,
+                json!({
+                  "info":{"title":"","version":""},
+                  "definitions": {},
+                  "paths": {
+                    "/ping": {
+                      "get": {
+                        "responses": {},
+                        "security": [{"ApiKey": []}],
+                        "x-internal": true,
+                        "x-owner": "pets",
+                        "x-rate-limit": 10
+                      }
+                    }
+                  },
+                  "securityDefinitions": {
+                    "ApiKey": {
+                      "in": "header",
+                      "name": "X-API-Key",
+                      "type": "apiKey",
+                      "x-scope": "pets"
+                    }
+                  },
+                  "swagger": "2.0"
+                }),
+            );
+        },
+    );
+}
+
+#[test]
+fn test_examples_app() {
+    #[derive(Deserialize, Serialize, Apiv2Schema)]
+    #[openapi(example = r#"{"name": "Doggo", "age": 3}"#)]
+    struct Pet {
+        #[openapi(example = "Doggo")]
+        name: String,
+        #[openapi(example = 3)]
+        age: u8,
+        #[openapi(example = r#"["good", "boy"]"#)]
+        tags: Vec<String>,
+        #[openapi(example = "12345")]
+        microchip: String,
+        #[openapi(example = "true")]
+        nickname: Option<String>,
+    }
+
+    #[api_v2_operation]
+    async fn add_pet(body: web::Json<Pet>) -> Result<web::Json<Pet>, ()> {
+        Ok(body)
+    }
+
+    run_and_check_app(
+        || {
+            App::new()
+                .wrap_api()
+                .with_json_spec_at("/api/spec")
+                .service(web::resource("/pets").route(web::post().to(add_pet)))
+                .build()
+        },
+        |addr| {
+            let resp = CLIENT
+                .get(&format!("http://{}/api/spec", addr))
+                .send()
+                .expect("request failed?");
+
+            check_json(
+                resp,
+                json!({
+                  "info":{"title":"","version":""},
+                  "definitions": {
+                    "Pet": {
+                      "example": {"name": "Doggo", "age": 3},
+                      "properties": {
+                        "age": {
+                          "example": 3,
+                          "format": "int32",
+                          "type": "integer"
+                        },
+                        "microchip": {
+                          "example": "12345",
+                          "type": "string"
+                        },
+                        "name": {
+                          "example": "Doggo",
+                          "type": "string"
+                        },
+                        "nickname": {
+                          "example": "true",
+                          "type": "string"
+                        },
+                        "tags": {
+                          "example": ["good", "boy"],
+                          "items": {
+                            "type": "string"
+                          },
+                          "type": "array"
+                        }
+                      },
+                      "required": ["age", "microchip", "name", "tags"],
+                      "type": "object"
+                    }
+                  },
+                  "paths": {
+                    "/pets": {
+                      "post": {
+                        "parameters": [{
+                          "in": "body",
+                          "name": "body",
+                          "required": true,
+                          "schema": {
+                            "$ref": "#/definitions/Pet"
+                          }
+                        }],
+                        "responses": {
+                          "200": {
+                            "description": "OK",
+                            "schema": {
+                              "$ref": "#/definitions/Pet"
+                            }
+                          }
+                        }
+                      }
+                    }
+                  },
+                  "swagger": "2.0"
+                }),
+            );
+        },
+    );
+}
+
 fn run_and_check_app<F, G, T, B, U>(factory: F, check: G) -> U
 where
     F: Fn() -> App<T, B> + Clone + Send + Sync + 'static,
@@ -0,0 +1,343 @@
+use once_cell::sync::Lazy;
+use paperclip::bundle::{self, FileLoader, RefLoader};
+use paperclip::v2::models::{DataType, DefaultSchema, HttpMethod, ResolvableApi, SpecFormat};
+use paperclip::v2::Schema;
+use paperclip::{v2, v3, PaperClipError};
+use serde_json::{json, Value};
+use url::Url;
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+static ROOT: Lazy<PathBuf> = Lazy::new(|| PathBuf::from(env!("CARGO_MANIFEST_DIR")));
+
+/// Loader for documents kept in memory.
+struct MemoryLoader(HashMap<&'static str, Value>);
+
+impl RefLoader for MemoryLoader {
+    fn load(&self, url: &Url) -> Result<(Value, SpecFormat), PaperClipError> {
+        self.0
+            .get(url.as_str())
+            .map(|v| (v.clone(), SpecFormat::Json))
+            .ok_or_else(|| PaperClipError::UnsupportedLocation(url.to_string()))
+    }
+}
+
+fn load_multi_file() -> Value {
+    let url = bundle::location_url(ROOT.join("tests/multi-file/api.yaml")).unwrap();
+    let (spec, format) = bundle::load(&url, &FileLoader).expect("loading spec");
+    assert_eq!(format, SpecFormat::Yaml);
+    spec
+}
+
+#[test]
+fn test_bundled_refs() {
+    let spec = load_multi_file();
+    let get = &spec["paths"]["/users"]["get"];
+    assert_eq!(
+        get["parameters"],
+        json!([{"$ref": "#/parameters/PageSize"}])
+    );
+    assert_eq!(
+        get["responses"]["200"]["schema"]["items"],
+        json!({"$ref": "#/definitions/User"})
+    );
+    assert_eq!(
+        get["responses"]["404"],
+        json!({"$ref": "#/responses/NotFound"})
+    );
+
+    // Aliases are replaced by the actual definition.
+    assert_eq!(
+        spec["paths"]["/users/{id}"]["get"]["responses"]["200"]["schema"],
+        json!({"$ref": "#/definitions/User"})
+    );
+
+    // Names already in the spec aren't reused.
+    assert_eq!(spec["definitions"]["Error"], json!({"type": "string"}));
+    assert_eq!(
+        spec["responses"]["NotFound"]["schema"],
+        json!({"$ref": "#/definitions/Error2"})
+    );
+
+    // References from other documents (including cyclic ones and ones to the root).
+    let user = &spec["definitions"]["User"]["properties"];
+    assert_eq!(user["address"], json!({"$ref": "#/definitions/Address"}));
+    assert_eq!(
+        user["friends"]["items"],
+        json!({"$ref": "#/definitions/User"})
+    );
+    assert_eq!(user["pets"]["items"], json!({"$ref": "#/definitions/Pet"}));
+    assert_eq!(
+        spec["definitions"]["Pet"]["properties"]["owner"],
+        json!({"$ref": "#/definitions/User"})
+    );
+
+    let mut names = spec["definitions"]
+        .as_object()
+        .unwrap()
+        .keys()
+        .collect::<Vec<_>>();
+    names.sort();
+    assert_eq!(names, ["Address", "Error", "Error2", "Pet", "User"]);
+    assert_eq!(spec["parameters"]["PageSize"]["name"], "page_size");
+}
+
+#[test]
+fn test_bundled_spec_resolution() {
+    // References to other files are loaded when parsing the spec.
+    let api: ResolvableApi<DefaultSchema> =
+        v2::from_path(ROOT.join("tests/multi-file/api.yaml")).expect("parsing spec");
+    assert_eq!(api.spec_format, SpecFormat::Yaml);
+    let api = api.resolve().expect("resolution");
+
+    let user = api.definitions["User"].read();
+    assert!(user.is_cyclic());
+    let address = user.properties["address"].read();
+    assert_eq!(
+        address.properties["city"].read().data_type,
+        Some(DataType::String)
+    );
+
+    let op = &api.paths["/users"].methods[&HttpMethod::Get];
+    let param = op.parameters[0].read();
+    assert_eq!(param.name, "page_size");
+    assert_eq!(param.data_type, Some(DataType::Integer));
+}
+
+#[test]
+fn test_url_refs_with_custom_loader() {
+    let mut docs = HashMap::new();
+    docs.insert(
+        "https://specs.example.com/api.json",
+        json!({
+            "openapi": "3.0.3",
+            "info": {"title": "Pets", "version": "1.0"},
+            "paths": {
+                "/pets": {
+                    "$ref": "paths/pets.json"
+                }
+            }
+        }),
+    );
+    docs.insert(
+        "https://specs.example.com/paths/pets.json",
+        json!({
+            "post": {
+                "requestBody": {"$ref": "../common.json#/Pet"},
+                "responses": {
+                    "200": {"$ref": "https://specs.example.com/common.json#/Ok"}
+                }
+            }
+        }),
+    );
+    docs.insert(
+        "https://specs.example.com/common.json",
+        json!({
+            "Pet": {
+                "content": {
+                    "application/json": {
+                        "schema": {"$ref": "#/Schemas/Pet"}
+                    }
+                }
+            },
+            "Ok": {"description": "OK"},
+            "Schemas": {
+                "Pet": {"type": "object", "properties": {"name": {"type": "string"}}}
+            }
+        }),
+    );
+
+    let loader = MemoryLoader(docs);
+    let url = Url::parse("https://specs.example.com/api.json").unwrap();
+    let (spec, format) = bundle::load(&url, &loader).expect("loading spec");
+    assert_eq!(format, SpecFormat::Json);
+
+    // Path items are inlined.
+    let post = &spec["paths"]["/pets"]["post"];
+    assert_eq!(
+        post["requestBody"],
+        json!({"$ref": "#/components/requestBodies/Pet"})
+    );
+    assert_eq!(
+        post["responses"]["200"],
+        json!({"$ref": "#/components/responses/Ok"})
+    );
+
+    let components = &spec["components"];
+    assert_eq!(
+        components["requestBodies"]["Pet"]["content"]["application/json"]["schema"],
+        json!({"$ref": "#/components/schemas/Pet"})
+    );
+    assert_eq!(components["responses"]["Ok"], json!({"description": "OK"}));
+    assert_eq!(components["schemas"]["Pet"]["type"], "object");
+
+    let api: v3::ResolvableApi<v3::DefaultSchema> =
+        v3::from_path_with("https://specs.example.com/api.json", &loader).expect("parsing spec");
+    let api = api.resolve().expect("resolution");
+    let pet = api.components.schemas["Pet"].read();
+    assert_eq!(
+        pet.properties["name"].read().data_type,
+        Some(DataType::String)
+    );
+}
+
+#[test]
+fn test_refs_in_all_of() {
+    let mut docs = HashMap::new();
+    docs.insert(
+        "https://specs.example.com/api.json",
+        json!({
+            "swagger": "2.0",
+            "info": {"title": "Pets", "version": "1.0"},
+            "paths": {},
+            "definitions": {
+                "Cat": {
+                    "allOf": [
+                        {"$ref": "models.json#/Pet"},
+                        {"properties": {"owner": {"$ref": "models.json#/Owner"}}}
+                    ]
+                }
+            }
+        }),
+    );
+    docs.insert(
+        "https://specs.example.com/models.json",
+        json!({
+            "Pet": {"properties": {"name": {"type": "string"}}},
+            "Owner": {"allOf": [{"$ref": "#/Pet"}]}
+        }),
+    );
+
+    let loader = MemoryLoader(docs);
+    let url = Url::parse("https://specs.example.com/api.json").unwrap();
+    let (spec, _) = bundle::load(&url, &loader).expect("loading spec");
+    let defs = &spec["definitions"];
+    assert_eq!(
+        defs["Cat"]["allOf"],
+        json!([
+            {"$ref": "#/definitions/Pet"},
+            {"properties": {"owner": {"$ref": "#/definitions/Owner"}}}
+        ])
+    );
+    assert_eq!(
+        defs["Owner"]["allOf"],
+        json!([{"$ref": "#/definitions/Pet"}])
+    );
+
+    let api: ResolvableApi<DefaultSchema> = serde_json::from_value(spec).expect("parsing spec");
+    let api = api.resolve().expect("resolution");
+    let cat = api.definitions["Cat"].read();
+    assert_eq!(
+        cat.all_of[0].read().properties["name"].read().data_type,
+        Some(DataType::String)
+    );
+}
+
+#[test]
+fn test_cyclic_and_missing_refs() {
+    let mut docs = HashMap::new();
+    docs.insert(
+        "https://specs.example.com/api.json",
+        json!({
+            "swagger": "2.0",
+            "info": {"title": "Pets", "version": "1.0"},
+            "paths": {},
+            "definitions": {
+                "Pet": {"$ref": "a.json#/A"},
+                "Owner": {"$ref": "a.json#/Missing"},
+                "Other": {"$ref": "missing.json#/Other"}
+            }
+        }),
+    );
+    docs.insert(
+        "https://specs.example.com/a.json",
+        json!({"A": {"$ref": "b.json#/B"}}),
+    );
+    docs.insert(
+        "https://specs.example.com/b.json",
+        json!({"B": {"$ref": "a.json#/A"}}),
+    );
+
+    let loader = MemoryLoader(docs);
+    let load = |defs: &[&str]| {
+        let url = Url::parse("https://specs.example.com/api.json").unwrap();
+        let (mut spec, _) = loader.load(&url).unwrap();
+        let defs_map = spec["definitions"].as_object_mut().unwrap();
+        defs_map.retain(|k, _| defs.contains(&k.as_str()));
+        bundle::bundle(spec, &url, &loader).unwrap_err().to_string()
+    };
+
+    assert_eq!(
+        load(&["Pet"]),
+        "Cyclic reference \"https://specs.example.com/a.json#/A\" (at /definitions/Pet)"
+    );
+    assert_eq!(
+        load(&["Owner"]),
+        "Reference missing in spec: https://specs.example.com/a.json#/Missing \
+         (at /definitions/Owner)"
+    );
+    assert_eq!(
+        load(&["Other"]),
+        "Cannot load https://specs.example.com/missing.json: \
+         Unsupported location \"https://specs.example.com/missing.json\" \
+         (at /definitions/Other)"
+    );
+}
+
+#[test]
+fn test_bundled_spec_is_self_contained() {
+    fn refs<'a>(value: &'a Value, found: &mut Vec<&'a str>) {
+        match value {
+            Value::Object(m) => {
+                if let Some(Value::String(r)) = m.get("$ref") {
+                    found.push(r);
+                }
+
+                m.values().for_each(|v| refs(v, found));
+            }
+            Value::Array(a) => a.iter().for_each(|v| refs(v, found)),
+            _ => (),
+        }
+    }
+
+    let spec = load_multi_file();
+    let mut found = vec![];
+    refs(&spec, &mut found);
+    assert_eq!(found.len(), 9);
+    assert!(found.iter().all(|r| r.starts_with("#/")));
+
+    // Bundled spec can be loaded without the other files.
+    let dir = std::env::temp_dir().join(format!("paperclip-bundle-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("bundled.yaml");
+    std::fs::write(&path, serde_yaml::to_string(&spec).unwrap()).unwrap();
+
+    let url = bundle::location_url(&path).unwrap();
+    let (loaded, _) = bundle::load(&url, &FileLoader).expect("loading bundled spec");
+    assert_eq!(loaded, spec);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_external_refs_without_bundling() {
+    let spec = "
+swagger: '2.0'
+info:
+  title: Pets
+  version: '1.0'
+paths: {}
+definitions:
+  Pet:
+    properties:
+      owner:
+        $ref: 'models/user.yaml#/User'
+";
+    let api: ResolvableApi<DefaultSchema> = v2::from_str(spec).expect("parsing spec");
+    assert_eq!(
+        api.resolve().unwrap_err().to_string(),
+        "$ref \"models/user.yaml#/User\" refers to another document (\"models/user.yaml\"). \
+         Load the spec with `from_path` (or `bundle::load`) for resolving such references. \
+         (at /definitions/Pet/properties/owner)"
+    );
+}
@@ -0,0 +1,195 @@
+use paperclip::v2::{
+    self,
+    codegen::{DefaultEmitter, Emitter, EmitterState},
+    models::{DefaultSchema, ResolvableApi},
+};
+
+use std::fs;
+
+const SPEC: &str = "swagger: '2.0'
+info: {title: Pets, version: '1.0'}
+securityDefinitions:
+  basic: {type: basic}
+  key: {type: apiKey, in: query, name: api_key}
+  oauth:
+    type: oauth2
+    flow: password
+    tokenUrl: https://example.com/token
+    scopes: {read: Read pets, write: Modify pets}
+security:
+  - key: []
+paths:
+  /pets:
+    get:
+      responses:
+        '200':
+          description: OK
+          schema:
+            $ref: '#/definitions/Pet'
+    post:
+      security:
+        - oauth: [read, write]
+          key: []
+        - basic: []
+      parameters:
+        - {in: body, name: body, schema: {$ref: '#/definitions/Pet'}}
+      responses:
+        '200':
+          description: OK
+          schema:
+            $ref: '#/definitions/Pet'
+    delete:
+      security: []
+      responses:
+        '200':
+          description: OK
+          schema:
+            $ref: '#/definitions/Pet'
+definitions:
+  Pet:
+    type: object
+    properties:
+      name:
+        type: string
+";
+
+#[test]
+fn test_security_in_emitter() {
+    let api: ResolvableApi<DefaultSchema> = v2::from_str(SPEC).expect("deserializing spec");
+
+    let mut state = EmitterState::default();
+    state.working_dir = std::env::temp_dir().join("paperclip-test-client-auth");
+    let _ = fs::remove_dir_all(&state.working_dir);
+    let dir = state.working_dir.clone();
+
+    let emitter = DefaultEmitter::from(state);
+    emitter
+        .generate(&api.resolve().expect("resolution"))
+        .expect("codegen");
+
+    let contents = fs::read_to_string(dir.join("pet.rs")).expect("reading generated code");
+    let securities = contents
+        .split("const SECURITY")
+        .skip(1)
+        .map(|s| s.split("\n\n").next().unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        securities,
+        vec![
+            // Global requirement for `GET`.
+            ": &'static [crate::client::SecurityRequirement] = &[
+        &[(\"key\", crate::client::SecurityScheme::ApiKeyQuery(\"api_key\"), &[])],
+    ];",
+            // Operation-level requirements for `POST`.
+            ": &'static [crate::client::SecurityRequirement] = &[
+        &[(\"key\", crate::client::SecurityScheme::ApiKeyQuery(\"api_key\"), &[]), \
+         (\"oauth\", crate::client::SecurityScheme::OAuth2, &[\"read\", \"write\"])],
+        &[(\"basic\", crate::client::SecurityScheme::Basic, &[])],
+    ];",
+        ]
+    );
+
+    // `DELETE` overrides the global requirement with an empty one.
+    let delete = contents
+        .split("http::Method::DELETE;")
+        .nth(1)
+        .expect("delete operation");
+    assert!(delete.starts_with("\n\n    fn rel_path"));
+
+    let lib = fs::read_to_string(dir.join("mod.rs")).expect("reading generated code");
+    assert!(lib.contains("pub struct AuthClient<C>"));
+
+    // Token provider for the `password` flow of `oauth`.
+    let provider = lib
+        .split("pub fn for_oauth<U, P>(username: U, password: P) -> Self")
+        .nth(1)
+        .expect("token provider for scheme");
+    assert!(provider.contains(
+        "OAuth2TokenProvider::password(\"https://example.com/token\", username, password)"
+    ));
+    assert!(!lib.contains("pub fn for_key"));
+}
+
+#[test]
+fn test_invalid_security_fails_codegen() {
+    let broken = SPEC.replace(
+        "securityDefinitions:\n",
+        "securityDefinitions:\n  broken: {type: apiKey, name: X-Key}\n",
+    );
+    for (spec, requirement, message) in &[
+        (
+            &broken,
+            "- broken: []",
+            "Security scheme \"broken\" requires \"in\" (at /paths/~1pets/post/security)",
+        ),
+        (
+            &SPEC.to_owned(),
+            "- unknown: []",
+            "Security scheme \"unknown\" is not defined in securityDefinitions \
+             (at /paths/~1pets/post/security)",
+        ),
+    ] {
+        let spec = spec.replace("- basic: []", requirement);
+        let api: ResolvableApi<DefaultSchema> = v2::from_str(&spec).expect("deserializing spec");
+
+        let mut state = EmitterState::default();
+        state.working_dir = std::env::temp_dir().join("paperclip-test-client-auth-invalid");
+        let _ = fs::remove_dir_all(&state.working_dir);
+
+        let emitter = DefaultEmitter::from(state);
+        let err = emitter
+            .generate(&api.resolve().expect("resolution"))
+            .expect_err("codegen should fail");
+        assert_eq!(err.to_string(), *message);
+    }
+}
+
+#[test]
+fn test_token_provider_names_and_urls() {
+    let spec = "swagger: '2.0'
+info: {title: Pets, version: '1.0'}
+securityDefinitions:
+  petstoreApp:
+    type: oauth2
+    flow: application
+    tokenUrl: https://example.com/app/token
+  petstore_app:
+    type: oauth2
+    flow: password
+    tokenUrl: 'https://example.com/password/token?q=\"); panic!(\"'
+paths:
+  /pets:
+    get:
+      responses:
+        '200':
+          description: OK
+          schema:
+            $ref: '#/definitions/Pet'
+definitions:
+  Pet:
+    type: object
+    properties:
+      name:
+        type: string
+";
+    let api: ResolvableApi<DefaultSchema> = v2::from_str(spec).expect("deserializing spec");
+
+    let mut state = EmitterState::default();
+    state.working_dir = std::env::temp_dir().join("paperclip-test-client-auth-names");
+    let _ = fs::remove_dir_all(&state.working_dir);
+    let dir = state.working_dir.clone();
+
+    let emitter = DefaultEmitter::from(state);
+    emitter
+        .generate(&api.resolve().expect("resolution"))
+        .expect("codegen");
+
+    let lib = fs::read_to_string(dir.join("mod.rs")).expect("reading generated code");
+    assert!(lib.contains("pub fn for_petstore_app<I, S>(client_id: I, client_secret: S) -> Self"));
+    assert!(lib.contains("pub fn for_petstore_app_2<U, P>(username: U, password: P) -> Self"));
+
+    // Token URLs are escaped in the generated code.
+    assert!(lib.contains(
+        r#"OAuth2TokenProvider::password("https://example.com/password/token?q=\"); panic!(\"", username, password)"#
+    ));
+}
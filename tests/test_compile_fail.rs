@@ -0,0 +1,5 @@
+#[test]
+fn test_invalid_security_attrs() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/compile-fail/security_*.rs");
+}
@@ -0,0 +1,280 @@
+use paperclip::diagnostics::{Diagnostic, Position, SourceMap};
+use paperclip::v2::{
+    self,
+    codegen::{DefaultEmitter, Emitter, EmitterState},
+    models::{DefaultSchema, ResolvableApi},
+};
+use paperclip::{v3, PaperClipError};
+
+fn v2_pointer(spec: &str) -> Option<String> {
+    let api: ResolvableApi<DefaultSchema> = v2::from_str(spec).expect("parsing spec");
+    let err = api.resolve().unwrap_err();
+    err.pointer().map(String::from)
+}
+
+fn v3_pointer(spec: &str) -> Option<String> {
+    let api: v3::ResolvableApi<v3::DefaultSchema> = v3::from_str(spec).expect("parsing spec");
+    let err = api.resolve().unwrap_err();
+    err.pointer().map(String::from)
+}
+
+fn codegen_error(spec: &str) -> anyhow::Error {
+    let emitter = DefaultEmitter::from(EmitterState::default());
+    if spec.starts_with("openapi") {
+        let api: v3::ResolvableApi<v3::DefaultSchema> = v3::from_str(spec).expect("parsing spec");
+        emitter
+            .generate_v3(&api.resolve().expect("resolution"))
+            .unwrap_err()
+    } else {
+        let api: ResolvableApi<DefaultSchema> = v2::from_str(spec).expect("parsing spec");
+        emitter
+            .generate(&api.resolve().expect("resolution"))
+            .unwrap_err()
+    }
+}
+
+#[test]
+fn test_source_map_positions() {
+    let yaml = "\u{feff}swagger: '2.0'
+paths:
+  /pets/{id}:
+    get:
+      parameters:
+        - name: id
+          in: path
+        - $ref: '#/parameters/Limit'
+      tags: [pets, 'a~b']
+";
+    let map = SourceMap::new(yaml);
+    let pos = |line, column| Some(Position { line, column });
+    assert_eq!(map.position("/swagger"), pos(1, 1));
+    assert_eq!(map.position("/paths/~1pets~1{id}"), pos(3, 3));
+    assert_eq!(
+        map.position("/paths/~1pets~1{id}/get/parameters/0"),
+        pos(6, 11)
+    );
+    assert_eq!(
+        map.position("/paths/~1pets~1{id}/get/parameters/1/$ref"),
+        pos(8, 11)
+    );
+    assert_eq!(map.position("/paths/~1pets~1{id}/get/tags/1"), pos(9, 20));
+    // Closest ancestor is used for missing nodes.
+    assert_eq!(map.position("/paths/~1pets~1{id}/get/responses"), pos(4, 5));
+    assert_eq!(map.position("/definitions/Pet"), None);
+    assert_eq!(map.position(""), None);
+    assert_eq!(map.line(3), Some("  /pets/{id}:"));
+
+    let json = r#"{
+  "swagger": "2.0",
+  "definitions": {
+    "Pet": {"properties": {"name": {"type": "string"}}}
+  }
+}"#;
+    let map = SourceMap::new(json);
+    assert_eq!(map.position("/definitions"), pos(3, 3));
+    assert_eq!(
+        map.position("/definitions/Pet/properties/name/type"),
+        pos(4, 37)
+    );
+}
+
+#[test]
+fn test_v2_resolver_pointers() {
+    let spec = "swagger: '2.0'
+info: {title: Pets, version: '1.0'}
+paths: {}
+definitions:
+  Pet:
+    properties:
+      owner:
+        $ref: '#/definitions/Owner'
+  Owner:
+    properties:
+      pets:
+        type: array
+        items:
+          $ref: '#/definitions/Missing'
+";
+    // Pointer is to the node in the referenced definition.
+    assert_eq!(
+        v2_pointer(spec).as_deref(),
+        Some("/definitions/Owner/properties/pets/items")
+    );
+
+    let spec = "swagger: '2.0'
+info: {title: Pets, version: '1.0'}
+paths:
+  /pets/{id}:
+    parameters:
+      - $ref: '#/parameters/Id'
+    post:
+      parameters:
+        - name: body
+          in: body
+          schema:
+            properties:
+              tag:
+                $ref: '#/definitions/Tag'
+      responses:
+        '200':
+          description: OK
+parameters:
+  Id: {name: id, in: path, type: string, required: true}
+";
+    assert_eq!(
+        v2_pointer(spec).as_deref(),
+        Some("/paths/~1pets~1{id}/post/parameters/0/schema/properties/tag")
+    );
+
+    let spec = "swagger: '2.0'
+info: {title: Pets, version: '1.0'}
+paths:
+  /pets:
+    get:
+      responses:
+        '404':
+          $ref: '#/responses/NotFound'
+";
+    let api: ResolvableApi<DefaultSchema> = v2::from_str(spec).expect("parsing spec");
+    let err = api.resolve().unwrap_err();
+    assert_eq!(err.pointer(), Some("/paths/~1pets/get/responses/404"));
+    assert_eq!(
+        err.to_string(),
+        "Reference missing in spec: NotFound (at /paths/~1pets/get/responses/404)"
+    );
+    assert_eq!(
+        err.inner().to_string(),
+        "Reference missing in spec: NotFound"
+    );
+}
+
+#[test]
+fn test_v3_resolver_pointers() {
+    let spec = "openapi: 3.0.3
+info: {title: Pets, version: '1.0'}
+paths:
+  /pets:
+    post:
+      requestBody:
+        $ref: '#/components/requestBodies/Pet'
+      responses:
+        '200':
+          description: OK
+          headers:
+            X-Rate-Limit:
+              $ref: '#/components/headers/Missing'
+components:
+  requestBodies:
+    Pet:
+      content:
+        application/json:
+          schema:
+            type: object
+";
+    assert_eq!(
+        v3_pointer(spec).as_deref(),
+        Some("/paths/~1pets/post/responses/200/headers/X-Rate-Limit")
+    );
+
+    let spec = "openapi: 3.0.3
+info: {title: Pets, version: '1.0'}
+paths:
+  /pets:
+    get:
+      responses:
+        '200':
+          description: OK
+          content:
+            application/json:
+              schema:
+                type: array
+                items:
+                  $ref: '#/definitions/Pet'
+";
+    assert_eq!(
+        v3_pointer(spec).as_deref(),
+        Some("/paths/~1pets/get/responses/200/content/application~1json/schema/items")
+    );
+}
+
+#[test]
+fn test_codegen_error_pointers() {
+    let err = codegen_error(
+        "swagger: '2.0'
+info: {title: Pets, version: '1.0'}
+paths:
+  /pets:
+    get:
+      parameters:
+        - {name: limit, in: query, type: integer}
+        - {name: Content-Type, in: header, type: string}
+      responses:
+        '200': {description: OK}
+",
+    );
+    let diagnostic = Diagnostic::from_error(err.as_ref());
+    assert_eq!(
+        diagnostic.pointer.as_deref(),
+        Some("/paths/~1pets/get/parameters/1")
+    );
+    assert_eq!(
+        diagnostic.message,
+        "Path \"/pets\" has header parameter \"Content-Type\" which is not allowed"
+    );
+
+    // Pointers are mapped to the v3 spec.
+    let err = codegen_error(
+        "openapi: 3.0.3
+info: {title: Pets, version: '1.0'}
+paths: {}
+components:
+  schemas:
+    Pets:
+      type: array
+",
+    );
+    match err.downcast_ref::<PaperClipError>() {
+        Some(PaperClipError::Located(p, e)) => {
+            assert_eq!(p, "/components/schemas/Pets");
+            assert!(matches!(**e, PaperClipError::MissingArrayItem(_)));
+        }
+        _ => panic!("unexpected error: {:?}", err),
+    }
+}
+
+#[test]
+fn test_rendering() {
+    let spec = "swagger: '2.0'
+info: {title: Pets, version: '1.0'}
+host: 'pets:store'
+paths: {}
+";
+    let err = codegen_error(spec);
+    let diagnostic = Diagnostic::from_error(err.as_ref());
+    assert_eq!(
+        diagnostic.render("api.yaml", Some(&SourceMap::new(spec))),
+        "error: Cannot parse host \"pets:store\": invalid port number
+ --> api.yaml:3:1
+  |
+3 | host: 'pets:store'
+  | ^
+  = at /host
+"
+    );
+
+    // Without the source.
+    assert_eq!(
+        diagnostic.render("https://example.com/api.yaml", None),
+        "error: Cannot parse host \"pets:store\": invalid port number
+  --> https://example.com/api.yaml#/host
+"
+    );
+
+    // Errors without locations.
+    let diagnostic = Diagnostic::from_error(&PaperClipError::UnsupportedOpenAPIVersion);
+    assert_eq!(diagnostic.pointer, None);
+    assert_eq!(
+        diagnostic.render("api.yaml", Some(&SourceMap::new(spec))),
+        "error: This version of OpenAPI is unsupported.\n"
+    );
+}
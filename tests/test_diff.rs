@@ -0,0 +1,346 @@
+use paperclip::v2::{
+    self,
+    diff::{self, Bump, Compatibility, Diff},
+    models::{DefaultSchema, ResolvableApi},
+};
+
+const OLD: &str = "swagger: '2.0'
+info: {title: Pets, version: 1.4.2}
+paths:
+  /pets:
+    get:
+      parameters:
+        - {name: limit, in: query, type: integer}
+        - {name: status, in: query, type: string, enum: [available, sold]}
+        - {name: tags, in: query, type: array, items: {type: string}}
+      responses:
+        '200':
+          description: OK
+          schema:
+            type: array
+            items:
+              $ref: '#/definitions/Pet'
+    post:
+      parameters:
+        - name: body
+          in: body
+          required: true
+          schema:
+            $ref: '#/definitions/Pet'
+      responses:
+        '201': {description: Created}
+        '400': {description: Bad request}
+  /pets/{id}:
+    parameters:
+      - {name: id, in: path, type: string, required: true}
+    get:
+      responses:
+        '200':
+          description: OK
+          schema:
+            $ref: '#/definitions/Pet'
+    delete:
+      responses:
+        '204': {description: Deleted}
+definitions:
+  Pet:
+    required: [name]
+    properties:
+      name: {type: string}
+      kind: {type: string, enum: [cat, dog]}
+      age: {type: integer, format: int32}
+      nickname: {type: string}
+      owner:
+        $ref: '#/definitions/Owner'
+  Owner:
+    properties:
+      pets:
+        type: array
+        items:
+          $ref: '#/definitions/Pet'
+";
+
+fn diff(old: &str, new: &str) -> Diff {
+    let old: ResolvableApi<DefaultSchema> = v2::from_str(old).expect("parsing old spec");
+    let new: ResolvableApi<DefaultSchema> = v2::from_str(new).expect("parsing new spec");
+    diff::diff(
+        &old.resolve().expect("resolving old spec"),
+        &new.resolve().expect("resolving new spec"),
+    )
+}
+
+fn changes(diff: &Diff) -> Vec<(Compatibility, &str, &str)> {
+    diff.changes
+        .iter()
+        .map(|c| (c.compatibility, c.pointer.as_str(), c.message.as_str()))
+        .collect()
+}
+
+#[test]
+fn test_same_spec() {
+    let diff = diff(OLD, OLD);
+    assert!(diff.changes.is_empty(), "{:?}", diff);
+    assert_eq!(diff.bump(), None);
+    assert_eq!(
+        diff.next_version(&"1.4.2".parse().unwrap()).to_string(),
+        "1.4.2"
+    );
+    assert_eq!(
+        diff.next_version(&"0.3.1".parse().unwrap()).to_string(),
+        "0.3.1"
+    );
+}
+
+#[test]
+fn test_breaking_changes() {
+    let new = "swagger: '2.0'
+info: {title: Pets, version: 1.5.0}
+paths:
+  /pets:
+    get:
+      parameters:
+        - {name: limit, in: query, type: integer, required: true}
+        - {name: status, in: query, type: string, enum: [available]}
+        - {name: tags, in: query, type: array, items: {type: integer}}
+        - {name: sort, in: query, type: string, required: true}
+      responses:
+        '200':
+          description: OK
+          schema:
+            type: array
+            items:
+              $ref: '#/definitions/Pet'
+    post:
+      parameters:
+        - name: body
+          in: body
+          required: true
+          schema:
+            $ref: '#/definitions/Pet'
+      responses:
+        '201': {description: Created}
+  /pets/{petId}:
+    parameters:
+      - {name: petId, in: path, type: string, required: true}
+    get:
+      responses:
+        '200':
+          description: OK
+          schema:
+            $ref: '#/definitions/Pet'
+definitions:
+  Pet:
+    required: [name, age]
+    properties:
+      name: {type: string}
+      kind: {type: string, enum: [cat, dog, bird]}
+      age: {type: integer, format: int64}
+      owner:
+        $ref: '#/definitions/Owner'
+  Owner:
+    properties:
+      pets:
+        type: array
+        items:
+          $ref: '#/definitions/Pet'
+";
+
+    let diff = diff(OLD, new);
+    use Compatibility::*;
+    assert_eq!(
+        changes(&diff),
+        vec![
+            (
+                Breaking,
+                "/paths/~1pets/get/parameters/0",
+                "Parameter \"limit\" is now required"
+            ),
+            (
+                Breaking,
+                "/paths/~1pets/get/parameters/1",
+                "Enum was narrowed (removed \"sold\")"
+            ),
+            (
+                Breaking,
+                "/paths/~1pets/get/parameters/2/items",
+                "Type changed from string to integer"
+            ),
+            (
+                Breaking,
+                "/paths/~1pets/get/parameters/3",
+                "Required parameter \"sort\" was added"
+            ),
+            (
+                NonBreaking,
+                "/paths/~1pets/get/responses/200/schema/items/properties/age",
+                "Property \"age\" is now required"
+            ),
+            (
+                Breaking,
+                "/paths/~1pets/get/responses/200/schema/items/properties/age",
+                "Format changed from int32 to int64"
+            ),
+            (
+                Breaking,
+                "/paths/~1pets/get/responses/200/schema/items/properties/kind",
+                "Enum was widened (added \"bird\")"
+            ),
+            (
+                Breaking,
+                "/paths/~1pets/get/responses/200/schema/items/properties/nickname",
+                "Property \"nickname\" was removed"
+            ),
+            (
+                Breaking,
+                "/paths/~1pets/post/parameters/0/schema/properties/age",
+                "Property \"age\" is now required"
+            ),
+            (
+                Breaking,
+                "/paths/~1pets/post/parameters/0/schema/properties/age",
+                "Format changed from int32 to int64"
+            ),
+            (
+                NonBreaking,
+                "/paths/~1pets/post/parameters/0/schema/properties/kind",
+                "Enum was widened (added \"bird\")"
+            ),
+            (
+                NonBreaking,
+                "/paths/~1pets/post/parameters/0/schema/properties/nickname",
+                "Property \"nickname\" was removed"
+            ),
+            (
+                Breaking,
+                "/paths/~1pets/post/responses/400",
+                "Response \"400\" was removed"
+            ),
+            (
+                NonBreaking,
+                "/paths/~1pets~1{petId}/get/responses/200/schema/properties/age",
+                "Property \"age\" is now required"
+            ),
+            (
+                Breaking,
+                "/paths/~1pets~1{petId}/get/responses/200/schema/properties/age",
+                "Format changed from int32 to int64"
+            ),
+            (
+                Breaking,
+                "/paths/~1pets~1{petId}/get/responses/200/schema/properties/kind",
+                "Enum was widened (added \"bird\")"
+            ),
+            (
+                Breaking,
+                "/paths/~1pets~1{petId}/get/responses/200/schema/properties/nickname",
+                "Property \"nickname\" was removed"
+            ),
+            (
+                Breaking,
+                "/paths/~1pets~1{id}/delete",
+                "Operation \"DELETE /pets/{id}\" was removed"
+            ),
+        ]
+    );
+
+    assert!(diff.is_breaking());
+    assert_eq!(diff.bump(), Some(Bump::Major));
+    assert_eq!(
+        diff.next_version(&"1.4.2".parse().unwrap()).to_string(),
+        "2.0.0"
+    );
+    assert_eq!(
+        diff.next_version(&"0.3.1".parse().unwrap()).to_string(),
+        "0.4.0"
+    );
+}
+
+#[test]
+fn test_non_breaking_changes() {
+    let new = "swagger: '2.0'
+info: {title: Pets, version: 1.5.0}
+paths:
+  /pets:
+    get:
+      parameters:
+        - {name: limit, in: query, type: integer}
+        - {name: status, in: query, type: string, enum: [available, sold, pending]}
+        - {name: tags, in: query, type: array, items: {type: string}}
+        - {name: offset, in: query, type: integer}
+      responses:
+        '200':
+          description: OK
+          schema:
+            type: array
+            items:
+              $ref: '#/definitions/Pet'
+    post:
+      parameters:
+        - name: body
+          in: body
+          required: true
+          schema:
+            $ref: '#/definitions/Pet'
+      responses:
+        '201': {description: Created}
+        '400': {description: Bad request}
+        '409': {description: Conflict}
+  /pets/{id}:
+    parameters:
+      - {name: id, in: path, type: string, required: true}
+    get:
+      responses:
+        '200':
+          description: OK
+          schema:
+            $ref: '#/definitions/Pet'
+    delete:
+      responses:
+        '204': {description: Deleted}
+  /stores:
+    get:
+      responses:
+        '200': {description: OK}
+definitions:
+  Pet:
+    required: [name]
+    properties:
+      name: {type: string}
+      kind: {type: string, enum: [cat, dog]}
+      age: {type: integer, format: int32}
+      nickname: {type: string}
+      color: {type: string}
+      owner:
+        $ref: '#/definitions/Owner'
+  Owner:
+    properties:
+      pets:
+        type: array
+        items:
+          $ref: '#/definitions/Pet'
+";
+
+    let diff = diff(OLD, new);
+    assert!(!diff.is_breaking(), "{:?}", diff);
+    assert_eq!(diff.non_breaking().count(), 7);
+    assert_eq!(
+        diff.changes
+            .iter()
+            .map(|c| c.message.as_str())
+            .collect::<Vec<_>>(),
+        vec![
+            "Enum was widened (added \"pending\")",
+            "Optional parameter \"offset\" was added",
+            "Property \"color\" was added",
+            "Property \"color\" was added",
+            "Response \"409\" was added",
+            "Property \"color\" was added",
+            "Path \"/stores\" was added",
+        ]
+    );
+    assert_eq!(diff.bump(), Some(Bump::Minor));
+    assert_eq!(
+        diff.next_version(&"1.4.2".parse().unwrap()).to_string(),
+        "1.5.0"
+    );
+}
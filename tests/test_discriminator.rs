@@ -0,0 +1,164 @@
+use paperclip::v2::{
+    self,
+    codegen::{DefaultEmitter, Emitter, EmitterState},
+    filter::Filter,
+    models::{DefaultSchema, DefaultSchemaRaw, Discriminator, ResolvableApi},
+    Schema,
+};
+
+use std::fs;
+
+const SPEC: &str = "swagger: '2.0'
+info: {title: Pets, version: '1.0'}
+paths:
+  /pets:
+    get:
+      operationId: listPets
+      responses:
+        '200':
+          description: OK
+          schema:
+            type: array
+            items:
+              $ref: '#/definitions/Pet'
+  /stores:
+    get:
+      operationId: listStores
+      responses:
+        '200':
+          description: OK
+          schema:
+            $ref: '#/definitions/Store'
+definitions:
+  Pet:
+    type: object
+    discriminator: petType
+    required: [name, petType]
+    properties:
+      name: {type: string}
+      petType: {type: string}
+  Cat:
+    allOf:
+      - $ref: '#/definitions/Pet'
+      - properties:
+          huntingSkill: {type: string}
+          mother:
+            $ref: '#/definitions/Pet'
+  big-dog:
+    allOf:
+      - $ref: '#/definitions/Pet'
+      - required: [packSize]
+        properties:
+          packSize: {type: integer, format: int32}
+  Store:
+    type: object
+    properties:
+      name: {type: string}
+";
+
+#[test]
+fn test_discriminator_formats() {
+    let v2: DefaultSchemaRaw =
+        serde_yaml::from_str("discriminator: petType").expect("deserializing schema");
+    let v3: DefaultSchemaRaw = serde_yaml::from_str(
+        "
+discriminator:
+  propertyName: petType
+  mapping:
+    dog: '#/components/schemas/Dog'
+",
+    )
+    .expect("deserializing schema");
+
+    let (v2, v3) = (v2.discriminator.unwrap(), v3.discriminator.unwrap());
+    assert_eq!(v2, Discriminator::Property("petType".into()));
+    assert_eq!(v2.property_name(), "petType");
+    assert_eq!(v3.property_name(), "petType");
+    assert_eq!(v2.value_for("Dog"), "Dog");
+    assert_eq!(v3.value_for("Dog"), "dog");
+    assert_eq!(v3.value_for("Cat"), "Cat");
+}
+
+#[test]
+fn test_discriminator_cycles() {
+    let api: ResolvableApi<DefaultSchema> = v2::from_str(SPEC).expect("deserializing spec");
+    let api = api.resolve().expect("resolution");
+
+    // `Pet` contains `Cat`, which contains `Pet`.
+    assert!(api.definitions["Pet"].read().is_cyclic());
+    assert!(!api.definitions["Cat"].read().is_cyclic());
+    assert!(!api.definitions["big-dog"].read().is_cyclic());
+}
+
+#[test]
+fn test_discriminator_subtypes_kept_by_filter() {
+    let api: ResolvableApi<DefaultSchema> = v2::from_str(SPEC).expect("deserializing spec");
+    let filter = Filter::default().include_operation("listPets");
+
+    let raw = filter.apply(&api);
+    assert_eq!(
+        raw.definitions.keys().collect::<Vec<_>>(),
+        vec!["Cat", "Pet", "big-dog"]
+    );
+
+    // Anonymous response schema is added to definitions during resolution.
+    let resolved = filter.apply(&api.resolve().expect("resolution"));
+    assert_eq!(
+        resolved.definitions.keys().collect::<Vec<_>>(),
+        vec!["Cat", "GetPetsResponse", "Pet", "big-dog"]
+    );
+}
+
+#[test]
+fn test_discriminator_in_emitter() {
+    let api: ResolvableApi<DefaultSchema> = v2::from_str(SPEC).expect("deserializing spec");
+
+    let mut state = EmitterState::default();
+    state.working_dir = std::env::temp_dir().join("paperclip-test-discriminator");
+    let _ = fs::remove_dir_all(&state.working_dir);
+    let dir = state.working_dir.clone();
+
+    let emitter = DefaultEmitter::from(state);
+    emitter
+        .generate(&api.resolve().expect("resolution"))
+        .expect("codegen");
+
+    let pet = fs::read_to_string(dir.join("pet.rs")).expect("reading generated code");
+    assert_eq!(
+        pet.trim_end(),
+        "#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = \"petType\")]
+pub enum Pet {
+    Cat(crate::cat::Cat),
+    #[serde(rename = \"big-dog\")]
+    BigDog(crate::big_dog::BigDog),
+    /// Unknown discriminator value.
+    #[serde(other)]
+    Unknown,
+}
+impl Default for Pet {
+    fn default() -> Self {
+        Pet::Unknown
+    }
+}"
+    );
+
+    // Discriminator properties are handled by the enum.
+    let cat = fs::read_to_string(dir.join("cat.rs")).expect("reading generated code");
+    assert!(cat.starts_with(
+        "#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct Cat {
+    #[serde(rename = \"huntingSkill\")]
+    pub hunting_skill: Option<String>,
+    pub mother: Option<Box<crate::pet::Pet>>,
+    pub name: String,
+}
+"
+    ));
+    assert!(cat.contains("pub fn mother(mut self, value: crate::pet::Pet) -> Self {"));
+
+    // Operations returning tagged enums aren't bound to them.
+    let misc = fs::read_to_string(dir.join("miscellaneous.rs")).expect("reading generated code");
+    assert!(misc.contains("type Output = Vec<crate::pet::Pet>;"));
+    let _ = fs::remove_dir_all(&dir);
+}
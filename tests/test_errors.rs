@@ -33,7 +33,8 @@ paths:
     let err = emitter.generate(&resolved).unwrap_err().to_string();
     assert_eq!(
         err,
-        "Parameter(s) {\"petId\"} aren't defined for templated path \"/pets/{petId}\"",
+        "Parameter(s) {\"petId\"} aren't defined for templated path \"/pets/{petId}\" \
+         (at /paths/~1pets~1{petId})",
     );
 }
 
@@ -79,6 +80,7 @@ paths:
     let err = emitter.generate(&resolved).unwrap_err().to_string();
     assert_eq!(
         err,
-        "Path similar to \"/store/{storeId}/pets/{petId}\" already exists.",
+        "Path similar to \"/store/{storeId}/pets/{petId}\" already exists. \
+         (at /paths/~1store~1{storeId}~1pets~1{petId})",
     );
 }
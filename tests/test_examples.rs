@@ -0,0 +1,142 @@
+use paperclip::v2::{
+    self,
+    codegen::{DefaultEmitter, Emitter, EmitterState},
+    models::{DefaultApiRaw, DefaultSchema, Either, HttpMethod, ResolvableApi, JSON_MIME},
+    Schema,
+};
+use paperclip::v3;
+
+use serde_json::json;
+use std::fs;
+
+const SPEC: &str = "swagger: '2.0'
+info: {title: Pets, version: '1.0'}
+produces: [application/json]
+paths:
+  /pets:
+    get:
+      responses:
+        '200':
+          description: OK
+          schema:
+            $ref: '#/definitions/Pet'
+          examples:
+            application/json:
+              name: Doggo
+              age: 3
+definitions:
+  Pet:
+    description: A pet.
+    type: object
+    example: {name: Doggo, age: 3}
+    properties:
+      name:
+        type: string
+        example: Doggo
+      age:
+        type: integer
+        example: 3
+      weight:
+        description: Weight in kilograms.
+        type: number
+        example: 12.5
+";
+
+#[test]
+fn test_examples_roundtrip() {
+    let api: DefaultApiRaw = serde_yaml::from_str(SPEC).expect("deserializing spec");
+    let pet = &api.definitions["Pet"];
+    assert_eq!(pet.example, Some(json!({"name": "Doggo", "age": 3})));
+    assert_eq!(pet.properties["age"].example, Some(json!(3)));
+    assert_eq!(pet.properties["weight"].example, Some(json!(12.5)));
+
+    let op = &api.paths["/pets"].methods[&HttpMethod::Get];
+    match &op.responses["200"] {
+        Either::Right(r) => assert_eq!(r.examples[&*JSON_MIME], json!({"name": "Doggo", "age": 3})),
+        Either::Left(_) => panic!("expected response"),
+    }
+
+    let value = serde_json::to_value(&api).expect("serializing spec");
+    let expected: serde_json::Value = serde_yaml::from_str(SPEC).expect("deserializing value");
+    assert_eq!(value, expected);
+}
+
+#[test]
+fn test_examples_in_v3() {
+    let spec: serde_json::Value = serde_yaml::from_str(SPEC).expect("deserializing value");
+    let spec = v3::convert::from_v2_value(spec).expect("converting spec");
+    let media = &spec["paths"]["/pets"]["get"]["responses"]["200"]["content"]["application/json"];
+    assert_eq!(media["example"], json!({"name": "Doggo", "age": 3}));
+    assert_eq!(
+        spec["components"]["schemas"]["Pet"]["properties"]["name"]["example"],
+        "Doggo"
+    );
+
+    let api: v3::models::ResolvableApi<DefaultSchema> =
+        serde_json::from_value(spec).expect("v3 spec");
+    let api = v3::codegen::lower(&api.resolve().expect("resolution")).expect("lowering");
+    let resp = api.paths["/pets"].methods[&HttpMethod::Get].responses["200"].read();
+    assert_eq!(
+        resp.examples[&*JSON_MIME],
+        json!({"name": "Doggo", "age": 3})
+    );
+    assert_eq!(
+        api.definitions["Pet"].read().example(),
+        Some(&json!({"name": "Doggo", "age": 3}))
+    );
+}
+
+#[test]
+fn test_examples_in_emitter() {
+    let api: ResolvableApi<DefaultSchema> = v2::from_str(SPEC).expect("deserializing spec");
+
+    let mut state = EmitterState::default();
+    state.working_dir = std::env::temp_dir().join("paperclip-test-examples");
+    let _ = fs::remove_dir_all(&state.working_dir);
+    let dir = state.working_dir.clone();
+
+    let emitter = DefaultEmitter::from(state);
+    emitter
+        .generate(&api.resolve().expect("resolution"))
+        .expect("codegen");
+
+    let contents = fs::read_to_string(dir.join("pet.rs")).expect("reading generated code");
+    assert!(contents.starts_with(
+        "
+/// A pet.
+///
+/// Example:
+///
+/// ```json
+/// {
+///   \"age\": 3,
+///   \"name\": \"Doggo\"
+/// }
+/// ```
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct Pet {
+    /// Example:
+    ///
+    /// ```json
+    /// 3
+    /// ```
+    pub age: Option<i64>,
+    /// Example:
+    ///
+    /// ```json
+    /// \"Doggo\"
+    /// ```
+    pub name: Option<String>,
+    /// Weight in kilograms.
+    ///
+    /// Example:
+    ///
+    /// ```json
+    /// 12.5
+    /// ```
+    pub weight: Option<f64>,
+}
+"
+    ));
+    let _ = fs::remove_dir_all(&dir);
+}
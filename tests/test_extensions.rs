@@ -0,0 +1,115 @@
+use paperclip::v2::models::{
+    DefaultApiRaw, DefaultSchema, Either, HttpMethod, ResolvableApi, SecurityScheme,
+};
+
+use serde_json::json;
+
+const SPEC: &str = "swagger: '2.0'
+info:
+  title: Pets
+  version: '1.0'
+  x-logo: pet.png
+tags:
+  - name: pets
+    x-display-name: Pets
+definitions: {}
+securityDefinitions:
+  ApiKey:
+    type: apiKey
+    name: X-API-Key
+    in: header
+    x-scope: pets
+parameters:
+  Limit:
+    name: limit
+    in: query
+    type: integer
+    x-example: 5
+responses:
+  NotFound:
+    description: Not found
+    x-cache: false
+paths:
+  /pets:
+    x-controller: pets
+    parameters:
+      - $ref: '#/parameters/Limit'
+    get:
+      x-rate-limit: 10
+      tags: [pets]
+      responses:
+        '200':
+          description: OK
+          x-cache: true
+        '404':
+          $ref: '#/responses/NotFound'
+";
+
+#[test]
+fn test_extensions_roundtrip() {
+    let api: DefaultApiRaw = serde_yaml::from_str(SPEC).expect("deserializing spec");
+    assert_eq!(api.info.extensions["x-logo"], "pet.png");
+    assert_eq!(api.tags[0].extensions["x-display-name"], "Pets");
+    assert_eq!(
+        api.security_definitions["ApiKey"].extensions["x-scope"],
+        "pets"
+    );
+    assert_eq!(api.parameters["Limit"].extensions["x-example"], 5);
+    assert_eq!(api.responses["NotFound"].extensions["x-cache"], false);
+
+    let item = &api.paths["/pets"];
+    assert_eq!(item.extensions["x-controller"], "pets");
+    // Extensions aren't mistaken for operations.
+    assert_eq!(item.methods.keys().collect::<Vec<_>>(), [&HttpMethod::Get]);
+    let op = &item.methods[&HttpMethod::Get];
+    assert_eq!(op.extensions["x-rate-limit"], 10);
+    // Other fields aren't captured as extensions.
+    assert_eq!(op.extensions.len(), 1);
+    match &op.responses["200"] {
+        Either::Right(r) => assert_eq!(r.extensions["x-cache"], true),
+        Either::Left(_) => panic!("expected response"),
+    }
+
+    let value = serde_json::to_value(&api).expect("serializing spec");
+    let expected: serde_json::Value = serde_yaml::from_str(SPEC).expect("deserializing value");
+    assert_eq!(value, expected);
+}
+
+#[test]
+fn test_extensions_in_resolved_spec() {
+    let api: ResolvableApi<DefaultSchema> =
+        paperclip::v2::from_str(SPEC).expect("deserializing spec");
+    let api = api.resolve().expect("resolution");
+
+    let op = &api.paths["/pets"].methods[&HttpMethod::Get];
+    assert_eq!(op.extensions["x-rate-limit"], 10);
+    assert_eq!(op.responses["404"].read().extensions["x-cache"], false);
+    assert_eq!(
+        api.paths["/pets"].parameters[0].read().extensions["x-example"],
+        5
+    );
+}
+
+#[test]
+fn test_security_scheme_extensions_merged() {
+    let mut map = Default::default();
+    let scheme: SecurityScheme = serde_json::from_value(json!({
+        "type": "apiKey",
+        "x-scope": "pets",
+    }))
+    .expect("deserializing scheme");
+    scheme.update_definitions("ApiKey", &mut map);
+
+    let scheme: SecurityScheme = serde_json::from_value(json!({
+        "type": "apiKey",
+        "x-scope": "stores",
+        "x-owner": "team",
+    }))
+    .expect("deserializing scheme");
+    scheme.update_definitions("ApiKey", &mut map);
+
+    assert_eq!(
+        serde_json::to_value(&map["ApiKey"].extensions).unwrap(),
+        json!({"x-owner": "team", "x-scope": "pets"})
+    );
+}
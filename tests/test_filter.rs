@@ -0,0 +1,213 @@
+use paperclip::v2::{
+    self,
+    codegen::{DefaultEmitter, Emitter, EmitterState},
+    filter::Filter,
+    models::{DefaultSchema, HttpMethod, ResolvableApi},
+};
+
+use std::fs::{self, File};
+use std::path::PathBuf;
+
+const SPEC: &str = "swagger: '2.0'
+info: {title: Pets, version: '1.0'}
+paths:
+  /pets:
+    get:
+      operationId: listPets
+      tags: [pets]
+      parameters:
+        - $ref: '#/parameters/Limit'
+      responses:
+        '200':
+          description: OK
+          schema:
+            type: array
+            items:
+              $ref: '#/definitions/Pet'
+    post:
+      operationId: addPet
+      tags: [pets]
+      parameters:
+        - name: body
+          in: body
+          schema:
+            $ref: '#/definitions/NewPet'
+      responses:
+        '201': {description: Created}
+  /pets/{id}:
+    parameters:
+      - {name: id, in: path, type: string, required: true}
+    delete:
+      operationId: deletePet
+      tags: [pets, admin]
+      responses:
+        default:
+          $ref: '#/responses/Error'
+  /stores:
+    get:
+      operationId: listStores
+      tags: [stores]
+      responses:
+        '200':
+          description: OK
+          schema:
+            type: array
+            items:
+              $ref: '#/definitions/Store'
+parameters:
+  Limit: {name: limit, in: query, type: integer}
+responses:
+  Error:
+    description: Error
+    schema:
+      $ref: '#/definitions/Error'
+definitions:
+  Pet:
+    properties:
+      owner:
+        $ref: '#/definitions/Owner'
+      tags:
+        type: array
+        items:
+          $ref: '#/definitions/Tag'
+  NewPet:
+    properties:
+      name: {type: string}
+  Owner:
+    properties:
+      pets:
+        type: array
+        items:
+          $ref: '#/definitions/Pet'
+  Tag:
+    properties:
+      name: {type: string}
+  Store:
+    properties:
+      name: {type: string}
+  Error:
+    properties:
+      message: {type: string}
+";
+
+fn api() -> ResolvableApi<DefaultSchema> {
+    v2::from_str(SPEC).expect("parsing spec")
+}
+
+fn operations(api: &ResolvableApi<DefaultSchema>) -> Vec<String> {
+    api.paths
+        .values()
+        .flat_map(|item| item.methods.values())
+        .filter_map(|op| op.operation_id.clone())
+        .collect()
+}
+
+#[test]
+fn test_filter_operations() {
+    let api = api();
+    let filtered = Filter::default().include_tag("pets").apply(&api);
+    assert_eq!(
+        operations(&filtered),
+        vec!["listPets", "addPet", "deletePet"]
+    );
+
+    let filtered = Filter::default()
+        .include_tag("pets")
+        .exclude_tag("admin")
+        .apply(&api);
+    assert_eq!(operations(&filtered), vec!["listPets", "addPet"]);
+
+    let filtered = Filter::default().include_path("/pets/*").apply(&api);
+    assert_eq!(operations(&filtered), vec!["deletePet"]);
+
+    let filtered = Filter::default()
+        .include_path("/pets*")
+        .include_method(HttpMethod::Get)
+        .include_method(HttpMethod::Delete)
+        .apply(&api);
+    assert_eq!(operations(&filtered), vec!["listPets", "deletePet"]);
+
+    let filtered = Filter::default()
+        .include_operation("listStores")
+        .include_operation("addPet")
+        .apply(&api);
+    assert_eq!(operations(&filtered), vec!["addPet", "listStores"]);
+
+    let filtered = Filter::default()
+        .exclude_path("/pets*")
+        .exclude_operation("listStores")
+        .apply(&api);
+    assert!(filtered.paths.is_empty());
+    assert!(filtered.definitions.is_empty());
+
+    assert!(Filter::default().is_empty());
+    assert_eq!(operations(&Filter::default().apply(&api)), operations(&api));
+}
+
+#[test]
+fn test_unreachable_objects_are_pruned() {
+    let filter = Filter::default().include_operation("listPets");
+    let check = |api: &ResolvableApi<DefaultSchema>, definitions: &[&str]| {
+        assert_eq!(api.paths.keys().collect::<Vec<_>>(), vec!["/pets"]);
+        assert_eq!(api.definitions.keys().collect::<Vec<_>>(), definitions);
+        assert_eq!(api.parameters.keys().collect::<Vec<_>>(), vec!["Limit"]);
+        assert!(api.responses.is_empty());
+    };
+
+    // Raw specs are filtered using the references.
+    check(&filter.apply(&api()), &["Owner", "Pet", "Tag"]);
+    // Resolved specs are filtered using the resolved schemas (which includes
+    // the definitions added for anonymous schemas).
+    check(
+        &filter.apply(&api().resolve().expect("resolving spec")),
+        &["GetPetsResponse", "Owner", "Pet", "Tag"],
+    );
+
+    let filtered = Filter::default()
+        .include_operation("deletePet")
+        .apply(&api().resolve().expect("resolving spec"));
+    assert_eq!(
+        filtered.definitions.keys().collect::<Vec<_>>(),
+        vec!["Error"]
+    );
+    assert_eq!(filtered.responses.keys().collect::<Vec<_>>(), vec!["Error"]);
+    assert!(filtered.parameters.is_empty());
+}
+
+#[test]
+fn test_filter_in_emitter() {
+    let root = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    let fd = File::open(root.join("tests/pet-v2.yaml")).expect("file?");
+    let api: ResolvableApi<DefaultSchema> = v2::from_reader(fd).expect("deserializing spec");
+
+    let mut state = EmitterState::default();
+    state.working_dir = std::env::temp_dir().join("paperclip-test-filter");
+    let _ = fs::remove_dir_all(&state.working_dir);
+    state.filter = Filter::default()
+        .include_path("/pets*")
+        .exclude_method(HttpMethod::Delete);
+    let dir = state.working_dir.clone();
+
+    let emitter = DefaultEmitter::from(state);
+    emitter
+        .generate(&api.resolve().expect("resolution"))
+        .expect("codegen");
+
+    let mut files = fs::read_dir(&dir)
+        .expect("reading generated code")
+        .map(|e| e.unwrap().file_name().into_string().unwrap())
+        .collect::<Vec<_>>();
+    files.sort();
+    assert_eq!(
+        files,
+        vec![
+            "category.rs",
+            "generics.rs",
+            "mod.rs",
+            "pet.rs",
+            "tag.rs",
+            "util.rs"
+        ]
+    );
+    let _ = fs::remove_dir_all(&dir);
+}
@@ -0,0 +1,111 @@
+use once_cell::sync::Lazy;
+use paperclip::v2::{
+    self,
+    models::{DefaultSchema, ResolvableApi, SpecFormat},
+};
+use paperclip::{v3, PaperClipError};
+
+use std::io::{self, Read};
+use std::path::PathBuf;
+
+static ROOT: Lazy<PathBuf> = Lazy::new(|| PathBuf::from(env!("CARGO_MANIFEST_DIR")));
+
+const SPEC_JSON: &str = r#"{
+  "swagger": "2.0",
+  "info": {"title": "Pets", "version": "1.0"},
+  "paths": {}
+}"#;
+
+const SPEC_YAML: &str = "swagger: \"2.0\"
+info:
+  title: Pets
+  version: \"1.0\"
+paths: {}
+";
+
+/// Reader which only reads a byte at a time (and cannot seek).
+struct Trickle<'a>(&'a [u8]);
+
+impl Read for Trickle<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.0.is_empty() || buf.is_empty() {
+            return Ok(0);
+        }
+
+        buf[0] = self.0[0];
+        self.0 = &self.0[1..];
+        Ok(1)
+    }
+}
+
+fn temp_file(name: &str, contents: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("paperclip-formats-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join(name);
+    std::fs::write(&path, contents).unwrap();
+    path
+}
+
+#[test]
+fn test_detection_with_bom_and_whitespace() {
+    let json = format!("\u{feff}\n  \t\r\n{}", SPEC_JSON);
+    let api: ResolvableApi<DefaultSchema> = v2::from_str(&json).expect("parsing JSON");
+    assert_eq!(api.spec_format, SpecFormat::Json);
+    assert_eq!(api.info.title, "Pets");
+
+    let yaml = format!("\u{feff}\n{}", SPEC_YAML);
+    let api: ResolvableApi<DefaultSchema> = v2::from_slice(yaml.as_bytes()).expect("parsing YAML");
+    assert_eq!(api.spec_format, SpecFormat::Yaml);
+    assert_eq!(api.info.version, "1.0");
+
+    // Errors come from the detected format.
+    match v2::from_str::<DefaultSchema>("\n  {\"swagger\": ") {
+        Err(PaperClipError::Json(_)) => (),
+        r => panic!("unexpected result: {:?}", r.map(|_| ())),
+    }
+
+    assert_eq!(SpecFormat::detect(b""), SpecFormat::Yaml);
+    assert_eq!(SpecFormat::detect(b"\xEF\xBB\xBF {}"), SpecFormat::Json);
+    assert_eq!(SpecFormat::from_extension("YML"), Some(SpecFormat::Yaml));
+    assert_eq!(SpecFormat::from_extension("txt"), None);
+}
+
+#[test]
+fn test_streaming_and_format_override() {
+    let api: ResolvableApi<DefaultSchema> =
+        v2::from_reader(Trickle(format!("\n\n{}", SPEC_JSON).as_bytes())).expect("parsing JSON");
+    assert_eq!(api.spec_format, SpecFormat::Json);
+
+    // JSON is also YAML.
+    let api: ResolvableApi<DefaultSchema> =
+        v2::from_reader_with_format(SPEC_JSON.as_bytes(), SpecFormat::Yaml)
+            .expect("parsing JSON as YAML");
+    assert_eq!(api.spec_format, SpecFormat::Yaml);
+
+    match v2::from_reader_with_format::<_, DefaultSchema>(SPEC_YAML.as_bytes(), SpecFormat::Json) {
+        Err(PaperClipError::Json(_)) => (),
+        r => panic!("unexpected result: {:?}", r.map(|_| ())),
+    }
+}
+
+#[test]
+fn test_from_path() {
+    // Extension takes precedence over the contents.
+    let path = temp_file("spec.yml", SPEC_JSON);
+    let api: ResolvableApi<DefaultSchema> = v2::from_path(&path).expect("parsing spec");
+    assert_eq!(api.spec_format, SpecFormat::Yaml);
+
+    let path = temp_file("spec.JSON", &format!("\r\n{}", SPEC_JSON));
+    let api: ResolvableApi<DefaultSchema> = v2::from_path(&path).expect("parsing spec");
+    assert_eq!(api.spec_format, SpecFormat::Json);
+
+    let path = temp_file("spec", &format!("  {}", SPEC_JSON));
+    let api: ResolvableApi<DefaultSchema> = v2::from_path(&path).expect("parsing spec");
+    assert_eq!(api.spec_format, SpecFormat::Json);
+    std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+
+    let api: v3::ResolvableApi<v3::DefaultSchema> =
+        v3::from_path(ROOT.join("tests/pet-v3.yaml")).expect("parsing spec");
+    assert_eq!(api.spec_format, SpecFormat::Yaml);
+    assert!(v2::from_path::<_, DefaultSchema>(ROOT.join("tests/missing.yaml")).is_err());
+}
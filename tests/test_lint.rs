@@ -0,0 +1,234 @@
+use paperclip::diagnostics::{Diagnostic, Severity};
+use paperclip::v2::{
+    lint::{Level, Lint, LintConfig, Linter, Rule},
+    models::{DefaultSchema, OperationProtocol, ResolvableApi},
+};
+use paperclip::PaperClipError;
+
+fn lints(linter: &Linter, spec: &str) -> Vec<(Severity, String, String)> {
+    let spec = serde_yaml::from_str(spec).expect("parsing spec");
+    linter
+        .lint(&spec)
+        .expect("linting spec")
+        .issues
+        .iter()
+        .map(|i| {
+            let d = Diagnostic::from_issue(i);
+            (d.severity, d.pointer.unwrap_or_default(), d.message)
+        })
+        .collect()
+}
+
+fn lint(pointer: &str, message: &str) -> (Severity, String, String) {
+    (Severity::Warning, pointer.into(), message.into())
+}
+
+#[test]
+fn test_builtin_rules() {
+    let spec = "swagger: '2.0'
+info: {title: Pets, version: '1.0'}
+paths:
+  /pets:
+    get:
+      operationId: ListPets
+      tags: [pets]
+      summary: List pets
+      responses:
+        '200':
+          description: OK
+          schema:
+            type: array
+            items:
+              $ref: '#/definitions/Pet'
+  /pet/{id}:
+    get:
+      operationId: get_pet
+      responses:
+        '200':
+          description: OK
+          schema:
+            properties:
+              name: {type: string}
+  /stores:
+    get:
+      operationId: listStores
+      tags: [stores]
+      description: List stores
+      parameters:
+        - $ref: '#/parameters/PageSize'
+      responses:
+        '200':
+          $ref: '#/responses/Stores'
+parameters:
+  PageSize: {name: page_size, in: query, type: integer}
+responses:
+  Stores:
+    description: OK
+    schema:
+      type: array
+      items:
+        type: array
+        items:
+          properties:
+            name: {type: string}
+definitions:
+  Pet:
+    type: object
+";
+
+    let linter = Linter::default();
+    assert_eq!(
+        linter.rule_names().collect::<Vec<_>>(),
+        vec![
+            "operation-id-camel-case",
+            "operation-tags",
+            "operation-description",
+            "no-inline-response-objects",
+            "plural-collection-paths",
+            "list-pagination",
+        ]
+    );
+
+    assert_eq!(
+        lints(&linter, spec),
+        vec![
+            lint(
+                "/paths/~1pet~1{id}/get/operationId",
+                "Operation ID \"get_pet\" is not in camelCase [operation-id-camel-case]"
+            ),
+            lint(
+                "/paths/~1pets/get/operationId",
+                "Operation ID \"ListPets\" is not in camelCase [operation-id-camel-case]"
+            ),
+            lint(
+                "/paths/~1pet~1{id}/get",
+                "Operation doesn't have any tags [operation-tags]"
+            ),
+            lint(
+                "/paths/~1pet~1{id}/get",
+                "Operation doesn't have a summary or description [operation-description]"
+            ),
+            lint(
+                "/responses/Stores/schema/items/items",
+                "Response schema is an anonymous object (it should be in definitions) \
+                 [no-inline-response-objects]"
+            ),
+            lint(
+                "/paths/~1pet~1{id}/get/responses/200/schema",
+                "Response schema is an anonymous object (it should be in definitions) \
+                 [no-inline-response-objects]"
+            ),
+            lint(
+                "/paths/~1pet~1{id}",
+                "Collection \"pet\" in path \"/pet/{id}\" should be plural [plural-collection-paths]"
+            ),
+            lint(
+                "/paths/~1pets/get",
+                "List operation doesn't have pagination parameters (limit, offset, page or cursor) \
+                 [list-pagination]"
+            ),
+        ]
+    );
+}
+
+#[test]
+fn test_configuration_and_suppression() {
+    let spec = "swagger: '2.0'
+info: {title: Pets, version: '1.0'}
+x-paperclip-lint-ignore: operation-tags
+paths:
+  /pet/{id}:
+    x-paperclip-lint-ignore: true
+    get:
+      responses:
+        '200': {description: OK}
+  /pets:
+    get:
+      operationId: list_pets
+      responses:
+        '200': {description: OK}
+  /stores:
+    get:
+      x-paperclip-lint-ignore: [operation-id-camel-case]
+      operationId: list_stores
+      responses:
+        '200': {description: OK}
+";
+
+    let config: LintConfig = serde_yaml::from_str(
+        "rules:
+  operation-id-camel-case: error
+  operation-description: off
+",
+    )
+    .expect("parsing config");
+    assert_eq!(config.rules["operation-description"], Level::Off);
+
+    let linter = Linter::default().configure(&config).expect("configuring");
+    assert_eq!(
+        lints(&linter, spec),
+        vec![(
+            Severity::Error,
+            "/paths/~1pets/get/operationId".into(),
+            "Operation ID \"list_pets\" is not in camelCase [operation-id-camel-case]".into()
+        )]
+    );
+
+    // Extensions in path items are ignored by the models.
+    let api: ResolvableApi<DefaultSchema> =
+        paperclip::v2::from_str(spec).expect("parsing spec as model");
+    assert_eq!(api.paths["/pet/{id}"].methods.len(), 1);
+
+    let config: LintConfig =
+        serde_yaml::from_str("rules: {no-such-rule: warning}").expect("parsing config");
+    match Linter::<DefaultSchema>::default().configure(&config) {
+        Err(PaperClipError::UnknownLintRule(r)) => assert_eq!(r, "no-such-rule"),
+        _ => panic!("expected unknown rule"),
+    }
+}
+
+#[test]
+fn test_custom_rules() {
+    struct HttpsOnly;
+
+    impl Rule<DefaultSchema> for HttpsOnly {
+        fn name(&self) -> &str {
+            "https-only"
+        }
+
+        fn severity(&self) -> Severity {
+            Severity::Error
+        }
+
+        fn check(&self, api: &ResolvableApi<DefaultSchema>) -> Vec<Lint> {
+            if api.schemes.contains(&OperationProtocol::Http) {
+                vec![Lint::new("/schemes", "API shouldn't be served over HTTP")]
+            } else {
+                vec![]
+            }
+        }
+    }
+
+    let spec = "swagger: '2.0'
+info: {title: Pets, version: '1.0'}
+schemes: [http, https]
+paths: {}
+";
+    let linter = Linter::empty().rule(HttpsOnly);
+    assert_eq!(
+        lints(&linter, spec),
+        vec![(
+            Severity::Error,
+            "/schemes".into(),
+            "API shouldn't be served over HTTP [https-only]".into()
+        )]
+    );
+
+    let config: LintConfig =
+        serde_yaml::from_str("rules: {https-only: off}").expect("parsing config");
+    let linter = Linter::empty()
+        .rule(HttpsOnly)
+        .configure(&config)
+        .expect("configuring");
+    assert!(lints(&linter, spec).is_empty());
+}
@@ -0,0 +1,260 @@
+use paperclip::overlay::Overlay;
+use paperclip::v2::{
+    merge::{Merger, Policy},
+    models::{DefaultApiRaw, HttpMethod},
+};
+use paperclip::PaperClipError;
+use serde_json::json;
+
+const PETS: &str = "swagger: '2.0'
+info: {title: Gateway, version: '1.0'}
+basePath: /api
+consumes: [application/json]
+tags: [{name: pets}]
+securityDefinitions:
+  oauth:
+    type: oauth2
+    flow: implicit
+    authorizationUrl: https://example.com/auth
+    scopes: {read: Read}
+paths:
+  /pets:
+    get:
+      responses:
+        '200': {description: OK}
+definitions:
+  Error:
+    properties:
+      message: {type: string}
+  Pet:
+    properties:
+      name: {type: string}
+";
+
+const STORES: &str = "swagger: '2.0'
+info: {title: Stores, version: '2.3'}
+basePath: /api/stores
+consumes: [application/xml]
+tags: [{name: pets}, {name: stores}]
+securityDefinitions:
+  oauth:
+    type: oauth2
+    flow: implicit
+    authorizationUrl: https://example.com/stores/auth
+    scopes: {write: Write}
+paths:
+  /{id}:
+    get:
+      responses:
+        '200': {description: OK}
+definitions:
+  Error:
+    properties:
+      message: {type: string}
+  Store:
+    properties:
+      name: {type: string}
+";
+
+fn spec(s: &str) -> DefaultApiRaw {
+    serde_yaml::from_str(s).expect("parsing spec")
+}
+
+#[test]
+fn test_merge_specs() {
+    let api = Merger::default()
+        .merge_all(vec![spec(PETS), spec(STORES)])
+        .expect("merging specs")
+        .expect("merged spec");
+
+    assert_eq!(api.info.title, "Gateway");
+    assert_eq!(api.base_path.as_deref(), Some("/api"));
+    assert_eq!(
+        api.paths.keys().collect::<Vec<_>>(),
+        vec!["/pets", "/stores/{id}"]
+    );
+    assert_eq!(
+        api.definitions.keys().collect::<Vec<_>>(),
+        vec!["Error", "Pet", "Store"]
+    );
+    assert_eq!(api.consumes.len(), 2);
+    assert_eq!(
+        api.tags.iter().map(|t| t.name.as_str()).collect::<Vec<_>>(),
+        vec!["pets", "stores"]
+    );
+
+    // Existing fields are kept and scopes are combined.
+    let oauth = &api.security_definitions["oauth"];
+    assert_eq!(oauth.auth_url.as_deref(), Some("https://example.com/auth"));
+    assert_eq!(
+        oauth.scopes.keys().collect::<Vec<_>>(),
+        vec!["read", "write"]
+    );
+}
+
+#[test]
+fn test_merge_preserves_number_precision() {
+    let mut stores = spec(STORES);
+    stores.definitions.insert(
+        "Limit".into(),
+        serde_json::from_value(json!({
+            "type": "number",
+            "maximum": 2147483647.0,
+            "minimum": 100.1,
+            "multipleOf": 0.01
+        }))
+        .expect("schema"),
+    );
+
+    let api = Merger::default()
+        .merge_all(vec![spec(PETS), stores])
+        .expect("merging specs")
+        .expect("merged spec");
+    let limit = serde_json::to_value(&api.definitions["Limit"]).expect("schema");
+    assert_eq!(limit["maximum"], 2147483647.0);
+    assert_eq!(limit["minimum"], 100.1);
+    assert_eq!(limit["multipleOf"], 0.01);
+}
+
+#[test]
+fn test_merge_policies() {
+    let mut stores = spec(STORES);
+    stores
+        .definitions
+        .get_mut("Error")
+        .unwrap()
+        .properties
+        .clear();
+
+    let err = Merger::default()
+        .merge(&mut spec(PETS), stores.clone())
+        .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Definition \"Error\" is different in the merged specs (at /definitions/Error)"
+    );
+
+    let mut api = spec(PETS);
+    Merger::default()
+        .definitions(Policy::Replace)
+        .merge(&mut api, stores.clone())
+        .expect("merging specs");
+    assert!(api.definitions["Error"].properties.is_empty());
+
+    let mut api = spec(PETS);
+    Merger::default()
+        .definitions(Policy::Keep)
+        .security_definitions(Policy::Keep)
+        .merge(&mut api, stores.clone())
+        .expect("merging specs");
+    assert_eq!(api.definitions["Error"].properties.len(), 1);
+    assert_eq!(api.security_definitions["oauth"].scopes.len(), 1);
+
+    match Merger::default()
+        .security_definitions(Policy::Error)
+        .merge(&mut spec(PETS), spec(STORES))
+    {
+        Err(PaperClipError::Located(pointer, e)) => {
+            assert_eq!(pointer, "/securityDefinitions/oauth");
+            assert!(matches!(*e, PaperClipError::MergeConflict(..)));
+        }
+        r => panic!("unexpected result: {:?}", r),
+    }
+}
+
+#[test]
+fn test_merge_paths() {
+    let other = "swagger: '2.0'
+info: {title: Pets, version: '1.0'}
+basePath: /api/
+paths:
+  /pets:
+    get:
+      responses:
+        '200': {description: OK}
+    post:
+      responses:
+        '201': {description: Created}
+";
+
+    // Operations are combined (identical ones are fine).
+    let mut api = spec(PETS);
+    Merger::default()
+        .merge(&mut api, spec(other))
+        .expect("merging specs");
+    assert_eq!(
+        api.paths["/pets"].methods.keys().collect::<Vec<_>>(),
+        vec![&HttpMethod::Get, &HttpMethod::Post]
+    );
+
+    // Conflicting operations are errors.
+    let other = other.replace("'200': {description: OK}", "'204': {description: OK}");
+    let err = Merger::default()
+        .merge(&mut spec(PETS), spec(&other))
+        .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Operation \"GET /pets\" is different in the merged specs (at /paths/~1pets/get)"
+    );
+
+    // Paths should be under the base path.
+    let err = Merger::default()
+        .merge(&mut spec(PETS), spec(&other.replace("/api/", "/v2")))
+        .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Path \"/pets\" is not under the base path \"/api\""
+    );
+}
+
+#[test]
+fn test_overlay() {
+    let mut spec = json!({
+        "info": {"title": "Pets", "version": "1.0"},
+        "tags": [{"name": "pets"}],
+        "paths": {
+            "/pets": {"get": {}, "post": {}},
+            "/internal/health": {"get": {}},
+            "/internal/metrics": {"get": {}},
+        },
+    });
+    let overlay: Overlay = serde_yaml::from_str(
+        "overlay: 1.0.0
+actions:
+  - target: /paths/~1internal*
+    description: Strip internal paths
+    remove: true
+  - target: /paths/*/get
+    update:
+      x-gateway: true
+      tags: [public]
+  - target: /tags
+    update:
+      - name: public
+  - target: /info/title
+    update: Pet store
+",
+    )
+    .expect("parsing overlay");
+
+    overlay.apply(&mut spec).expect("applying overlay");
+    assert_eq!(
+        spec,
+        json!({
+            "info": {"title": "Pet store", "version": "1.0"},
+            "tags": [{"name": "pets"}, {"name": "public"}],
+            "paths": {
+                "/pets": {"get": {"x-gateway": true, "tags": ["public"]}, "post": {}},
+            },
+        })
+    );
+
+    // Targets should match something.
+    let overlay: Overlay =
+        serde_yaml::from_str("actions: [{target: /paths/~1internal*, remove: true}]")
+            .expect("parsing overlay");
+    match overlay.apply(&mut spec) {
+        Err(PaperClipError::OverlayTarget(t)) => assert_eq!(t, "/paths/~1internal*"),
+        r => panic!("unexpected result: {:?}", r),
+    }
+}
@@ -0,0 +1,15 @@
+
+pub struct MissingFoobar;
+pub struct FoobarExists;
+pub struct MissingId;
+pub struct IdExists;
+pub struct MissingName;
+pub struct NameExists;
+pub struct MissingPetId;
+pub struct PetIdExists;
+pub struct MissingSomeDataFile;
+pub struct SomeDataFileExists;
+pub struct MissingValues;
+pub struct ValuesExists;
+pub struct MissingXAuth;
+pub struct XAuthExists;
@@ -0,0 +1,1151 @@
+
+#[macro_use]
+extern crate serde;
+
+pub mod category {
+    include!("./category.rs");
+}
+
+pub mod get_shipments_id_response {
+    include!("./get_shipments_id_response.rs");
+}
+
+pub mod miscellaneous {
+    include!("./miscellaneous.rs");
+}
+
+pub mod order {
+    include!("./order.rs");
+}
+
+pub mod pet {
+    include!("./pet.rs");
+}
+
+pub mod post_shipments_body {
+    include!("./post_shipments_body.rs");
+}
+
+pub mod recursive_container {
+    include!("./recursive_container.rs");
+}
+
+pub mod recursive_object {
+    include!("./recursive_object.rs");
+}
+
+pub mod status {
+    include!("./status.rs");
+}
+
+pub mod tag {
+    include!("./tag.rs");
+}
+
+pub mod test_enum {
+    include!("./test_enum.rs");
+}
+
+pub mod test_nested_array_with_object {
+    include!("./test_nested_array_with_object.rs");
+}
+
+pub mod client {
+    use futures::Stream;
+    use parking_lot::Mutex;
+
+    use std::borrow::Cow;
+    use std::fmt::Debug;
+    use std::path::Path;
+
+    /// Common API errors.
+    #[derive(Debug, thiserror::Error)]
+    pub enum ApiError<R: Debug + Send + 'static> {
+        #[error("API request failed for path: {} (code: {})", _0, _1)]
+        Failure(String, http::status::StatusCode, Mutex<R>),
+        #[error("Unsupported media type in response: {}", _0)]
+        UnsupportedMediaType(String, Mutex<R>),
+        #[error("An error has occurred while performing the API request: {}", _0)]
+        Reqwest(reqwest::Error),
+        #[error("I/O error: {}", _0)]
+        Io(std::io::Error),
+        #[error("Authentication error: {}", _0)]
+        Auth(AuthError),
+        #[error("API request timed out after {:?}", _0)]
+        Timeout(std::time::Duration),
+        #[error("Error en/decoding \"application/json\" data: {}", _0)]
+        ApplicationJson(serde_json::Error),
+        #[error("Error en/decoding \"application/yaml\" data: {}", _0)]
+        ApplicationYaml(serde_yaml::Error),
+    }
+
+    /// Form object for building multipart request body.
+    pub trait Form: Sized {
+        /// Creates a new builder.
+        fn new() -> Self;
+
+        /// Adds the given key and value as text.
+        fn text<T, U>(self, key: T, value: U) -> Self
+            where T: Into<Cow<'static, str>>,
+                  U: Into<Cow<'static, str>>;
+
+        /// Adds the file from the given path for streaming.
+        fn file<K>(self, key: K, path: &Path) -> std::io::Result<Self>
+            where K: Into<Cow<'static, str>>;
+    }
+
+    /// HTTP Request.
+    pub trait Request {
+        type Form: Form;
+
+        /// Sets the header with the given key and value.
+        fn header(self, name: &'static str, value: &str) -> Self;
+
+        /// Sets body using the given vector of bytes.
+        ///
+        /// **NOTE:** Appropriate `Content-Type` header must be set
+        /// after calling this method.
+        fn body_bytes(self, body: Vec<u8>) -> Self;
+
+        /// Sets JSON body based on the given value.
+        fn json<T: serde::Serialize>(self, value: &T) -> Self;
+
+        /// Sets `multipart/form-data` body using the given form.
+        fn multipart_form_data(self, form: Self::Form) -> Self;
+
+        /// Sets/adds query parameters based on the given value.
+        ///
+        /// **NOTE:** This method must be called only once. It's unspecified
+        /// as to whether this appends/replaces query parameters.
+        fn query<T: serde::Serialize>(self, params: &T) -> Self;
+
+        /// Sets the `Authorization` header for HTTP basic authentication.
+        fn basic_auth(self, username: &str, password: Option<&str>) -> Self;
+
+        /// Sets the `Authorization` header using the given bearer token.
+        fn bearer_auth(self, token: &str) -> Self;
+
+        /// Clones this request, if its body can be cloned (requests
+        /// which can't be cloned aren't retried).
+        fn try_clone(&self) -> Option<Self>
+            where Self: Sized
+        {
+            None
+        }
+    }
+
+    impl Form for reqwest::multipart::Form {
+        fn new() -> Self {
+            reqwest::multipart::Form::new()
+        }
+
+        fn text<T, U>(self, key: T, value: U) -> Self
+            where T: Into<Cow<'static, str>>,
+                  U: Into<Cow<'static, str>>
+        {
+            reqwest::multipart::Form::text(self, key, value)
+        }
+
+        fn file<K>(self, key: K, path: &Path) -> std::io::Result<Self>
+            where K: Into<Cow<'static, str>>
+        {
+            use reqwest::multipart::{Form, Part};
+            use tokio_util::codec::{BytesCodec, FramedRead};
+
+            let fd = std::fs::File::open(path)?;
+            let reader = tokio::fs::File::from_std(fd);
+            let bytes_stream = FramedRead::new(reader, BytesCodec::new());
+            let part = Part::stream(reqwest::Body::wrap_stream(bytes_stream));
+            Ok(Form::part(self, key, part))
+        }
+    }
+
+    impl Request for reqwest::RequestBuilder {
+        type Form = reqwest::multipart::Form;
+
+        fn header(self, name: &'static str, value: &str) -> Self {
+            reqwest::RequestBuilder::header(self, name, value)
+        }
+
+        fn multipart_form_data(self, form: Self::Form) -> Self {
+            self.multipart(form)
+        }
+
+        fn body_bytes(self, body: Vec<u8>) -> Self {
+            self.body(body)
+        }
+
+        fn json<T: serde::Serialize>(self, value: &T) -> Self {
+            <reqwest::RequestBuilder>::json(self, value)
+        }
+
+        fn query<T: serde::Serialize>(self, params: &T) -> Self {
+            reqwest::RequestBuilder::query(self, params)
+        }
+
+        fn basic_auth(self, username: &str, password: Option<&str>) -> Self {
+            reqwest::RequestBuilder::basic_auth(self, username, password)
+        }
+
+        fn bearer_auth(self, token: &str) -> Self {
+            reqwest::RequestBuilder::bearer_auth(self, token)
+        }
+
+        fn try_clone(&self) -> Option<Self> {
+            reqwest::RequestBuilder::try_clone(self)
+        }
+    }
+
+    /// HTTP Response.
+    #[async_trait::async_trait]
+    pub trait Response: Debug + Send + Sized {
+        type Bytes: AsRef<[u8]>;
+        type Error;
+
+        /// Gets the value for the given header name, if any.
+        fn header(&self, name: &'static str) -> Option<&str>;
+
+        /// Takes all headers from the response.
+        fn take_headers(&mut self) -> http::header::HeaderMap;
+
+        /// Status code for this response.
+        fn status(&self) -> http::status::StatusCode;
+
+        /// Media type for this response body (if any).
+        fn media_type(&self) -> Option<mime::MediaType>;
+
+        /// Response body as a stream.
+        fn stream(self) -> Box<dyn Stream<Item=Result<Self::Bytes, Self::Error>> + Unpin>;
+
+        /// Vector of bytes from the response body.
+        async fn body_bytes(self) -> Result<Self::Bytes, ApiError<Self>>;
+    }
+
+    #[async_trait::async_trait]
+    impl Response for reqwest::Response {
+        type Bytes = bytes::Bytes;
+        type Error = reqwest::Error;
+
+        fn header(&self, name: &'static str) -> Option<&str> {
+            self.headers().get(name).and_then(|v| v.to_str().ok())
+        }
+
+        fn take_headers(&mut self) -> http::header::HeaderMap {
+            std::mem::replace(self.headers_mut(), http::header::HeaderMap::new())
+        }
+
+        fn status(&self) -> http::status::StatusCode {
+            reqwest::Response::status(self)
+        }
+
+        fn media_type(&self) -> Option<mime::MediaType> {
+            self.header(http::header::CONTENT_TYPE.as_str())
+                .and_then(|v| v.parse().ok())
+        }
+
+        fn stream(self) -> Box<dyn Stream<Item=Result<Self::Bytes, Self::Error>> + Unpin> {
+            Box::new(self.bytes_stream()) as Box<_>
+        }
+
+        async fn body_bytes(self) -> Result<Self::Bytes, ApiError<Self>> {
+            Ok(self.bytes().await.map_err(ApiError::Reqwest)?)
+        }
+    }
+
+    /// Represents an API client.
+    #[async_trait::async_trait]
+    pub trait ApiClient {
+        type Request: Request + Send;
+        type Response: Response;
+
+        /// Consumes a method and a relative path and produces a request builder for a single API call.
+        fn request_builder(&self, method: http::Method, rel_path: &str) -> Self::Request;
+
+        /// Performs the HTTP request using the given `Request` object
+        /// and returns a `Response` future.
+        async fn make_request(&self, req: Self::Request) -> Result<Self::Response, ApiError<Self::Response>>;
+
+        /// Credentials for the operations requiring authentication (if any).
+        fn auth(&self) -> Option<&Auth> {
+            None
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl ApiClient for reqwest::Client {
+        type Request = reqwest::RequestBuilder;
+        type Response = reqwest::Response;
+
+        fn request_builder(&self, method: http::Method, rel_path: &str) -> Self::Request {
+            let mut u = String::from("https://pets.com:8888/api");
+            u.push_str(rel_path.trim_start_matches('/'));
+            self.request(method, &u)
+        }
+
+        async fn make_request(&self, req: Self::Request) -> Result<Self::Response, ApiError<Self::Response>> {
+            let req = req.build().map_err(ApiError::Reqwest)?;
+            let resp = self.execute(req).await.map_err(ApiError::Reqwest)?;
+            Ok(resp)
+        }
+    }
+
+    /// Error from authenticating requests (say, failure to get an access token).
+    pub type AuthError = Box<dyn std::error::Error + Send + Sync>;
+
+    /// Security scheme (from `securityDefinitions` in the spec).
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum SecurityScheme {
+        /// HTTP basic authentication.
+        Basic,
+        /// API key in the header with the given name.
+        ApiKeyHeader(&'static str),
+        /// API key in the query parameter with the given name.
+        ApiKeyQuery(&'static str),
+        /// OAuth2 access token (sent as a bearer token).
+        OAuth2,
+    }
+
+    /// Security requirement of an operation. This has the names of the schemes
+    /// (all of which should be applied) along with the scopes needed from them.
+    pub type SecurityRequirement = &'static [(&'static str, SecurityScheme, &'static [&'static str])];
+
+    /// Provider of OAuth2 access tokens.
+    #[async_trait::async_trait]
+    pub trait TokenProvider: Send + Sync {
+        /// Returns an access token having the given scopes.
+        async fn token(&self, scopes: &[&str]) -> Result<String, AuthError>;
+    }
+
+    /// Static access token.
+    #[async_trait::async_trait]
+    impl TokenProvider for String {
+        async fn token(&self, _: &[&str]) -> Result<String, AuthError> {
+            Ok(self.clone())
+        }
+    }
+
+    /// Provider which gets (and caches) access tokens from the token endpoint of an
+    /// `oauth2` scheme using the client credentials (`application`) or the `password`
+    /// flow. Tokens are cached for each set of scopes and they're refreshed (using
+    /// refresh tokens if the endpoint gave us one) before they expire.
+    pub struct OAuth2TokenProvider {
+        client: reqwest::Client,
+        token_url: String,
+        grant: OAuth2Grant,
+        client_auth: Option<(String, Option<String>)>,
+        refresh_margin: std::time::Duration,
+        tokens: futures::lock::Mutex<std::collections::BTreeMap<Vec<String>, OAuth2Token>>,
+    }
+
+    enum OAuth2Grant {
+        ClientCredentials,
+        Password {
+            username: String,
+            password: String,
+        },
+    }
+
+    struct OAuth2Token {
+        access_token: String,
+        refresh_token: Option<String>,
+        refresh_at: Option<std::time::Instant>,
+    }
+
+    #[derive(serde::Deserialize)]
+    struct OAuth2TokenResponse {
+        access_token: String,
+        #[serde(default)]
+        expires_in: Option<u64>,
+        #[serde(default)]
+        refresh_token: Option<String>,
+    }
+
+    impl OAuth2TokenProvider {
+        /// Creates a provider for the client credentials flow. The client is
+        /// authenticated with the token endpoint using HTTP basic authentication.
+        pub fn client_credentials<U, I, S>(token_url: U, client_id: I, client_secret: S) -> Self
+            where U: Into<String>,
+                  I: Into<String>,
+                  S: Into<String>
+        {
+            OAuth2TokenProvider::new(token_url.into(), OAuth2Grant::ClientCredentials)
+                .client(client_id, Some(client_secret.into()))
+        }
+
+        /// Creates a provider for the resource owner password flow. Use `client`
+        /// if the token endpoint also needs the client credentials.
+        pub fn password<T, U, P>(token_url: T, username: U, password: P) -> Self
+            where T: Into<String>,
+                  U: Into<String>,
+                  P: Into<String>
+        {
+            OAuth2TokenProvider::new(token_url.into(), OAuth2Grant::Password {
+                username: username.into(),
+                password: password.into(),
+            })
+        }
+
+        fn new(token_url: String, grant: OAuth2Grant) -> Self {
+            OAuth2TokenProvider {
+                client: reqwest::Client::new(),
+                token_url,
+                grant,
+                client_auth: None,
+                refresh_margin: std::time::Duration::from_secs(30),
+                tokens: futures::lock::Mutex::new(std::collections::BTreeMap::new()),
+            }
+        }
+
+        /// Sets the client ID and secret for authenticating with the token endpoint.
+        pub fn client<I: Into<String>>(mut self, client_id: I, client_secret: Option<String>) -> Self {
+            self.client_auth = Some((client_id.into(), client_secret));
+            self
+        }
+
+        /// Sets the HTTP client used for requesting tokens.
+        pub fn http_client(mut self, client: reqwest::Client) -> Self {
+            self.client = client;
+            self
+        }
+
+        /// Sets how long before their expiry the tokens should be refreshed (30 seconds
+        /// by default). Tokens are refreshed after at most half their lifetime regardless.
+        pub fn refresh_margin(mut self, margin: std::time::Duration) -> Self {
+            self.refresh_margin = margin;
+            self
+        }
+
+        /// Creates a provider for the "petstore_app" scheme (client credentials flow).
+        pub fn for_petstore_app<I, S>(client_id: I, client_secret: S) -> Self
+            where I: Into<String>,
+                  S: Into<String>
+        {
+            OAuth2TokenProvider::client_credentials("https://pets.com/oauth/token", client_id, client_secret)
+        }
+
+        async fn request_token(&self, params: &[(&str, &str)], scopes: &[String]) -> Result<OAuth2Token, AuthError> {
+            let scope = scopes.join(" ");
+            let mut params = params.to_vec();
+            if !scope.is_empty() {
+                params.push(("scope", &scope));
+            }
+
+            let mut req = self.client.post(&self.token_url).form(&params);
+            if let Some((id, secret)) = self.client_auth.as_ref() {
+                req = req.basic_auth(id, secret.as_ref());
+            }
+
+            let resp = req.send().await?;
+            let status = resp.status();
+            let bytes = resp.bytes().await?;
+            if !status.is_success() {
+                return Err(format!(
+                    "Token request failed (code: {}): {}", status, String::from_utf8_lossy(&bytes)
+                ).into());
+            }
+
+            let resp: OAuth2TokenResponse = serde_json::from_slice(&bytes)?;
+            let refresh_at = resp.expires_in.map(|secs| {
+                let lifetime = std::time::Duration::from_secs(secs);
+                let refresh_in = std::cmp::max(
+                    lifetime.checked_sub(self.refresh_margin).unwrap_or_default(),
+                    lifetime / 2,
+                );
+                std::time::Instant::now() + refresh_in
+            });
+
+            Ok(OAuth2Token {
+                access_token: resp.access_token,
+                refresh_token: resp.refresh_token,
+                refresh_at,
+            })
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl TokenProvider for OAuth2TokenProvider {
+        async fn token(&self, scopes: &[&str]) -> Result<String, AuthError> {
+            let mut key: Vec<String> = scopes.iter().map(|s| s.to_string()).collect();
+            key.sort();
+            key.dedup();
+
+            // Lock is held while requesting, so that concurrent calls don't request the same token.
+            let mut tokens = self.tokens.lock().await;
+            let mut refresh_token = None;
+            if let Some(token) = tokens.get(&key) {
+                match token.refresh_at {
+                    Some(t) if t <= std::time::Instant::now() => refresh_token = token.refresh_token.clone(),
+                    _ => return Ok(token.access_token.clone()),
+                }
+            }
+
+            let mut token = None;
+            if let Some(refresh) = refresh_token {
+                let params = [("grant_type", "refresh_token"), ("refresh_token", refresh.as_str())];
+                // Refresh tokens may have expired, in which case we fall back to the flow.
+                token = self.request_token(&params, &key).await.ok();
+            }
+
+            let mut token = match token {
+                Some(t) => t,
+                None => match &self.grant {
+                    OAuth2Grant::ClientCredentials => {
+                        self.request_token(&[("grant_type", "client_credentials")], &key).await?
+                    },
+                    OAuth2Grant::Password { username, password } => {
+                        let params = [("grant_type", "password"), ("username", username.as_str()), ("password", password.as_str())];
+                        self.request_token(&params, &key).await?
+                    },
+                },
+            };
+
+            // Endpoints needn't send refresh tokens again while refreshing.
+            if token.refresh_token.is_none() {
+                token.refresh_token = tokens.get(&key).and_then(|t| t.refresh_token.clone());
+            }
+
+            let access_token = token.access_token.clone();
+            tokens.insert(key, token);
+            Ok(access_token)
+        }
+    }
+
+    /// Credentials for a security scheme.
+    #[derive(Clone)]
+    pub enum Credentials {
+        /// Key for `apiKey` schemes.
+        ApiKey(String),
+        /// Username and password for `basic` schemes.
+        Basic {
+            username: String,
+            password: Option<String>,
+        },
+        /// Provider of access tokens for `oauth2` schemes.
+        OAuth2(std::sync::Arc<dyn TokenProvider>),
+    }
+
+    /// Credentials for security schemes (by their names in `securityDefinitions`).
+    #[derive(Clone, Default)]
+    pub struct Auth {
+        credentials: std::collections::BTreeMap<String, Credentials>,
+    }
+
+    impl Auth {
+        /// Sets the credentials for the given scheme.
+        pub fn credentials<S: Into<String>>(mut self, scheme: S, credentials: Credentials) -> Self {
+            self.credentials.insert(scheme.into(), credentials);
+            self
+        }
+
+        /// Sets the key for the given `apiKey` scheme.
+        pub fn api_key<S, K>(self, scheme: S, key: K) -> Self
+            where S: Into<String>,
+                  K: Into<String>
+        {
+            self.credentials(scheme, Credentials::ApiKey(key.into()))
+        }
+
+        /// Sets the username and password for the given `basic` scheme.
+        pub fn basic<S, U>(self, scheme: S, username: U, password: Option<String>) -> Self
+            where S: Into<String>,
+                  U: Into<String>
+        {
+            self.credentials(scheme, Credentials::Basic {
+                username: username.into(),
+                password,
+            })
+        }
+
+        /// Sets the token provider for the given `oauth2` scheme. Strings
+        /// can be used as (static) access tokens.
+        pub fn oauth2<S, T>(self, scheme: S, provider: T) -> Self
+            where S: Into<String>,
+                  T: TokenProvider + 'static
+        {
+            self.credentials(scheme, Credentials::OAuth2(std::sync::Arc::new(provider)))
+        }
+
+        /// Applies the credentials for the first security requirement (of an operation)
+        /// whose schemes have credentials. The request is unchanged if there's no such
+        /// requirement.
+        pub async fn apply<R, E>(&self, mut req: R, security: &[SecurityRequirement]) -> Result<R, ApiError<E>>
+            where R: Request + Send,
+                  E: Response + 'static
+        {
+            let requirement = security.iter().find(|r| {
+                !r.is_empty() && r.iter().all(|(name, _, _)| self.credentials.contains_key(*name))
+            });
+            let requirement = match requirement {
+                Some(r) => r,
+                None => return Ok(req),
+            };
+
+            for &(name, scheme, scopes) in requirement.iter() {
+                req = match (scheme, &self.credentials[name]) {
+                    (SecurityScheme::ApiKeyHeader(header), Credentials::ApiKey(key)) => req.header(header, key),
+                    (SecurityScheme::ApiKeyQuery(param), Credentials::ApiKey(key)) => req.query(&[(param, key)]),
+                    (SecurityScheme::Basic, Credentials::Basic { username, password }) => {
+                        req.basic_auth(username, password.as_deref())
+                    },
+                    (SecurityScheme::OAuth2, Credentials::OAuth2(provider)) => {
+                        let token = provider.token(scopes).await.map_err(ApiError::Auth)?;
+                        req.bearer_auth(&token)
+                    },
+                    _ => return Err(ApiError::Auth(
+                        format!("Credentials for {:?} don't match its scheme", name).into()
+                    )),
+                };
+            }
+
+            Ok(req)
+        }
+    }
+
+    /// Client which authenticates the requests of operations requiring
+    /// it (through their `SECURITY`) using the given credentials.
+    pub struct AuthClient<C> {
+        client: C,
+        auth: Auth,
+    }
+
+    impl<C> AuthClient<C> {
+        /// Wraps the given client.
+        pub fn new(client: C, auth: Auth) -> Self {
+            AuthClient { client, auth }
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl<C: ApiClient + Send + Sync> ApiClient for AuthClient<C> {
+        type Request = C::Request;
+        type Response = C::Response;
+
+        fn request_builder(&self, method: http::Method, rel_path: &str) -> Self::Request {
+            self.client.request_builder(method, rel_path)
+        }
+
+        async fn make_request(&self, req: Self::Request) -> Result<Self::Response, ApiError<Self::Response>> {
+            self.client.make_request(req).await
+        }
+
+        fn auth(&self) -> Option<&Auth> {
+            Some(&self.auth)
+        }
+    }
+
+    /// Policy for retrying failed requests in `RetryClient`.
+    #[derive(Debug, Clone)]
+    pub struct RetryPolicy {
+        max_retries: u32,
+        initial_backoff: std::time::Duration,
+        max_backoff: std::time::Duration,
+        jitter: bool,
+        timeout: Option<std::time::Duration>,
+        methods: Vec<http::Method>,
+        statuses: Vec<http::status::StatusCode>,
+    }
+
+    impl Default for RetryPolicy {
+        /// Policy for retrying idempotent requests (at most 3 times) which failed
+        /// to get a response, or got 408, 429, 500, 502, 503 or 504 responses.
+        fn default() -> Self {
+            use http::status::StatusCode;
+
+            RetryPolicy {
+                max_retries: 3,
+                initial_backoff: std::time::Duration::from_millis(100),
+                max_backoff: std::time::Duration::from_secs(10),
+                jitter: true,
+                timeout: None,
+                methods: vec![
+                    http::Method::GET,
+                    http::Method::HEAD,
+                    http::Method::PUT,
+                    http::Method::DELETE,
+                    http::Method::OPTIONS,
+                    http::Method::TRACE,
+                ],
+                statuses: vec![
+                    StatusCode::REQUEST_TIMEOUT,
+                    StatusCode::TOO_MANY_REQUESTS,
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    StatusCode::BAD_GATEWAY,
+                    StatusCode::SERVICE_UNAVAILABLE,
+                    StatusCode::GATEWAY_TIMEOUT,
+                ],
+            }
+        }
+    }
+
+    impl RetryPolicy {
+        /// Sets the maximum number of retries for a request.
+        pub fn max_retries(mut self, retries: u32) -> Self {
+            self.max_retries = retries;
+            self
+        }
+
+        /// Sets the backoff before the first retry. This is doubled for each
+        /// retry until it reaches the maximum backoff.
+        pub fn backoff(mut self, initial: std::time::Duration, max: std::time::Duration) -> Self {
+            self.initial_backoff = initial;
+            self.max_backoff = max;
+            self
+        }
+
+        /// Sets whether the backoffs should be randomized (enabled by default),
+        /// in which case they're between half and all of the computed backoff.
+        pub fn jitter(mut self, jitter: bool) -> Self {
+            self.jitter = jitter;
+            self
+        }
+
+        /// Sets the timeout for each attempt of a request.
+        pub fn timeout(mut self, timeout: std::time::Duration) -> Self {
+            self.timeout = Some(timeout);
+            self
+        }
+
+        /// Sets the methods whose requests can be retried (idempotent methods by default).
+        pub fn methods(mut self, methods: Vec<http::Method>) -> Self {
+            self.methods = methods;
+            self
+        }
+
+        /// Sets the response status codes for which requests are retried.
+        pub fn statuses(mut self, statuses: Vec<http::status::StatusCode>) -> Self {
+            self.statuses = statuses;
+            self
+        }
+
+        /// Backoff before the given retry (starting from zero).
+        fn backoff_for(&self, retry: u32) -> std::time::Duration {
+            use std::hash::{BuildHasher, Hasher};
+
+            let factor = 2u32.checked_pow(retry).unwrap_or(u32::MAX);
+            let backoff = self.initial_backoff.checked_mul(factor)
+                .map(|b| std::cmp::min(b, self.max_backoff))
+                .unwrap_or(self.max_backoff);
+            if !self.jitter {
+                return backoff;
+            }
+
+            // Random keys of the hasher are good enough for jitter.
+            let random = std::collections::hash_map::RandomState::new().build_hasher().finish();
+            let half = backoff / 2;
+            half + std::time::Duration::from_nanos(random % (half.as_nanos() as u64 + 1))
+        }
+
+        /// Delay from the `Retry-After` header (in seconds or as an HTTP date).
+        fn retry_after<R: Response>(resp: &R) -> Option<std::time::Duration> {
+            let value = resp.header("retry-after")?.trim();
+            if let Ok(secs) = value.parse::<u64>() {
+                return Some(std::time::Duration::from_secs(secs));
+            }
+
+            let date = httpdate::parse_http_date(value).ok()?;
+            Some(date.duration_since(std::time::SystemTime::now()).unwrap_or_default())
+        }
+    }
+
+    /// Request from client wrappers (like `RetryClient`), which keeps
+    /// track of its method and relative path.
+    pub struct WrappedRequest<R> {
+        inner: R,
+        method: http::Method,
+        rel_path: String,
+    }
+
+    impl<R> WrappedRequest<R> {
+        fn new(inner: R, method: http::Method, rel_path: &str) -> Self {
+            WrappedRequest { inner, method, rel_path: rel_path.into() }
+        }
+
+        /// HTTP method of this request.
+        pub fn method(&self) -> &http::Method {
+            &self.method
+        }
+
+        /// Relative path of this request.
+        pub fn rel_path(&self) -> &str {
+            &self.rel_path
+        }
+
+        /// Request from the wrapped client.
+        pub fn inner(&self) -> &R {
+            &self.inner
+        }
+
+        /// Changes the request from the wrapped client.
+        pub fn map(self, f: impl FnOnce(R) -> R) -> Self {
+            WrappedRequest { inner: f(self.inner), ..self }
+        }
+    }
+
+    impl<R: Request> Request for WrappedRequest<R> {
+        type Form = R::Form;
+
+        fn header(self, name: &'static str, value: &str) -> Self {
+            self.map(|r| r.header(name, value))
+        }
+
+        fn body_bytes(self, body: Vec<u8>) -> Self {
+            self.map(|r| r.body_bytes(body))
+        }
+
+        fn json<T: serde::Serialize>(self, value: &T) -> Self {
+            self.map(|r| r.json(value))
+        }
+
+        fn multipart_form_data(self, form: Self::Form) -> Self {
+            self.map(|r| r.multipart_form_data(form))
+        }
+
+        fn query<T: serde::Serialize>(self, params: &T) -> Self {
+            self.map(|r| r.query(params))
+        }
+
+        fn basic_auth(self, username: &str, password: Option<&str>) -> Self {
+            self.map(|r| r.basic_auth(username, password))
+        }
+
+        fn bearer_auth(self, token: &str) -> Self {
+            self.map(|r| r.bearer_auth(token))
+        }
+
+        fn try_clone(&self) -> Option<Self> {
+            Some(WrappedRequest {
+                inner: self.inner.try_clone()?,
+                method: self.method.clone(),
+                rel_path: self.rel_path.clone(),
+            })
+        }
+    }
+
+    /// Client which retries failed requests (with exponential backoff) and times
+    /// them out based on the given policy. `Retry-After` headers in responses are
+    /// honored, unless they're longer than the maximum backoff (in which case the
+    /// response is returned as it is).
+    pub struct RetryClient<C> {
+        client: C,
+        policy: RetryPolicy,
+    }
+
+    impl<C> RetryClient<C> {
+        /// Wraps the given client.
+        pub fn new(client: C, policy: RetryPolicy) -> Self {
+            RetryClient { client, policy }
+        }
+
+        async fn attempt(&self, req: C::Request) -> Result<C::Response, ApiError<C::Response>>
+            where C: ApiClient + Sync,
+                  C::Response: 'static
+        {
+            let timeout = match self.policy.timeout {
+                Some(t) => t,
+                None => return self.client.make_request(req).await,
+            };
+
+            match tokio::time::timeout(timeout, self.client.make_request(req)).await {
+                Ok(result) => result,
+                Err(_) => Err(ApiError::Timeout(timeout)),
+            }
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl<C: ApiClient + Send + Sync> ApiClient for RetryClient<C> {
+        type Request = WrappedRequest<C::Request>;
+        type Response = C::Response;
+
+        fn request_builder(&self, method: http::Method, rel_path: &str) -> Self::Request {
+            let req = self.client.request_builder(method.clone(), rel_path);
+            WrappedRequest::new(req, method, rel_path)
+        }
+
+        async fn make_request(&self, req: Self::Request) -> Result<Self::Response, ApiError<Self::Response>> {
+            let can_retry = self.policy.methods.contains(&req.method);
+            let (method, rel_path) = (req.method.clone(), req.rel_path.clone());
+            let mut req = req.inner;
+            let mut retry = 0;
+            loop {
+                let next = if can_retry && retry < self.policy.max_retries {
+                    req.try_clone()
+                } else {
+                    None
+                };
+                let next = match next {
+                    Some(r) => r,
+                    None => return self.attempt(req).await,
+                };
+
+                let delay = match self.attempt(req).await {
+                    Ok(resp) if self.policy.statuses.contains(&resp.status()) => {
+                        match RetryPolicy::retry_after(&resp) {
+                            Some(d) if d > self.policy.max_backoff => return Ok(resp),
+                            Some(d) => d,
+                            None => self.policy.backoff_for(retry),
+                        }
+                    },
+                    Ok(resp) => return Ok(resp),
+                    Err(ApiError::Reqwest(_)) | Err(ApiError::Io(_)) | Err(ApiError::Timeout(_)) => {
+                        self.policy.backoff_for(retry)
+                    },
+                    Err(e) => return Err(e),
+                };
+
+                log::debug!("Retrying {} {} after {:?} (retry {})", method, rel_path, delay, retry + 1);
+                tokio::time::delay_for(delay).await;
+                req = next;
+                retry += 1;
+            }
+        }
+
+        fn auth(&self) -> Option<&Auth> {
+            self.client.auth()
+        }
+    }
+
+    /// Details of a request for `Interceptor::after_response`.
+    #[derive(Debug, Clone)]
+    pub struct RequestInfo {
+        /// HTTP method of the request.
+        pub method: http::Method,
+        /// Relative path of the request.
+        pub rel_path: String,
+        /// Time taken for getting the response (or error).
+        pub elapsed: std::time::Duration,
+    }
+
+    /// Hooks for the requests and responses of `InterceptedClient`.
+    #[async_trait::async_trait]
+    pub trait Interceptor<C: ApiClient + 'static>: Send + Sync {
+        /// Called before sending a request (say, for adding headers or signing it).
+        /// Errors are returned without sending the request.
+        async fn before_request(&self, req: WrappedRequest<C::Request>)
+            -> Result<WrappedRequest<C::Request>, ApiError<C::Response>>
+        {
+            Ok(req)
+        }
+
+        /// Called with the response (or error) for a request (say, for logging
+        /// or recording metrics).
+        async fn after_response(&self, _info: &RequestInfo, result: Result<C::Response, ApiError<C::Response>>)
+            -> Result<C::Response, ApiError<C::Response>>
+        {
+            result
+        }
+    }
+
+    /// Client which calls the given interceptors for its requests. `before_request`
+    /// hooks are called in the order in which the interceptors were added, and
+    /// `after_response` hooks are called in the reverse order.
+    pub struct InterceptedClient<C> {
+        client: C,
+        interceptors: Vec<Box<dyn Interceptor<C>>>,
+    }
+
+    impl<C: ApiClient + 'static> InterceptedClient<C> {
+        /// Wraps the given client.
+        pub fn new(client: C) -> Self {
+            InterceptedClient { client, interceptors: vec![] }
+        }
+
+        /// Adds the given interceptor.
+        pub fn interceptor<I: Interceptor<C> + 'static>(mut self, interceptor: I) -> Self {
+            self.interceptors.push(Box::new(interceptor));
+            self
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl<C: ApiClient + Send + Sync + 'static> ApiClient for InterceptedClient<C> {
+        type Request = WrappedRequest<C::Request>;
+        type Response = C::Response;
+
+        fn request_builder(&self, method: http::Method, rel_path: &str) -> Self::Request {
+            let req = self.client.request_builder(method.clone(), rel_path);
+            WrappedRequest::new(req, method, rel_path)
+        }
+
+        async fn make_request(&self, mut req: Self::Request) -> Result<Self::Response, ApiError<Self::Response>> {
+            for interceptor in &self.interceptors {
+                req = interceptor.before_request(req).await?;
+            }
+
+            let start = std::time::Instant::now();
+            let mut result = self.client.make_request(req.inner).await;
+            let info = RequestInfo {
+                method: req.method,
+                rel_path: req.rel_path,
+                elapsed: start.elapsed(),
+            };
+
+            for interceptor in self.interceptors.iter().rev() {
+                result = interceptor.after_response(&info, result).await;
+            }
+
+            result
+        }
+
+        fn auth(&self) -> Option<&Auth> {
+            self.client.auth()
+        }
+    }
+
+    /// A trait for indicating that the implementor can send an API call.
+    #[async_trait::async_trait]
+    pub trait Sendable<Client>
+    where
+        Client: ApiClient + Sync + 'static,
+        Self: Sized
+    {
+        /// The output object from this API request.
+        type Output: serde::de::DeserializeOwned;
+
+        /// HTTP method used by this call.
+        const METHOD: http::Method;
+
+        /// Security requirements for this call (any one of which should be satisfied).
+        /// If the client has credentials, then they're applied after `modify`.
+        const SECURITY: &'static [SecurityRequirement] = &[];
+
+        /// Relative URL for this API call formatted appropriately with parameter values.
+        ///
+        /// **NOTE:** This URL **must** begin with `/`.
+        fn rel_path(&self) -> std::borrow::Cow<'static, str>;
+
+        /// Modifier for this object. Builders override this method if they
+        /// wish to add query parameters, set body, etc.
+        fn modify(&self, req: Client::Request) -> Result<Client::Request, ApiError<Client::Response>> {
+            Ok(req)
+        }
+
+        /// Sends the request and returns a future for the response object.
+        async fn send(&self, client: &Client) -> Result<ResponseWrapper<Self::Output, Self>, ApiError<Client::Response>> {
+            let resp = self.send_raw(client).await?;
+            let media = resp.media_type();
+            if let Some(ty) = media {
+                if media_types::M_0.matches(&ty) {
+                    return ResponseWrapper::wrap(resp, |r| async {
+                        let bytes = r.body_bytes().await?;
+                        serde_json::from_reader(bytes.as_ref()).map_err(ApiError::from)
+                    }).await
+                }
+                else if media_types::M_1.matches(&ty) {
+                    return ResponseWrapper::wrap(resp, |r| async {
+                        let bytes = r.body_bytes().await?;
+                        serde_yaml::from_reader(bytes.as_ref()).map_err(ApiError::from)
+                    }).await
+                }
+            }
+
+            let ty = resp.header(http::header::CONTENT_TYPE.as_str())
+                .map(|v| String::from_utf8_lossy(v.as_bytes()).into_owned())
+                .unwrap_or_default();
+            Err(ApiError::UnsupportedMediaType(ty, Mutex::new(resp)))
+        }
+
+        /// Convenience method for returning a raw response after sending a request.
+        async fn send_raw(&self, client: &Client) -> Result<Client::Response, ApiError<Client::Response>> {
+            let rel_path = self.rel_path();
+            let mut req = self.modify(client.request_builder(Self::METHOD, &rel_path))?;
+            if let Some(auth) = client.auth() {
+                req = auth.apply::<_, Client::Response>(req, Self::SECURITY).await?;
+            }
+
+            let resp = client.make_request(req).await?;
+            if resp.status().is_success() {
+                Ok(resp)
+            } else {
+                Err(ApiError::Failure(rel_path.into_owned(), resp.status(), Mutex::new(resp)))
+            }
+        }
+    }
+
+    /// Wrapper containing response-related information.
+    pub struct ResponseWrapper<T, B> {
+        /// Response object
+        pub object: T,
+        /// Response headers
+        pub headers: http::HeaderMap,
+        /// Response status code
+        pub status: http::status::StatusCode,
+        _builder: core::marker::PhantomData<B>,
+    }
+
+    impl<T, B> ResponseWrapper<T, B> {
+        pub(crate) async fn wrap<F, R>(mut resp: R, f: impl FnOnce(R) -> F) -> Result<Self, ApiError<R>>
+            where F: std::future::Future<Output=Result<T, ApiError<R>>>,
+                  R: Response + 'static
+        {
+            let status = resp.status();
+            let headers = resp.take_headers();
+            Ok(ResponseWrapper {
+                object: f(resp).await?,
+                headers,
+                status,
+                _builder: core::marker::PhantomData,
+            })
+        }
+    }
+
+    impl<'de, T, B> serde::de::Deserialize<'de> for ResponseWrapper<T, B> {
+        fn deserialize<D>(_: D) -> Result<Self, D::Error>
+        where
+            D: serde::de::Deserializer<'de>
+        {
+            unimplemented!("ResponseWrapper is not supposed to be deserialized.");
+        }
+    }
+
+    impl<T, B> std::ops::Deref for ResponseWrapper<T, B> {
+        type Target = T;
+
+        fn deref(&self) -> &Self::Target {
+            &self.object
+        }
+    }
+
+    impl<T, B> std::ops::DerefMut for ResponseWrapper<T, B> {
+        fn deref_mut(&mut self) -> &mut <Self as std::ops::Deref>::Target {
+            &mut self.object
+        }
+    }
+
+    pub mod media_types {
+        use lazy_static::lazy_static;
+
+        lazy_static! {
+            pub static ref M_0: mime::MediaRange =
+                mime::MediaRange::parse("application/json").expect("cannot parse \"application/json\" as media range");
+            pub static ref M_1: mime::MediaRange =
+                mime::MediaRange::parse("application/yaml").expect("cannot parse \"application/yaml\" as media range");
+        }
+    }
+
+    impl<R: Response + 'static> From<std::io::Error> for ApiError<R> {
+        fn from(e: std::io::Error) -> Self {
+            ApiError::Io(e)
+        }
+    }
+
+    impl<R: Response + 'static> From<serde_json::Error> for ApiError<R> {
+        fn from(e: serde_json::Error) -> Self {
+            ApiError::ApplicationJson(e)
+        }
+    }
+
+    impl<R: Response + 'static> From<serde_yaml::Error> for ApiError<R> {
+        fn from(e: serde_yaml::Error) -> Self {
+            ApiError::ApplicationYaml(e)
+        }
+    }
+}
+
+pub mod generics {
+    include!("./generics.rs");
+}
+
+pub mod util {
+    include!("./util.rs");
+}
@@ -0,0 +1,244 @@
+use paperclip::bundle;
+use paperclip::remote::SpecLoader;
+use paperclip::v2::models::SpecFormat;
+use paperclip::v3::{DefaultSchema, ResolvableApi};
+use paperclip::PaperClipError;
+
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpListener;
+use std::path::PathBuf;
+use std::sync::mpsc;
+use std::thread;
+
+const SPEC_JSON: &str =
+    r#"{"openapi": "3.0.3", "info": {"title": "Pets", "version": "1.0"}, "paths": {}}"#;
+const SPEC_YAML: &str = "openapi: 3.0.3\ninfo:\n  title: Pets\n  version: '1.0'\npaths: {}\n";
+
+/// Canned response from the stub server.
+struct Reply {
+    status: &'static str,
+    headers: Vec<(&'static str, &'static str)>,
+    body: String,
+}
+
+impl Reply {
+    fn ok<S: Into<String>>(headers: Vec<(&'static str, &'static str)>, body: S) -> Self {
+        Reply {
+            status: "200 OK",
+            headers,
+            body: body.into(),
+        }
+    }
+}
+
+/// Starts a server which serves the given replies (one per request) and
+/// returns its address, along with a receiver for the request heads.
+fn serve(replies: Vec<Reply>) -> (String, mpsc::Receiver<Vec<String>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").expect("binding listener");
+    let addr = format!("http://{}", listener.local_addr().unwrap());
+    let (tx, rx) = mpsc::channel();
+
+    thread::spawn(move || {
+        for reply in replies {
+            let (mut stream, _) = listener.accept().expect("accepting connection");
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut head = vec![];
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                let line = line.trim_end().to_owned();
+                if line.is_empty() {
+                    break;
+                }
+
+                head.push(line.to_ascii_lowercase());
+            }
+
+            let _ = tx.send(head);
+            let mut resp = format!(
+                "HTTP/1.1 {}\r\ncontent-length: {}\r\nconnection: close\r\n",
+                reply.status,
+                reply.body.len()
+            );
+            for (name, value) in reply.headers {
+                resp.push_str(&format!("{}: {}\r\n", name, value));
+            }
+
+            resp.push_str("\r\n");
+            resp.push_str(&reply.body);
+            stream.write_all(resp.as_bytes()).unwrap();
+        }
+
+        // Stop listening before closing the channel.
+        drop(listener);
+        drop(tx);
+    });
+
+    (addr, rx)
+}
+
+fn cache_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("paperclip-test-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    dir
+}
+
+#[test]
+fn test_format_from_content_type() {
+    let (addr, _) = serve(vec![
+        Reply::ok(vec![("content-type", "application/json")], SPEC_JSON),
+        Reply::ok(
+            vec![("content-type", "application/x-yaml; charset=utf-8")],
+            SPEC_YAML,
+        ),
+    ]);
+
+    let loader = SpecLoader::default();
+    let spec = loader
+        .fetch(&format!("{}/spec", addr))
+        .expect("fetching spec");
+    assert_eq!(spec.format, SpecFormat::Json);
+    assert!(!spec.from_cache);
+    let api: ResolvableApi<DefaultSchema> = spec.deserialize().expect("parsing spec");
+    assert_eq!(api.info.title, "Pets");
+
+    // Content type takes precedence over the extension.
+    let spec = loader
+        .fetch(&format!("{}/spec.json", addr))
+        .expect("fetching spec");
+    assert_eq!(spec.format, SpecFormat::Yaml);
+    let api: ResolvableApi<DefaultSchema> = spec.deserialize().expect("parsing spec");
+    assert_eq!(api.info.version, "1.0");
+}
+
+#[test]
+fn test_format_from_extension_and_contents() {
+    let (addr, _) = serve(vec![
+        Reply::ok(vec![("content-type", "text/plain")], SPEC_JSON),
+        Reply::ok(vec![], SPEC_YAML),
+        Reply::ok(vec![], "\n  { \"openapi\": \"3.0.3\" }"),
+    ]);
+
+    let loader = SpecLoader::default();
+    let spec = loader
+        .fetch(&format!("{}/api/spec.JSON?v=1", addr))
+        .expect("fetching spec");
+    assert_eq!(spec.format, SpecFormat::Json);
+    let spec = loader
+        .fetch(&format!("{}/api/spec.yml", addr))
+        .expect("fetching spec");
+    assert_eq!(spec.format, SpecFormat::Yaml);
+    let spec = loader
+        .fetch(&format!("{}/api/spec", addr))
+        .expect("fetching spec");
+    assert_eq!(spec.format, SpecFormat::Json);
+}
+
+#[test]
+fn test_auth_header_and_errors() {
+    let (addr, heads) = serve(vec![Reply {
+        status: "401 Unauthorized",
+        headers: vec![],
+        body: String::new(),
+    }]);
+
+    let loader = SpecLoader::default().auth_header("Authorization", "Bearer foobar");
+    match loader.fetch(&format!("{}/spec.yaml", addr)) {
+        Err(PaperClipError::RemoteSpec(url, 401)) => assert!(url.ends_with("/spec.yaml")),
+        r => panic!("unexpected result: {:?}", r),
+    }
+
+    let head = heads.recv().unwrap();
+    assert_eq!(head[0], "get /spec.yaml http/1.1");
+    assert!(head.iter().any(|h| h == "authorization: bearer foobar"));
+}
+
+#[test]
+fn test_cache() {
+    let (addr, heads) = serve(vec![
+        Reply::ok(vec![("etag", "\"v1\"")], SPEC_YAML),
+        Reply {
+            status: "304 Not Modified",
+            headers: vec![],
+            body: String::new(),
+        },
+    ]);
+
+    let dir = cache_dir("cache");
+    let loader = SpecLoader::default().cache_dir(&dir);
+    let url = format!("{}/spec", addr);
+    let spec = loader.fetch(&url).expect("fetching spec");
+    assert!(!spec.from_cache);
+    assert_eq!(spec.format, SpecFormat::Yaml);
+
+    // Revalidated using the etag.
+    let spec = loader.fetch(&url).expect("fetching spec");
+    assert!(spec.from_cache);
+    assert_eq!(spec.body, SPEC_YAML.as_bytes());
+    let all_heads = heads.iter().collect::<Vec<_>>();
+    assert!(all_heads[1].iter().any(|h| h == "if-none-match: \"v1\""));
+
+    // Server has stopped (it only serves two requests).
+    let spec = loader.fetch(&url).expect("fetching spec");
+    assert!(spec.from_cache);
+    let api: ResolvableApi<DefaultSchema> = spec.deserialize().expect("parsing spec");
+    assert_eq!(api.info.title, "Pets");
+
+    // Nothing cached for other URLs.
+    assert!(loader.fetch(&format!("{}/other", addr)).is_err());
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_auth_header_for_other_origins() {
+    let (other, other_heads) = serve(vec![Reply::ok(
+        vec![("content-type", "application/json")],
+        r#"{"Pet": {"type": "object"}}"#,
+    )]);
+    let (addr, heads) = serve(vec![
+        Reply::ok(
+            vec![("content-type", "application/json")],
+            format!(
+                r##"{{
+                    "openapi": "3.0.3",
+                    "info": {{"title": "Pets", "version": "1.0"}},
+                    "paths": {{}},
+                    "components": {{
+                        "schemas": {{
+                            "Pet": {{"$ref": "{}/models.json#/Pet"}},
+                            "Name": {{"$ref": "common.json#/Name"}}
+                        }}
+                    }}
+                }}"##,
+                other
+            ),
+        ),
+        Reply::ok(
+            vec![("content-type", "application/json")],
+            r#"{"Name": {"type": "string"}}"#,
+        ),
+    ]);
+
+    let url = bundle::location_url(format!("{}/api/spec.json", addr)).unwrap();
+    let loader = SpecLoader::default()
+        .auth_header("Authorization", "Bearer foobar")
+        .auth_origin(&url);
+    let (spec, _) = bundle::load(&url, &loader).expect("loading spec");
+    assert_eq!(
+        spec.pointer("/components/schemas/Pet/type"),
+        Some(&"object".into())
+    );
+
+    // Documents from the same origin get the header.
+    let heads = heads.iter().collect::<Vec<_>>();
+    assert_eq!(heads[0][0], "get /api/spec.json http/1.1");
+    assert_eq!(heads[1][0], "get /api/common.json http/1.1");
+    for head in &heads {
+        assert!(head.iter().any(|h| h == "authorization: bearer foobar"));
+    }
+
+    // ... but not the other server.
+    let head = other_heads.recv().unwrap();
+    assert_eq!(head[0], "get /models.json http/1.1");
+    assert!(!head.iter().any(|h| h.starts_with("authorization:")));
+}
@@ -0,0 +1,104 @@
+use paperclip::v2::{
+    self,
+    models::{DefaultSchema, DefaultSchemaRaw, Discriminator, ResolvableApi},
+    Schema,
+};
+
+const SCHEMA: &str = "
+type: object
+required: [name]
+discriminator: kind
+externalDocs:
+  url: https://example.com/pets
+xml:
+  name: pet
+  namespace: https://example.com/schema
+  prefix: p
+properties:
+  kind: {type: string}
+  name:
+    type: string
+    default: Doggo
+    minLength: 1
+    maxLength: 64
+    pattern: '^[A-Za-z ]+$'
+  age:
+    type: number
+    minimum: 0.0
+    maximum: 30.5
+    exclusiveMaximum: true
+    multipleOf: 0.5
+  id:
+    type: integer
+    readOnly: true
+  tags:
+    type: array
+    items: {type: string}
+    minItems: 1
+    maxItems: 5
+    uniqueItems: true
+    xml: {wrapped: true}
+";
+
+#[test]
+fn test_schema_keywords_roundtrip() {
+    let schema: DefaultSchemaRaw = serde_yaml::from_str(SCHEMA).expect("deserializing schema");
+    let value = serde_json::to_value(&schema).expect("serializing schema");
+    let expected: serde_json::Value = serde_yaml::from_str(SCHEMA).expect("deserializing value");
+    assert_eq!(value, expected);
+
+    let again: DefaultSchemaRaw = serde_json::from_value(value).expect("deserializing value");
+    assert_eq!(
+        serde_json::to_value(&again).expect("serializing schema"),
+        expected
+    );
+}
+
+#[test]
+fn test_schema_keywords_accessors() {
+    let api: ResolvableApi<DefaultSchema> = v2::from_str(&format!(
+        "swagger: '2.0'
+info: {{title: Pets, version: '1.0'}}
+paths: {{}}
+definitions:
+  Pet:{}",
+        SCHEMA.replace('\n', "\n    ")
+    ))
+    .expect("deserializing spec");
+    let api = api.resolve().expect("resolution");
+
+    let pet = api.definitions["Pet"].read();
+    assert_eq!(
+        pet.discriminator(),
+        Some(&Discriminator::Property("kind".into()))
+    );
+    assert_eq!(
+        pet.external_docs().map(|d| d.url.as_str()),
+        Some("https://example.com/pets")
+    );
+    let xml = pet.xml().expect("xml");
+    assert_eq!(xml.name.as_deref(), Some("pet"));
+    assert_eq!(xml.prefix.as_deref(), Some("p"));
+    assert!(!xml.wrapped);
+
+    let props = pet.properties().expect("properties");
+    let name = props["name"].read();
+    assert_eq!(name.default_value(), Some(&serde_json::json!("Doggo")));
+    assert_eq!(name.min_length(), Some(1));
+    assert_eq!(name.max_length(), Some(64));
+    assert_eq!(name.pattern(), Some("^[A-Za-z ]+$"));
+
+    let age = props["age"].read();
+    assert_eq!(age.minimum(), Some((0.0, false)));
+    assert_eq!(age.maximum(), Some((30.5, true)));
+    assert_eq!(age.multiple_of(), Some(0.5));
+    assert!(!age.read_only());
+
+    assert!(props["id"].read().read_only());
+
+    let tags = props["tags"].read();
+    assert_eq!(tags.min_items(), Some(1));
+    assert_eq!(tags.max_items(), Some(5));
+    assert!(tags.unique_items());
+    assert!(tags.xml().expect("xml").wrapped);
+}
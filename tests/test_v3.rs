@@ -0,0 +1,664 @@
+use paperclip::v2::models::{
+    CollectionFormat, DataType, HttpMethod, OperationProtocol, ParameterIn as V2ParameterIn,
+};
+use paperclip::v3::{
+    self,
+    models::{DefaultSchema, ParameterIn, ResolvableApi, Version},
+    Schema,
+};
+
+use once_cell::sync::Lazy;
+use serde_json::json;
+use std::fs::File;
+use std::io::Cursor;
+use std::path::PathBuf;
+
+static ROOT: Lazy<PathBuf> = Lazy::new(|| PathBuf::from(env!("CARGO_MANIFEST_DIR")));
+static PET_SCHEMA: Lazy<ResolvableApi<DefaultSchema>> = Lazy::new(|| {
+    let fd = File::open(ROOT.join("tests/pet-v3.yaml")).expect("file?");
+    let raw: ResolvableApi<DefaultSchema> = v3::from_reader(fd).expect("deserializing spec");
+    raw.resolve().expect("resolution")
+});
+
+#[test]
+fn test_components_and_servers() {
+    assert_eq!(PET_SCHEMA.openapi, Version::V3_0_3);
+    assert_eq!(
+        PET_SCHEMA.servers[0].default_url(),
+        "https://pets.com:8888/api"
+    );
+
+    let pet = PET_SCHEMA.components.schemas["Pet"].read();
+    assert_eq!(pet.name.as_deref(), Some("Pet"));
+    // `friend` refers to `Pet` itself.
+    assert!(pet.is_cyclic());
+    let scheme = &PET_SCHEMA.components.security_schemes["PetAuth"];
+    let flow = scheme.flows.as_ref().unwrap().password.as_ref().unwrap();
+    assert_eq!(flow.token_url.as_deref(), Some("https://pets.com/token"));
+}
+
+#[test]
+fn test_resolved_references() {
+    let list = &PET_SCHEMA.paths["/pets"].methods[&HttpMethod::Get];
+    let param = list.parameters[0].read();
+    assert_eq!(param.name, "limit");
+    assert_eq!(param.in_, ParameterIn::Query);
+
+    let resp = list.responses["default"].read();
+    let schema = resp.content[&v3::models::JSON_MIME]
+        .schema
+        .as_ref()
+        .unwrap();
+    assert!(schema.read().reference.is_none()); // this was a reference
+    assert_eq!(schema.read().name.as_deref(), Some("Error"));
+    let header = list.responses["200"].read();
+    assert_eq!(
+        header.headers["x-next"].description.as_deref(),
+        Some("A link to the next page of responses")
+    );
+
+    let create = &PET_SCHEMA.paths["/pets"].methods[&HttpMethod::Post];
+    let body = create.request_body.as_ref().unwrap().read();
+    assert!(body.required);
+    let schema = body.content[&v3::models::JSON_MIME]
+        .schema
+        .as_ref()
+        .unwrap();
+    assert_eq!(schema.read().name.as_deref(), Some("Pet"));
+    assert_eq!(
+        create.security.as_ref().unwrap()[0]["PetAuth"],
+        ["pets.write"]
+    );
+
+    let callback = &create.callbacks["adopted"]["{$request.body#/callbackUrl}"];
+    assert!(callback.methods[&HttpMethod::Post].request_body.is_some());
+
+    let show = &PET_SCHEMA.paths["/pets/{petId}"];
+    assert_eq!(show.parameters[0].read().name, "petId");
+    let resp = show.methods[&HttpMethod::Get].responses["200"].read();
+    assert_eq!(
+        resp.links["friend"].operation_id.as_deref(),
+        Some("showPetById")
+    );
+    assert!(show.methods[&HttpMethod::Get]
+        .security
+        .as_ref()
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn test_anonymous_schemas_added_to_components() {
+    let body = PET_SCHEMA.components.schemas["PostPetsPetIdPhotosBody"].read();
+    assert!(body.properties.contains_key("photo"));
+    let resp = PET_SCHEMA.components.schemas["PostPetsPetIdPhotosResponse"].read();
+    assert!(resp.properties.contains_key("url"));
+}
+
+#[test]
+fn test_missing_reference() {
+    let spec = Cursor::new(
+        b"
+openapi: \"3.0.0\"
+info:
+  title:  \"Petstore\"
+  version: \"1.0.0\"
+paths:
+  /pets:
+    get:
+      responses:
+        \"200\":
+          description: OK
+          content:
+            application/json:
+              schema:
+                $ref: \"#/components/schemas/Pet\"
+" as &[_],
+    );
+
+    let raw: ResolvableApi<DefaultSchema> = v3::from_reader(spec).expect("deserializing spec");
+    let err = raw.resolve().unwrap_err().to_string();
+    assert_eq!(
+        err,
+        "Reference missing in spec: Pet \
+         (at /paths/~1pets/get/responses/200/content/application~1json/schema)"
+    );
+}
+
+#[test]
+fn test_invalid_reference() {
+    let spec = Cursor::new(
+        b"
+openapi: \"3.0.0\"
+info:
+  title:  \"Petstore\"
+  version: \"1.0.0\"
+paths:
+  /pets:
+    get:
+      parameters:
+      - $ref: \"#/definitions/Limit\"
+      responses:
+        \"204\":
+          description: OK
+" as &[_],
+    );
+
+    let raw: ResolvableApi<DefaultSchema> = v3::from_reader(spec).expect("deserializing spec");
+    let err = raw.resolve().unwrap_err().to_string();
+    assert_eq!(
+        err,
+        "Invalid $ref URI \"#/definitions/Limit\". Only references to objects in the spec \
+         are supported. (at /paths/~1pets/get/parameters/0)"
+    );
+}
+
+#[test]
+fn test_lowering_to_v2() {
+    let api = v3::codegen::lower(&PET_SCHEMA).expect("lowering");
+    assert_eq!(api.host.as_deref(), Some("pets.com:8888"));
+    assert_eq!(api.base_path.as_deref(), Some("/api"));
+    assert_eq!(
+        api.schemes.iter().collect::<Vec<_>>(),
+        [&OperationProtocol::Https]
+    );
+    assert!(api.definitions.contains_key("Pet"));
+    assert!(api.security_definitions.contains_key("PetAuth"));
+
+    let create = &api.paths["/pets"].methods[&HttpMethod::Post];
+    let body = create.parameters[0].read();
+    assert_eq!(body.in_, V2ParameterIn::Body);
+    assert!(body.required);
+    assert_eq!(
+        body.schema.as_ref().unwrap().read().name.as_deref(),
+        Some("Pet")
+    );
+
+    let upload = &api.paths["/pets/{petId}/photos"].methods[&HttpMethod::Post];
+    let photo = upload
+        .parameters
+        .iter()
+        .find(|p| p.read().name == "photo")
+        .unwrap()
+        .read();
+    assert_eq!(photo.in_, V2ParameterIn::FormData);
+    assert_eq!(photo.data_type, Some(DataType::File));
+    assert!(photo.required);
+    let path_params = &api.paths["/pets/{petId}/photos"].parameters;
+    assert_eq!(path_params[0].read().in_, V2ParameterIn::Path);
+}
+
+#[test]
+fn test_lowering_parameter_styles() {
+    let spec = Cursor::new(
+        b"
+openapi: \"3.0.0\"
+info:
+  title:  \"Petstore\"
+  version: \"1.0.0\"
+paths:
+  /pets:
+    get:
+      parameters:
+      - name: tags
+        in: query
+        schema:
+          type: array
+          items:
+            type: string
+      - name: ids
+        in: query
+        style: pipeDelimited
+        schema:
+          type: array
+          items:
+            type: integer
+      - name: session
+        in: cookie
+        schema:
+          type: string
+      responses:
+        \"204\":
+          description: OK
+" as &[_],
+    );
+
+    let raw: ResolvableApi<DefaultSchema> = v3::from_reader(spec).expect("deserializing spec");
+    let api = v3::codegen::lower(&raw.resolve().unwrap()).expect("lowering");
+    let params = &api.paths["/pets"].methods[&HttpMethod::Get].parameters;
+    // cookie parameters are dropped.
+    assert_eq!(params.len(), 2);
+    assert_eq!(
+        params[0].read().collection_format,
+        Some(CollectionFormat::Multi)
+    );
+    assert_eq!(
+        params[1].read().collection_format,
+        Some(CollectionFormat::Pipes)
+    );
+}
+
+#[test]
+fn test_lowering_extensions() {
+    let spec = Cursor::new(
+        b"
+openapi: \"3.0.0\"
+info:
+  title:  \"Petstore\"
+  version: \"1.0.0\"
+  x-audience: public
+paths:
+  /pets:
+    x-controller: pets
+    get:
+      x-rate-limit: 10
+      parameters:
+      - name: limit
+        in: query
+        x-example: 5
+        schema:
+          type: integer
+      responses:
+        \"204\":
+          description: OK
+          x-cache: false
+components:
+  securitySchemes:
+    ApiKey:
+      type: apiKey
+      name: X-API-Key
+      in: header
+      x-scope: pets
+" as &[_],
+    );
+
+    let raw: ResolvableApi<DefaultSchema> = v3::from_reader(spec).expect("deserializing spec");
+    assert_eq!(raw.paths["/pets"].extensions["x-controller"], "pets");
+    assert_eq!(raw.paths["/pets"].methods.len(), 1);
+
+    let api = v3::codegen::lower(&raw.resolve().unwrap()).expect("lowering");
+    assert_eq!(api.info.extensions["x-audience"], "public");
+    assert_eq!(
+        api.security_definitions["ApiKey"].extensions["x-scope"],
+        "pets"
+    );
+    let item = &api.paths["/pets"];
+    assert_eq!(item.extensions["x-controller"], "pets");
+    let op = &item.methods[&HttpMethod::Get];
+    assert_eq!(op.extensions["x-rate-limit"], 10);
+    assert_eq!(op.parameters[0].read().extensions["x-example"], 5);
+    assert_eq!(op.responses["204"].read().extensions["x-cache"], false);
+}
+
+#[test]
+fn test_conversion_from_v2() {
+    let fd = File::open(ROOT.join("tests/pet-v2.yaml")).expect("file?");
+    let (spec, fmt) = v3::convert::from_v2_reader(fd).expect("converting spec");
+    assert_eq!(fmt, v3::models::SpecFormat::Yaml);
+    assert_eq!(spec["openapi"], "3.0.3");
+    assert_eq!(spec["servers"], json!([{"url": "//pets.com:8888/api"}]));
+
+    // body parameter (form parameters are ignored when there's a body).
+    let add_pet = &spec["paths"]["/pets"]["post"];
+    assert_eq!(
+        add_pet["requestBody"],
+        json!({
+            "content": {
+                "application/json": {
+                    "schema": {"$ref": "#/components/schemas/Pet"}
+                }
+            },
+            "required": true
+        })
+    );
+    assert_eq!(add_pet["parameters"].as_array().unwrap().len(), 2);
+    assert_eq!(
+        add_pet["responses"]["200"]["headers"]["X-Rate-Limit"]["schema"],
+        json!({"type": "integer"})
+    );
+
+    // form parameters with files.
+    let upload = &spec["paths"]["/test/file"]["put"];
+    let schema = &upload["requestBody"]["content"]["multipart/form-data"]["schema"];
+    assert_eq!(
+        schema["properties"]["someDataFile"],
+        json!({"type": "string", "format": "binary"})
+    );
+    assert_eq!(schema["required"], json!(["foobar", "someDataFile"]));
+    assert_eq!(
+        upload["responses"]["200"]["$ref"],
+        "#/components/responses/StatusResponse"
+    );
+    assert_eq!(
+        spec["paths"]["/test/file"]["get"]["responses"]["200"]["content"]["application/json"]
+            ["schema"],
+        json!({"type": "string", "format": "binary"})
+    );
+
+    // The converted spec should be a valid v3 spec.
+    let api: ResolvableApi<DefaultSchema> = serde_json::from_value(spec).expect("v3 spec");
+    api.resolve().expect("resolution");
+}
+
+#[test]
+fn test_conversion_preserves_extensions() {
+    let spec = json!({
+        "swagger": "2.0",
+        "info": {
+            "title": "Petstore",
+            "version": "1.0.0",
+            "x-logo": "pet.png",
+            "contact": {"name": "Pets", "x-team": "pets"},
+            "license": {"name": "MIT", "x-spdx": "MIT"}
+        },
+        "x-internal": true,
+        "externalDocs": {"url": "https://pets.com/docs", "x-lang": "en"},
+        "schemes": ["http", "https"],
+        "host": "pets.com",
+        "securityDefinitions": {
+            "PetAuth": {
+                "type": "oauth2",
+                "flow": "application",
+                "tokenUrl": "https://pets.com/token",
+                "x-audience": "pets"
+            }
+        },
+        "definitions": {
+            "Pet": {
+                "type": "object",
+                "x-table": "pets",
+                "properties": {
+                    "name": {"type": "string", "x-column": "pet_name"}
+                }
+            }
+        },
+        "paths": {
+            "/pets": {
+                "x-controller": "pets",
+                "post": {
+                    "x-rate-limit": 10,
+                    "consumes": ["application/json", "application/xml"],
+                    "parameters": [{
+                        "in": "body",
+                        "name": "body",
+                        "x-body-name": "pet",
+                        "schema": {"$ref": "#/definitions/Pet"}
+                    }, {
+                        "in": "query",
+                        "name": "tags",
+                        "type": "array",
+                        "collectionFormat": "multi",
+                        "items": {"type": "string"},
+                        "x-example": "cat"
+                    }],
+                    "responses": {
+                        "204": {
+                            "description": "Created",
+                            "x-cache": false,
+                            "headers": {
+                                "X-Rate-Limit": {"type": "integer", "x-window": "1m"}
+                            }
+                        }
+                    }
+                },
+                "put": {
+                    "externalDocs": {"url": "https://pets.com/docs/put"},
+                    "consumes": ["application/x-www-form-urlencoded"],
+                    "parameters": [{
+                        "in": "formData",
+                        "name": "name",
+                        "type": "string",
+                        "x-form-field": "pet_name"
+                    }],
+                    "responses": {"204": {"description": "Updated"}}
+                }
+            }
+        }
+    });
+
+    let spec = v3::convert::from_v2_value(spec).expect("converting spec");
+    assert_eq!(spec["x-internal"], true);
+    assert_eq!(spec["info"]["x-logo"], "pet.png");
+    assert_eq!(spec["info"]["contact"]["x-team"], "pets");
+    assert_eq!(spec["info"]["license"]["x-spdx"], "MIT");
+    assert_eq!(spec["externalDocs"]["x-lang"], "en");
+    assert_eq!(
+        spec["servers"],
+        json!([{"url": "http://pets.com"}, {"url": "https://pets.com"}])
+    );
+
+    let scheme = &spec["components"]["securitySchemes"]["PetAuth"];
+    assert_eq!(scheme["x-audience"], "pets");
+    assert_eq!(
+        scheme["flows"]["clientCredentials"]["tokenUrl"],
+        "https://pets.com/token"
+    );
+    let pet = &spec["components"]["schemas"]["Pet"];
+    assert_eq!(pet["x-table"], "pets");
+    assert_eq!(pet["properties"]["name"]["x-column"], "pet_name");
+
+    let item = &spec["paths"]["/pets"];
+    assert_eq!(item["x-controller"], "pets");
+    let op = &item["post"];
+    assert_eq!(op["x-rate-limit"], 10);
+    assert_eq!(op["requestBody"]["x-body-name"], "pet");
+    let content = op["requestBody"]["content"].as_object().unwrap();
+    assert_eq!(
+        content.keys().collect::<Vec<_>>(),
+        ["application/json", "application/xml"]
+    );
+    assert_eq!(
+        op["parameters"][0],
+        json!({
+            "in": "query",
+            "name": "tags",
+            "style": "form",
+            "explode": true,
+            "schema": {"type": "array", "items": {"type": "string"}},
+            "x-example": "cat"
+        })
+    );
+    assert_eq!(op["responses"]["204"]["x-cache"], false);
+    assert_eq!(
+        op["responses"]["204"]["headers"]["X-Rate-Limit"]["x-window"],
+        "1m"
+    );
+
+    let op = &item["put"];
+    assert_eq!(op["externalDocs"]["url"], "https://pets.com/docs/put");
+    let form = &op["requestBody"]["content"]["application/x-www-form-urlencoded"];
+    assert_eq!(
+        form["schema"]["properties"]["name"]["x-form-field"],
+        "pet_name"
+    );
+}
+
+#[test]
+fn test_conversion_preserves_constraints() {
+    let limit = json!({
+        "type": "integer",
+        "default": 10,
+        "maximum": 100.0,
+        "exclusiveMaximum": true,
+        "minimum": 1.0,
+        "exclusiveMinimum": false,
+        "multipleOf": 0.5
+    });
+    let name = json!({
+        "type": "string",
+        "default": "cat",
+        "maxLength": 64,
+        "minLength": 1,
+        "pattern": "^[a-z]+$"
+    });
+    let tags = json!({
+        "type": "array",
+        "maxItems": 5,
+        "minItems": 1,
+        "uniqueItems": true,
+        "items": {"type": "string", "maxLength": 10, "enum": ["cat", "dog"]}
+    });
+
+    let param = |in_: &str, name: &str, schema: &serde_json::Value| {
+        let mut param = schema.clone();
+        param["in"] = in_.into();
+        param["name"] = name.into();
+        param
+    };
+
+    let mut header = limit.clone();
+    header["example"] = 5.into();
+    let spec = json!({
+        "swagger": "2.0",
+        "info": {"title": "Petstore", "version": "1.0.0"},
+        "paths": {
+            "/pets": {
+                "get": {
+                    "parameters": [
+                        param("query", "limit", &limit),
+                        param("query", "name", &name),
+                        param("query", "tags", &tags),
+                    ],
+                    "responses": {
+                        "200": {
+                            "description": "Pets",
+                            "headers": {"X-Rate-Limit": header}
+                        }
+                    }
+                },
+                "post": {
+                    "consumes": ["application/x-www-form-urlencoded"],
+                    "parameters": [
+                        param("formData", "limit", &limit),
+                        param("formData", "name", &name),
+                        param("formData", "tags", &tags),
+                    ],
+                    "responses": {"204": {"description": "Created"}}
+                }
+            }
+        }
+    });
+
+    let spec = v3::convert::from_v2_value(spec).expect("converting spec");
+    let op = &spec["paths"]["/pets"]["get"];
+    for (i, schema) in [&limit, &name, &tags].iter().enumerate() {
+        assert_eq!(op["parameters"][i]["schema"], **schema);
+    }
+
+    let header = &op["responses"]["200"]["headers"]["X-Rate-Limit"];
+    assert_eq!(header["schema"], limit);
+    assert_eq!(header["example"], 5);
+
+    let form = &spec["paths"]["/pets"]["post"]["requestBody"]["content"]
+        ["application/x-www-form-urlencoded"]["schema"]["properties"];
+    assert_eq!(form["limit"], limit);
+    assert_eq!(form["name"], name);
+    assert_eq!(form["tags"], tags);
+}
+
+#[test]
+fn test_conversion_preserves_number_precision() {
+    let limit = json!({
+        "type": "number",
+        "maximum": 2147483647.0,
+        "minimum": 100.1,
+        "multipleOf": 0.01
+    });
+    let spec = json!({
+        "swagger": "2.0",
+        "info": {"title": "Petstore", "version": "1.0.0"},
+        "paths": {
+            "/pets": {
+                "get": {
+                    "parameters": [{"in": "query", "name": "limit", "type": "number",
+                                    "maximum": 2147483647.0, "minimum": 100.1,
+                                    "multipleOf": 0.01}],
+                    "responses": {"204": {"description": "No pets"}}
+                }
+            }
+        },
+        "definitions": {"Limit": limit}
+    });
+
+    let spec = v3::convert::from_v2_value(spec).expect("converting spec");
+    assert_eq!(spec["components"]["schemas"]["Limit"], limit);
+    assert_eq!(
+        spec["paths"]["/pets"]["get"]["parameters"][0]["schema"],
+        limit
+    );
+}
+
+#[test]
+fn test_conversion_of_discriminators() {
+    let spec = json!({
+        "swagger": "2.0",
+        "info": {"title": "Petstore", "version": "1.0.0"},
+        "paths": {},
+        "definitions": {
+            "Pet": {
+                "type": "object",
+                "discriminator": "petType",
+                "required": ["petType"],
+                "properties": {
+                    "petType": {"type": "string"}
+                }
+            },
+            "Cat": {
+                "allOf": [
+                    {"$ref": "#/definitions/Pet"},
+                    {"properties": {"huntingSkill": {"type": "string"}}}
+                ]
+            }
+        }
+    });
+
+    let spec = v3::convert::from_v2_value(spec).expect("converting spec");
+    let schemas = &spec["components"]["schemas"];
+    assert_eq!(
+        schemas["Pet"]["discriminator"],
+        json!({"propertyName": "petType"})
+    );
+    assert_eq!(
+        schemas["Cat"]["allOf"][0],
+        json!({"$ref": "#/components/schemas/Pet"})
+    );
+
+    let api: ResolvableApi<DefaultSchema> = serde_json::from_value(spec).expect("v3 spec");
+    let api = api.resolve().expect("resolution");
+    let pet = api.components.schemas["Pet"].read();
+    assert_eq!(pet.discriminator().unwrap().property_name(), "petType");
+}
+
+#[test]
+fn test_trace_operations() {
+    let spec = "
+openapi: 3.0.0
+info: {title: Pets, version: '1.0'}
+paths:
+  /pets:
+    get:
+      responses:
+        '204': {description: OK}
+    trace:
+      responses:
+        '204': {description: OK}
+";
+    let raw: ResolvableApi<DefaultSchema> = v3::from_str(spec).expect("deserializing spec");
+    let api = raw.resolve().expect("resolution");
+    assert!(api.paths["/pets"].methods.contains_key(&HttpMethod::Trace));
+
+    // `trace` is dropped while lowering to v2, and it's not allowed in v2 specs.
+    let api = v3::codegen::lower(&api).expect("lowering");
+    let methods = api.paths["/pets"].methods.keys().collect::<Vec<_>>();
+    assert_eq!(methods, vec![&HttpMethod::Get]);
+
+    let v2_spec = spec.replace("openapi: 3.0.0", "swagger: '2.0'");
+    let err = paperclip::v2::from_str::<paperclip::v2::DefaultSchema>(&v2_spec).unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("`trace` operations are only allowed in OpenAPI v3"));
+
+    let mut item = paperclip::v2::models::DefaultPathItemRaw::default();
+    item.methods.insert(HttpMethod::Trace, Default::default());
+    assert_eq!(serde_json::to_value(&item).unwrap(), json!({}));
+}
@@ -0,0 +1,385 @@
+use paperclip::diagnostics::{Diagnostic, Severity, SourceMap};
+use paperclip::v2::{
+    self,
+    models::{DefaultSchema, ResolvableApi},
+};
+
+const SPEC: &str = "swagger: '2.0'
+info: {title: Pets, version: '1.0'}
+paths:
+  /pets/{id}:
+    get:
+      parameters:
+        - {name: id, in: path, type: string, required: true}
+        - {name: filter, in: query, type: object}
+        - $ref: '#/parameters/Limit'
+      responses:
+        '200':
+          schema:
+            $ref: '#/definitions/Pet'
+        '600':
+          description: Unknown
+        '404':
+          $ref: '#/responses/NotFound'
+  /pets/{name}:
+    get:
+      responses:
+        default:
+          description: Error
+  /stores: {}
+definitions:
+  Pet:
+    properties:
+      owner:
+        $ref: '#/definitions/Owner'
+      tags:
+        type: array
+        items:
+          $ref: 'tags.yaml#/Tag'
+";
+
+fn issues(spec: &str) -> Vec<(Severity, Option<String>, String)> {
+    let api: ResolvableApi<DefaultSchema> = v2::from_str(spec).expect("parsing spec");
+    v2::validate(&api)
+        .issues
+        .iter()
+        .map(|i| {
+            let d = Diagnostic::from_issue(i);
+            (d.severity, d.pointer, d.message)
+        })
+        .collect()
+}
+
+#[test]
+fn test_all_issues_are_collected() {
+    let issue = |severity, pointer: &str, message: &str| {
+        (severity, Some(pointer.to_owned()), message.to_owned())
+    };
+
+    assert_eq!(
+        issues(SPEC),
+        vec![
+            issue(
+                Severity::Error,
+                "/definitions/Pet/properties/owner",
+                "Reference missing in spec: Owner"
+            ),
+            issue(
+                Severity::Error,
+                "/definitions/Pet/properties/tags/items",
+                "$ref \"tags.yaml#/Tag\" refers to another document (\"tags.yaml\"). Load the \
+                 spec with `from_path` (or `bundle::load`) for resolving such references."
+            ),
+            issue(
+                Severity::Error,
+                "/paths/~1pets~1{id}/get/parameters/1",
+                "Parameter \"filter\" in path \"/pets/{id}\" has specified Some(Object) type, \
+                 but it's invalid for Query parameters"
+            ),
+            issue(
+                Severity::Error,
+                "/paths/~1pets~1{id}/get/parameters/2",
+                "Reference missing in spec: Limit"
+            ),
+            issue(
+                Severity::Error,
+                "/paths/~1pets~1{id}/get/responses/404",
+                "Reference missing in spec: NotFound"
+            ),
+            issue(
+                Severity::Error,
+                "/paths/~1pets~1{id}/get/responses/600",
+                "Invalid status code \"600\" for response (expected a status code or \"default\")"
+            ),
+            issue(
+                Severity::Error,
+                "/paths/~1pets~1{name}",
+                "Path similar to \"/pets/{name}\" already exists."
+            ),
+            issue(
+                Severity::Error,
+                "/paths/~1pets~1{name}",
+                "Parameter(s) {\"name\"} aren't defined for templated path \"/pets/{name}\""
+            ),
+            issue(
+                Severity::Warning,
+                "/paths/~1stores",
+                "Path \"/stores\" doesn't have any operations"
+            ),
+        ]
+    );
+}
+
+#[test]
+fn test_valid_spec() {
+    let spec = "swagger: '2.0'
+info: {title: Pets, version: '1.0'}
+paths:
+  /pets/{id}:
+    parameters:
+      - $ref: '#/parameters/Id'
+    get:
+      responses:
+        '200':
+          schema:
+            $ref: '#/definitions/Pet'
+        default:
+          $ref: '#/responses/Error'
+parameters:
+  Id: {name: id, in: path, type: string, required: true}
+responses:
+  Error:
+    description: Error
+definitions:
+  Pet:
+    type: object
+";
+    let api: ResolvableApi<DefaultSchema> = v2::from_str(spec).expect("parsing spec");
+    let report = v2::validate(&api);
+    assert!(report.issues.is_empty(), "{:?}", report);
+    assert_eq!(report.render("api.yaml", None), "");
+    assert!(api.resolve().is_ok());
+}
+
+#[test]
+fn test_report_rendering() {
+    let spec = "swagger: '2.0'
+info: {title: Pets, version: '1.0'}
+paths:
+  /pets:
+    get:
+      responses:
+        '20':
+          description: OK
+  /stores: {}
+";
+    let api: ResolvableApi<DefaultSchema> = v2::from_str(spec).expect("parsing spec");
+    let report = v2::validate(&api);
+    assert!(report.has_errors());
+    assert_eq!(report.errors().count(), 1);
+    assert_eq!(report.warnings().count(), 1);
+    assert_eq!(
+        report.render("api.yaml", Some(&SourceMap::new(spec))),
+        "error: Invalid status code \"20\" for response (expected a status code or \"default\")
+ --> api.yaml:7:9
+  |
+7 |         '20':
+  |         ^
+  = at /paths/~1pets/get/responses/20
+
+warning: Path \"/stores\" doesn't have any operations
+ --> api.yaml:9:3
+  |
+9 |   /stores: {}
+  |   ^
+  = at /paths/~1stores
+
+error: 1 error and 1 warning found in api.yaml
+"
+    );
+}
+
+#[test]
+fn test_conformance_checks() {
+    let spec = "swagger: '2.0'
+info: {title: Pets, version: '1.0'}
+consumes: [application/json]
+securityDefinitions:
+  oauth:
+    type: oauth2
+    flow: implicit
+    authorizationUrl: https://example.com/auth
+    scopes: {read: Read pets}
+  key: {type: apiKey, in: header, name: X-Key}
+security:
+  - oauth: [read, write]
+paths:
+  /pets/{id}:
+    get:
+      operationId: getPet
+      security:
+        - key: []
+        - token: []
+      parameters:
+        - {name: petId, in: path, type: string, required: true}
+      responses:
+        '200': {description: OK}
+    put:
+      operationId: getPet
+      consumes: [multipart/form-data]
+      parameters:
+        - {name: id, in: path, type: string, required: true}
+        - {name: photo, in: formData, type: file}
+      responses:
+        '200': {description: OK}
+    post:
+      parameters:
+        - {name: id, in: path, type: string, required: true}
+        - {name: photo, in: formData, type: file}
+      responses:
+        '200': {description: OK}
+";
+    let issue = |pointer: &str, message: &str| {
+        (
+            Severity::Error,
+            Some(pointer.to_owned()),
+            message.to_owned(),
+        )
+    };
+
+    assert_eq!(
+        issues(spec),
+        vec![
+            issue(
+                "/security/0/oauth/1",
+                "Scope \"write\" is not defined for security scheme \"oauth\""
+            ),
+            issue(
+                "/paths/~1pets~1{id}/get/parameters/0",
+                "Path parameter \"petId\" is not in the template of path \"/pets/{id}\""
+            ),
+            issue(
+                "/paths/~1pets~1{id}/get/security/1/token",
+                "Security scheme \"token\" is not defined in securityDefinitions"
+            ),
+            issue(
+                "/paths/~1pets~1{id}/put/operationId",
+                "Operation ID \"getPet\" is not unique (it's also used at \
+                 /paths/~1pets~1{id}/get/operationId)"
+            ),
+            issue(
+                "/paths/~1pets~1{id}/post/parameters/1",
+                "File parameter \"photo\" in path \"/pets/{id}\" requires \"multipart/form-data\" \
+                 or \"application/x-www-form-urlencoded\" in consumes"
+            ),
+        ]
+    );
+
+    // File parameters without `consumes` are only warned.
+    let spec = "swagger: '2.0'
+info: {title: Pets, version: '1.0'}
+paths:
+  /pets:
+    post:
+      parameters:
+        - {name: photo, in: formData, type: file}
+      responses:
+        '200': {description: OK}
+";
+    let api: ResolvableApi<DefaultSchema> = v2::from_str(spec).expect("parsing spec");
+    let report = v2::validate(&api);
+    assert!(!report.has_errors());
+    assert_eq!(report.warnings().count(), 1);
+}
+
+#[test]
+fn test_security_scheme_checks() {
+    let spec = "swagger: '2.0'
+info: {title: Pets, version: '1.0'}
+securityDefinitions:
+  basic: {type: basic}
+  key: {type: apiKey, name: X-Key}
+  oauth: {type: oauth2, tokenUrl: https://example.com/token}
+  implicit: {type: oauth2, flow: implicit, tokenUrl: https://example.com/token}
+  password: {type: oauth2, flow: password, authorizationUrl: https://example.com/auth}
+  code:
+    type: oauth2
+    flow: accessCode
+    authorizationUrl: https://example.com/auth
+    tokenUrl: https://example.com/token
+paths:
+  /pets:
+    get:
+      responses:
+        '200': {description: OK}
+";
+    let issue = |pointer: &str, message: &str| {
+        (
+            Severity::Error,
+            Some(pointer.to_owned()),
+            message.to_owned(),
+        )
+    };
+
+    assert_eq!(
+        issues(spec),
+        vec![
+            issue(
+                "/securityDefinitions/implicit",
+                "Security scheme \"implicit\" requires \"authorizationUrl\""
+            ),
+            issue(
+                "/securityDefinitions/key",
+                "Security scheme \"key\" requires \"in\""
+            ),
+            issue(
+                "/securityDefinitions/oauth",
+                "Security scheme \"oauth\" requires \"flow\""
+            ),
+            issue(
+                "/securityDefinitions/password",
+                "Security scheme \"password\" requires \"tokenUrl\""
+            ),
+        ]
+    );
+
+    // Unknown types, locations and flows aren't even parsed.
+    for scheme in &[
+        "{type: bearer}",
+        "{type: apiKey, in: cookie, name: X-Key}",
+        "{type: oauth2, flow: magic, tokenUrl: https://example.com/token}",
+    ] {
+        let spec = format!(
+            "swagger: '2.0'
+info: {{title: Pets, version: '1.0'}}
+securityDefinitions:
+  scheme: {}
+paths: {{}}
+",
+            scheme
+        );
+        assert!(v2::from_str::<DefaultSchema>(&spec).is_err());
+    }
+}
+
+#[test]
+fn test_report_json() {
+    let spec = "swagger: '2.0'
+info: {title: Pets, version: '1.0'}
+paths:
+  /pets:
+    get:
+      responses:
+        '20':
+          description: OK
+  /stores: {}
+";
+    let api: ResolvableApi<DefaultSchema> = v2::from_str(spec).expect("parsing spec");
+    let report = v2::validate(&api);
+    assert_eq!(
+        report.to_json("api.yaml", Some(&SourceMap::new(spec))),
+        serde_json::json!({
+            "spec": "api.yaml",
+            "valid": false,
+            "errors": 1,
+            "warnings": 1,
+            "issues": [{
+                "severity": "error",
+                "message": "Invalid status code \"20\" for response (expected a status code or \"default\")",
+                "pointer": "/paths/~1pets/get/responses/20",
+                "line": 7,
+                "column": 9,
+            }, {
+                "severity": "warning",
+                "message": "Path \"/stores\" doesn't have any operations",
+                "pointer": "/paths/~1stores",
+                "line": 9,
+                "column": 3,
+            }]
+        })
+    );
+
+    // Positions are unknown without the source.
+    let json = report.to_json("https://example.com/api.yaml", None);
+    assert_eq!(json["issues"][0]["line"], serde_json::Value::Null);
+}